@@ -37,6 +37,10 @@ fn get_all_benchmarks(target: &str) -> Vec<(String, String)> {
       "tauri_3mb_transfer".into(),
       format!("../target/{target}/release/bench_files_transfer"),
     ),
+    (
+      "tauri_hello_world_encrypted_assets".into(),
+      format!("../target/{target}/release/bench_encrypted_assets"),
+    ),
   ]
 }
 
@@ -9,7 +9,7 @@ use std::{
   io::{BufWriter, Write},
   path::{Path, PathBuf},
 };
-use tauri_codegen::{context_codegen, ContextData};
+use tauri_codegen::{context_codegen_with_snapshot, ContextData};
 use tauri_utils::config::FrontendDist;
 
 // TODO docs
@@ -20,6 +20,7 @@ pub struct CodegenContext {
   config_path: PathBuf,
   out_file: PathBuf,
   capabilities: Option<Vec<PathBuf>>,
+  snapshot: Option<PathBuf>,
 }
 
 impl Default for CodegenContext {
@@ -28,6 +29,7 @@ impl Default for CodegenContext {
       config_path: PathBuf::from("tauri.conf.json"),
       out_file: PathBuf::from("tauri-build-context.rs"),
       capabilities: None,
+      snapshot: None,
     }
   }
 }
@@ -76,6 +78,15 @@ impl CodegenContext {
     self
   }
 
+  /// Writes a snapshot of the generated context to the given path (relative to the config directory).
+  ///
+  /// See [`crate::Attributes::context_snapshot`] for more information.
+  #[must_use]
+  pub fn snapshot(mut self, path: impl Into<PathBuf>) -> Self {
+    self.snapshot.replace(path.into());
+    self
+  }
+
   /// Generate the code and write it to the output file - returning the path it was saved to.
   ///
   /// Unless you are doing something special with this builder, you don't need to do anything with
@@ -129,17 +140,20 @@ impl CodegenContext {
       }
     }
 
-    let code = context_codegen(ContextData {
-      dev: crate::is_dev(),
-      config,
-      config_parent,
-      // it's very hard to have a build script for unit tests, so assume this is always called from
-      // outside the tauri crate, making the ::tauri root valid.
-      root: quote::quote!(::tauri),
-      capabilities: self.capabilities,
-      assets: None,
-      test: false,
-    })?;
+    let code = context_codegen_with_snapshot(
+      ContextData {
+        dev: crate::is_dev(),
+        config,
+        config_parent,
+        // it's very hard to have a build script for unit tests, so assume this is always called from
+        // outside the tauri crate, making the ::tauri root valid.
+        root: quote::quote!(::tauri),
+        capabilities: self.capabilities,
+        assets: None,
+        test: false,
+      },
+      self.snapshot.as_deref(),
+    )?;
 
     // get the full output file path
     let out = var("OUT_DIR")
@@ -346,6 +346,7 @@ pub struct Attributes {
   codegen: Option<codegen::context::CodegenContext>,
   inlined_plugins: HashMap<&'static str, InlinedPlugin>,
   app_manifest: AppManifest,
+  context_snapshot: Option<PathBuf>,
 }
 
 impl Attributes {
@@ -401,6 +402,22 @@ impl Attributes {
     self
   }
 
+  /// Writes a snapshot of the context generated by [`tauri::generate_context!`] to the given path,
+  /// relative to the package directory.
+  ///
+  /// The snapshot is a stable JSON description of the config digest, the embedded assets with their hashes and sizes,
+  /// the CSP hashes of each HTML file, the resolved ACL and the icons.
+  /// Commit it to review how upgrades and changes affect what ends up in the binary.
+  ///
+  /// The `TAURI_CONTEXT_SNAPSHOT` environment variable can be used instead.
+  ///
+  /// [`tauri::generate_context!`]: https://docs.rs/tauri/latest/tauri/macro.generate_context.html
+  #[must_use]
+  pub fn context_snapshot(mut self, path: impl Into<PathBuf>) -> Self {
+    self.context_snapshot.replace(path.into());
+    self
+  }
+
   #[cfg(feature = "codegen")]
   #[cfg_attr(docsrs, doc(cfg(feature = "codegen")))]
   #[must_use]
@@ -512,6 +529,21 @@ pub fn try_build(attributes: Attributes) -> Result<()> {
   tauri_utils::plugin::save_global_api_scripts_paths(&out_dir, None);
 
   println!("cargo:rustc-env=TAURI_ENV_TARGET_TRIPLE={target_triple}");
+
+  println!("cargo:rerun-if-env-changed=TAURI_CONTEXT_SNAPSHOT");
+  let context_snapshot = attributes
+    .context_snapshot
+    .clone()
+    .or_else(|| env::var_os("TAURI_CONTEXT_SNAPSHOT").map(PathBuf::from))
+    .map(|path| env::current_dir().map(|dir| dir.join(path)))
+    .transpose()
+    .context("failed to resolve the context snapshot path")?;
+  if let Some(context_snapshot) = &context_snapshot {
+    println!(
+      "cargo:rustc-env=TAURI_CONTEXT_SNAPSHOT={}",
+      context_snapshot.display()
+    );
+  }
   // when running codegen in this build script, we need to access the env var directly
   env::set_var("TAURI_ENV_TARGET_TRIPLE", &target_triple);
 
@@ -691,7 +723,10 @@ pub fn try_build(attributes: Attributes) -> Result<()> {
   }
 
   #[cfg(feature = "codegen")]
-  if let Some(codegen) = attributes.codegen {
+  if let Some(mut codegen) = attributes.codegen {
+    if let Some(context_snapshot) = context_snapshot {
+      codegen = codegen.snapshot(context_snapshot);
+    }
     codegen.try_build()?;
   }
 
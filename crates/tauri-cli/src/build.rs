@@ -9,13 +9,14 @@ use crate::{
     self,
     app_paths::Dirs,
     config::{get_config, ConfigMetadata, FrontendDist},
+    message::{self, Artifact, Message, MessageFormat, Phase},
   },
   info::plugins::check_mismatched_packages,
   interface::{rust::get_cargo_target_dir, AppInterface},
   ConfigValue, Result,
 };
 use clap::{ArgAction, Parser};
-use std::env::set_current_dir;
+use std::{env::set_current_dir, path::PathBuf};
 use tauri_utils::config::RunnerConfig;
 use tauri_utils::platform::Target;
 
@@ -31,13 +32,17 @@ pub struct Options {
   /// Builds with the debug flag
   #[clap(short, long)]
   pub debug: bool,
-  /// Target triple to build against.
+  /// Space or comma separated list of target triples to build against, defaults to the host target.
   ///
   /// It must be one of the values outputted by `$rustc --print target-list` or `universal-apple-darwin` for an universal macOS application.
   ///
   /// Note that compiling an universal macOS application requires both `aarch64-apple-darwin` and `x86_64-apple-darwin` targets to be installed.
-  #[clap(short, long)]
-  pub target: Option<String>,
+  ///
+  /// When multiple targets are provided, they must all be for the same platform.
+  /// The frontend is built once, then each target is compiled and bundled into its own `target/<triple>` folder.
+  /// `build.beforeBuildCommand` runs only once, with the `TAURI_ENV_TARGET_TRIPLE` and `TAURI_ENV_ARCH` of the first target.
+  #[clap(short, long = "target", action = ArgAction::Append, num_args(0..), value_delimiter = ',')]
+  pub targets: Vec<String>,
   /// Space or comma separated list of features to activate
   #[clap(short, long, action = ArgAction::Append, num_args(0..), value_delimiter = ',')]
   pub features: Vec<String>,
@@ -79,9 +84,16 @@ pub struct Options {
   /// Skip code signing when bundling the app
   #[clap(long)]
   pub no_sign: bool,
+  /// The format of the messages written to stdout.
+  ///
+  /// With `json`, phase start and end events, warnings and the produced artifacts are written to stdout as JSON objects, one per line.
+  #[clap(long, value_enum, default_value_t = MessageFormat::Human)]
+  pub message_format: MessageFormat,
 }
 
 pub fn command(mut options: Options, verbosity: u8) -> Result<()> {
+  message::set_format(options.message_format);
+
   let dirs = crate::helpers::app_paths::resolve_dirs();
 
   if options.no_sign {
@@ -90,11 +102,7 @@ pub fn command(mut options: Options, verbosity: u8) -> Result<()> {
 
   let ci = options.ci;
 
-  let target = options
-    .target
-    .as_deref()
-    .map(Target::from_triple)
-    .unwrap_or_else(Target::current);
+  let (target, targets) = resolve_targets(std::mem::take(&mut options.targets))?;
 
   let config = get_config(
     target,
@@ -102,39 +110,138 @@ pub fn command(mut options: Options, verbosity: u8) -> Result<()> {
     dirs.tauri,
   )?;
 
-  let mut interface = AppInterface::new(&config, options.target.clone(), dirs.tauri)?;
-
+  // the frontend is built once, with the environment of the first target
+  let interface = AppInterface::new(&config, targets[0].clone(), dirs.tauri)?;
   setup(&interface, &mut options, &config, &dirs, false)?;
+  let mut interface = Some(interface);
 
   if let Some(minimum_system_version) = &config.bundle.macos.minimum_system_version {
     std::env::set_var("MACOSX_DEPLOYMENT_TARGET", minimum_system_version);
   }
 
+  let mut summary = Vec::new();
+  for triple in &targets {
+    let mut interface = match interface.take() {
+      Some(interface) => interface,
+      None => AppInterface::new(&config, triple.clone(), dirs.tauri)?,
+    };
+    let options = Options {
+      targets: triple.iter().cloned().collect(),
+      ..options.clone()
+    };
+    if targets.len() > 1 {
+      log::info!(action = "Building"; "target {}", triple.as_deref().unwrap_or_default());
+    }
+    let built = build_target(&mut interface, options, verbosity, ci, &config, &dirs)?;
+    summary.push((interface.app_settings().target_triple().to_string(), built));
+  }
+
+  if summary.len() > 1 {
+    print_summary(&summary);
+  }
+
+  Ok(())
+}
+
+/// Removes the duplicate `--target` values, checking that they are all for the same platform.
+///
+/// Returns the platform and the targets to build, `None` standing for the host target when none is provided.
+fn resolve_targets(triples: Vec<String>) -> Result<(Target, Vec<Option<String>>)> {
+  let mut targets = Vec::new();
+  for triple in triples {
+    if !targets.contains(&Some(triple.clone())) {
+      targets.push(Some(triple));
+    }
+  }
+  if targets.is_empty() {
+    targets.push(None);
+  }
+
+  let target = targets[0]
+    .as_deref()
+    .map(Target::from_triple)
+    .unwrap_or_else(Target::current);
+  if let Some(triple) = targets
+    .iter()
+    .flatten()
+    .find(|triple| Target::from_triple(triple) != target)
+  {
+    crate::error::bail!(
+      "Cannot build `{triple}` together with `{}`, all targets must be for the same platform.",
+      targets[0].as_deref().unwrap_or_default()
+    );
+  }
+
+  Ok((target, targets))
+}
+
+/// The outputs of a single target.
+struct BuiltTarget {
+  bin_path: PathBuf,
+  bundles: Vec<PathBuf>,
+}
+
+/// Compiles and bundles the target of the given options, the frontend must already be built.
+fn build_target(
+  interface: &mut AppInterface,
+  options: Options,
+  verbosity: u8,
+  ci: bool,
+  config: &ConfigMetadata,
+  dirs: &Dirs,
+) -> Result<BuiltTarget> {
   let app_settings = interface.app_settings();
   let interface_options = options.clone().into();
 
   let out_dir = app_settings.out_dir(&interface_options, dirs.tauri)?;
 
-  let bin_path = interface.build(interface_options, &dirs)?;
+  let target = options.targets.first().map(String::as_str);
+  let bin_path = message::phase(Phase::Compile, target, || {
+    interface.build(interface_options, dirs)
+  })?;
 
-  log::info!(action = "Built"; "application at: {}", tauri_utils::display_path(bin_path));
+  log::info!(action = "Built"; "application at: {}", tauri_utils::display_path(&bin_path));
+  if message::is_json() {
+    message::emit(Message::Artifact(Artifact::new(
+      &bin_path, "binary", target,
+    )?));
+  }
 
   let app_settings = interface.app_settings();
 
-  if !options.no_bundle && (config.bundle.active || options.bundles.is_some()) {
+  let bundles = if !options.no_bundle && (config.bundle.active || options.bundles.is_some()) {
     crate::bundle::bundle(
       &options.into(),
       verbosity,
       ci,
-      &interface,
+      interface,
       &*app_settings,
-      &config,
-      &dirs,
+      config,
+      dirs,
       &out_dir,
-    )?;
-  }
+    )?
+  } else {
+    Vec::new()
+  };
 
-  Ok(())
+  Ok(BuiltTarget { bin_path, bundles })
+}
+
+fn print_summary(summary: &[(String, BuiltTarget)]) {
+  use std::fmt::Write;
+  let mut printable = String::new();
+  for (triple, built) in summary {
+    let _ = writeln!(printable, "    {triple}:");
+    let _ = writeln!(
+      printable,
+      "        {}",
+      tauri_utils::display_path(&built.bin_path)
+    );
+    for bundle in &built.bundles {
+      let _ = writeln!(printable, "        {}", tauri_utils::display_path(bundle));
+    }
+  }
+  log::info!(action = "Finished"; "{} targets:\n{printable}", summary.len());
 }
 
 pub fn setup(
@@ -186,13 +293,15 @@ pub fn setup(
   }
 
   if let Some(before_build) = config.build.before_build_command.clone() {
-    helpers::run_hook(
-      "beforeBuildCommand",
-      before_build,
-      interface,
-      options.debug,
-      dirs.frontend,
-    )?;
+    message::phase(Phase::BeforeBuildCommand, None, || {
+      helpers::run_hook(
+        "beforeBuildCommand",
+        before_build,
+        interface,
+        options.debug,
+        dirs.frontend,
+      )
+    })?;
   }
 
   if let Some(FrontendDist::Directory(web_asset_path)) = &config.build.frontend_dist {
@@ -258,3 +367,48 @@ pub fn setup(
 
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::resolve_targets;
+  use tauri_utils::platform::Target;
+
+  #[test]
+  fn resolve_targets_defaults_to_the_host() {
+    let (target, targets) = resolve_targets(Vec::new()).unwrap();
+    assert_eq!(target, Target::current());
+    assert_eq!(targets, [None]);
+  }
+
+  #[test]
+  fn resolve_targets_removes_duplicates() {
+    let (target, targets) = resolve_targets(vec![
+      "x86_64-pc-windows-msvc".into(),
+      "aarch64-pc-windows-msvc".into(),
+      "x86_64-pc-windows-msvc".into(),
+    ])
+    .unwrap();
+    assert_eq!(target, Target::Windows);
+    assert_eq!(
+      targets,
+      [
+        Some("x86_64-pc-windows-msvc".to_string()),
+        Some("aarch64-pc-windows-msvc".to_string()),
+      ]
+    );
+  }
+
+  #[test]
+  fn resolve_targets_requires_the_same_platform() {
+    let error = resolve_targets(vec![
+      "aarch64-apple-darwin".into(),
+      "universal-apple-darwin".into(),
+      "x86_64-unknown-linux-gnu".into(),
+    ])
+    .unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "Cannot build `x86_64-unknown-linux-gnu` together with `aarch64-apple-darwin`, all targets must be for the same platform."
+    );
+  }
+}
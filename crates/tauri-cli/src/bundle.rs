@@ -18,6 +18,7 @@ use crate::{
     self,
     app_paths::Dirs,
     config::{get_config, ConfigMetadata},
+    message::{self, Artifact, Message, MessageFormat, Phase},
     updater_signature,
   },
   interface::{AppInterface, AppSettings},
@@ -100,24 +101,32 @@ pub struct Options {
   /// are not available or not needed.
   #[clap(long)]
   pub no_sign: bool,
+  /// The format of the messages written to stdout.
+  ///
+  /// With `json`, phase start and end events, warnings and the produced artifacts are written to stdout as JSON objects, one per line.
+  #[clap(long, value_enum, default_value_t = MessageFormat::Human)]
+  pub message_format: MessageFormat,
 }
 
 impl From<crate::build::Options> for Options {
   fn from(value: crate::build::Options) -> Self {
     Self {
       bundles: value.bundles,
-      target: value.target,
+      target: value.targets.into_iter().next(),
       features: value.features,
       debug: value.debug,
       ci: value.ci,
       config: value.config,
       skip_stapling: value.skip_stapling,
       no_sign: value.no_sign,
+      message_format: value.message_format,
     }
   }
 }
 
 pub fn command(options: Options, verbosity: u8) -> crate::Result<()> {
+  message::set_format(options.message_format);
+
   let dirs = crate::helpers::app_paths::resolve_dirs();
 
   let ci = options.ci;
@@ -156,9 +165,12 @@ pub fn command(options: Options, verbosity: u8) -> crate::Result<()> {
     &config,
     &dirs,
     &out_dir,
-  )
+  )?;
+
+  Ok(())
 }
 
+/// Bundles the application, returning the paths of the generated bundles.
 #[allow(clippy::too_many_arguments)]
 pub fn bundle<A: AppSettings>(
   options: &Options,
@@ -169,7 +181,7 @@ pub fn bundle<A: AppSettings>(
   config: &ConfigMetadata,
   dirs: &Dirs,
   out_dir: &Path,
-) -> crate::Result<()> {
+) -> crate::Result<Vec<PathBuf>> {
   let package_types: Vec<PackageType> = if let Some(bundles) = &options.bundles {
     bundles.iter().map(|bundle| bundle.0).collect::<Vec<_>>()
   } else {
@@ -183,19 +195,23 @@ pub fn bundle<A: AppSettings>(
   };
 
   if package_types.is_empty() {
-    return Ok(());
+    return Ok(Vec::new());
   }
 
+  let target = options.target.as_deref();
+
   // if we have a package to bundle, let's run the `before_bundle_command`.
   if !package_types.is_empty() {
     if let Some(before_bundle) = config.build.before_bundle_command.clone() {
-      helpers::run_hook(
-        "beforeBundleCommand",
-        before_bundle,
-        interface,
-        options.debug,
-        dirs.frontend,
-      )?;
+      message::phase(Phase::BeforeBundleCommand, target, || {
+        helpers::run_hook(
+          "beforeBundleCommand",
+          before_bundle,
+          interface,
+          options.debug,
+          dirs.frontend,
+        )
+      })?;
     }
   }
 
@@ -216,21 +232,42 @@ pub fn bundle<A: AppSettings>(
     _ => log::Level::Trace,
   });
 
-  let bundles = tauri_bundler::bundle_project(&settings).map_err(Box::new)?;
-
-  sign_updaters(settings, bundles, ci)?;
+  let (bundles, signatures) = message::phase(Phase::Bundle, target, || {
+    let bundles = tauri_bundler::bundle_project(&settings).map_err(Box::new)?;
+    let signatures = sign_updaters(settings, &bundles, ci)?;
+    Ok((bundles, signatures))
+  })?;
+
+  if message::is_json() {
+    for bundle in &bundles {
+      for path in &bundle.bundle_paths {
+        let mut artifact = Artifact::new(path, bundle.package_type.short_name(), target)?;
+        artifact.signature = signatures
+          .iter()
+          .find(|(signed, _)| signed == path)
+          .map(|(_, signature)| signature.clone());
+        message::emit(Message::Artifact(artifact));
+      }
+    }
+  }
 
-  Ok(())
+  Ok(
+    bundles
+      .into_iter()
+      .flat_map(|bundle| bundle.bundle_paths)
+      .collect(),
+  )
 }
 
+/// Signs the updater bundles, returning the signed paths with their signature paths.
 fn sign_updaters(
   settings: tauri_bundler::Settings,
-  bundles: Vec<tauri_bundler::Bundle>,
+  bundles: &[tauri_bundler::Bundle],
   ci: bool,
-) -> crate::Result<()> {
+) -> crate::Result<Vec<(PathBuf, PathBuf)>> {
   let Some(update_settings) = settings.updater() else {
     // Updater not enabled
-    return Ok(());
+    return Ok(Vec::new());
   };
 
   let update_enabled_bundles: Vec<&tauri_bundler::Bundle> = bundles
@@ -249,12 +286,12 @@ fn sign_updaters(
     .collect();
 
   if update_enabled_bundles.is_empty() {
-    return Ok(());
+    return Ok(Vec::new());
   }
 
   if settings.no_sign() {
     log::warn!("Updater signing is skipped due to --no-sign flag.");
-    return Ok(());
+    return Ok(Vec::new());
   }
 
   // get the public key
@@ -301,13 +338,18 @@ fn sign_updaters(
       if signature.keynum() != public_key.keynum() {
         log::warn!("The updater secret key from `TAURI_SIGNING_PRIVATE_KEY` does not match the public key from `plugins > updater > pubkey`. If you are not rotating keys, this means your configuration is wrong and won't be accepted at runtime when performing update.");
       }
-      signed_paths.push(signature_path);
+      signed_paths.push((path.clone(), signature_path));
     }
   }
 
-  print_signed_updater_archive(&signed_paths)?;
+  print_signed_updater_archive(
+    &signed_paths
+      .iter()
+      .map(|(_, signature)| signature.clone())
+      .collect::<Vec<_>>(),
+  )?;
 
-  Ok(())
+  Ok(signed_paths)
 }
 
 fn print_signed_updater_archive(output_paths: &[PathBuf]) -> crate::Result<()> {
@@ -91,6 +91,16 @@ pub struct Options {
   /// Specify port for the built-in dev server for static files. Defaults to 1430.
   #[clap(long, env = "TAURI_CLI_PORT")]
   pub port: Option<u16>,
+  /// Proxy `build.devUrl` through the built-in dev server, adding the `app.security.headers`
+  /// and Content-Security-Policy the production `tauri://` protocol sets.
+  ///
+  /// The headers are read when the dev server starts.
+  #[clap(long, conflicts_with = "no_dev_server")]
+  pub dev_server_proxy: bool,
+  /// Serve the built-in dev server over HTTPS, using a self-signed certificate
+  /// generated in the local data directory.
+  #[clap(long, conflicts_with = "no_dev_server")]
+  pub dev_server_https: bool,
 
   #[clap(skip)]
   pub host: Option<IpAddr>,
@@ -234,35 +244,62 @@ pub fn setup(
 
   let mut dev_url = config.build.dev_url.clone();
   let frontend_dist = config.build.frontend_dist.clone();
-  if !options.no_dev_server && dev_url.is_none() {
-    if let Some(FrontendDist::Directory(path)) = &frontend_dist {
-      if path.exists() {
+  let source = if options.no_dev_server {
+    None
+  } else if let Some(url) = &dev_url {
+    options
+      .dev_server_proxy
+      .then(|| builtin_dev_server::Source::Proxy(url.clone()))
+  } else {
+    if options.dev_server_proxy {
+      crate::error::bail!("--dev-server-proxy requires `build.devUrl` to be set");
+    }
+    match &frontend_dist {
+      Some(FrontendDist::Directory(path)) if path.exists() => {
         let path = path
           .canonicalize()
           .fs_context("failed to canonicalize path", path.to_path_buf())?;
-
-        let ip = options
-          .host
-          .unwrap_or_else(|| Ipv4Addr::new(127, 0, 0, 1).into());
-
-        let server_url = builtin_dev_server::start(path, ip, options.port)
-          .context("failed to start builtin dev server")?;
-        let server_url = format!("http://{server_url}");
-        dev_url = Some(server_url.parse().unwrap());
-
-        options.config.push(crate::ConfigValue(serde_json::json!({
-          "build": {
-            "devUrl": server_url
-          }
-        })));
-
-        reload_config(
-          config,
-          &options.config.iter().map(|c| &c.0).collect::<Vec<_>>(),
-          dirs.tauri,
-        )?;
+        Some(builtin_dev_server::Source::Directory(path))
       }
+      _ => None,
+    }
+  };
+
+  if let Some(source) = source {
+    let is_proxy = matches!(source, builtin_dev_server::Source::Proxy(_));
+
+    let ip = options
+      .host
+      .unwrap_or_else(|| Ipv4Addr::new(127, 0, 0, 1).into());
+
+    let isolation_schema_path = interface
+      .app_settings()
+      .dev_isolation_schema_path(&options.clone().into(), dirs.tauri)?;
+    let server_url = builtin_dev_server::start(
+      source,
+      ip,
+      options.port,
+      options.dev_server_https,
+      config,
+      isolation_schema_path,
+    )
+    .context("failed to start builtin dev server")?;
+    // when proxying, keep waiting for the frontend dev server itself
+    if !is_proxy {
+      dev_url = Some(server_url.clone());
     }
+
+    options.config.push(crate::ConfigValue(serde_json::json!({
+      "build": {
+        "devUrl": server_url
+      }
+    })));
+
+    reload_config(
+      config,
+      &options.config.iter().map(|c| &c.0).collect::<Vec<_>>(),
+      dirs.tauri,
+    )?;
   }
 
   if !options.no_dev_server_wait {
@@ -4,36 +4,62 @@
 
 use axum::{
   extract::{ws, State, WebSocketUpgrade},
-  http::{header, StatusCode, Uri},
+  http::{header, HeaderMap, HeaderValue, StatusCode, Uri},
   response::{IntoResponse, Response},
 };
 use html5ever::{namespace_url, ns, LocalName, QualName};
 use kuchiki::{traits::TendrilSink, NodeRef};
 use std::{
   net::{IpAddr, SocketAddr},
+  ops::Range,
   path::{Path, PathBuf},
   thread,
   time::Duration,
 };
-use tauri_utils::mime_type::MimeType;
+use tauri_utils::{config::Config, mime_type::MimeType};
 use tokio::sync::broadcast::{channel, Sender};
+use url::Url;
 
-use crate::error::ErrorExt;
+use crate::error::{Context, ErrorExt};
+
+mod proxy;
+mod security;
+mod tls;
+
+use security::Security;
 
 const RELOAD_SCRIPT: &str = include_str!("./auto-reload.js");
 
+/// What the built-in dev server serves.
+///
+/// Both add the `app.security` headers and CSP the production `tauri://` protocol adds.
+pub enum Source {
+  /// The static files of a directory, reloading the page when they change.
+  Directory(PathBuf),
+  /// The frontend dev server at the given URL.
+  Proxy(Url),
+}
+
 #[derive(Clone)]
 struct ServerState {
   dir: PathBuf,
-  address: SocketAddr,
+  /// The URL of the reload websocket.
+  reload_url: String,
   tx: Sender<()>,
+  security: Security,
 }
 
-pub fn start<P: AsRef<Path>>(dir: P, ip: IpAddr, port: Option<u16>) -> crate::Result<SocketAddr> {
-  let dir = dir.as_ref();
-  let dir =
-    dunce::canonicalize(dir).fs_context("failed to canonicalize path", dir.to_path_buf())?;
-
+/// Starts the built-in dev server, returning its URL.
+///
+/// `isolation_schema_path` is the file the build script writes the isolation schema of the app to.
+pub fn start(
+  source: Source,
+  ip: IpAddr,
+  port: Option<u16>,
+  https: bool,
+  config: &Config,
+  isolation_schema_path: PathBuf,
+) -> crate::Result<Url> {
   // bind port and tcp listener
   let auto_port = port.is_none();
   let mut port = port.unwrap_or(1430);
@@ -51,64 +77,179 @@ pub fn start<P: AsRef<Path>>(dir: P, ip: IpAddr, port: Option<u16>) -> crate::Re
     port += 1;
   };
 
-  let (tx, _) = channel(1);
+  let certificate = if https {
+    Some(tls::certificate(ip)?)
+  } else {
+    None
+  };
+  let scheme = if https { "https" } else { "http" };
+  let url = Url::parse(&format!("{scheme}://{address}")).context("invalid dev server URL")?;
 
-  // watch dir for changes
-  let tx_c = tx.clone();
-  watch(dir.clone(), move || {
-    let _ = tx_c.send(());
-  });
+  let security = Security::new(config, Some(isolation_schema_path));
+  let router = match source {
+    Source::Directory(dir) => {
+      let dir = dunce::canonicalize(&dir).fs_context("failed to canonicalize path", dir.clone())?;
+
+      let (tx, _) = channel(1);
 
-  let state = ServerState { dir, tx, address };
+      // watch dir for changes
+      let tx_c = tx.clone();
+      watch(dir.clone(), move || {
+        let _ = tx_c.send(());
+      });
+
+      let ws_scheme = if https { "wss" } else { "ws" };
+      let state = ServerState {
+        dir,
+        reload_url: format!("{ws_scheme}://{address}/__tauri_cli"),
+        tx,
+        security,
+      };
+      axum::Router::new()
+        .fallback(handler)
+        .route("/__tauri_cli", axum::routing::get(ws_handler))
+        .with_state(state)
+    }
+    Source::Proxy(url) => axum::Router::new()
+      .fallback(proxy::handler)
+      .with_state(proxy::ProxyState::new(url, security)),
+  };
 
   // start router thread
   std::thread::spawn(move || {
     tokio::runtime::Builder::new_current_thread()
-      .enable_io()
+      .enable_all()
       .build()
       .expect("failed to start tokio runtime for builtin dev server")
       .block_on(async move {
-        let router = axum::Router::new()
-          .fallback(handler)
-          .route("/__tauri_cli", axum::routing::get(ws_handler))
-          .with_state(state);
-
-        axum::serve(tokio::net::TcpListener::from_std(tcp_listener)?, router).await
+        match certificate {
+          Some(certificate) => {
+            let _ = rustls::crypto::ring::default_provider().install_default();
+            let tls_config = axum_server::tls_rustls::RustlsConfig::from_pem_file(
+              certificate.cert,
+              certificate.key,
+            )
+            .await?;
+            axum_server::from_tcp_rustls(tcp_listener, tls_config)
+              .serve(router.into_make_service())
+              .await
+          }
+          None => axum::serve(tokio::net::TcpListener::from_std(tcp_listener)?, router).await,
+        }
       })
       .expect("builtin server errored");
   });
 
-  Ok(address)
+  Ok(url)
 }
 
-async fn handler(uri: Uri, state: State<ServerState>) -> impl IntoResponse {
+async fn handler(uri: Uri, headers: HeaderMap, state: State<ServerState>) -> Response {
   // Frontend files should not contain query parameters. This seems to be how Vite handles it.
-  let uri = uri.path();
+  let path = percent_encoding::percent_decode_str(uri.path()).decode_utf8_lossy();
+  let path = path.trim_start_matches('/').trim_end_matches('/');
+  let path = if path.is_empty() { "index.html" } else { path };
 
-  let uri = if uri == "/" {
-    uri
-  } else {
-    uri.strip_prefix('/').unwrap_or(uri)
+  let mut response_headers = HeaderMap::new();
+  state.security.add_headers(&mut response_headers);
+
+  // same lookup as the production `tauri://` protocol, falling back to `index.html` for client-side routes
+  let asset = [
+    path.to_string(),
+    format!("{path}.html"),
+    format!("{path}/index.html"),
+    "index.html".to_string(),
+  ]
+  .into_iter()
+  .find_map(|asset_path| {
+    fs_read_scoped(state.dir.join(&asset_path), &state.dir)
+      .ok()
+      .map(|bytes| (asset_path, bytes))
+  });
+
+  let Some((asset_path, mut bytes)) = asset else {
+    response_headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+    return (StatusCode::NOT_FOUND, response_headers).into_response();
   };
 
-  let bytes = fs_read_scoped(state.dir.join(uri), &state.dir)
-    .or_else(|_| fs_read_scoped(state.dir.join(format!("{}.html", &uri)), &state.dir))
-    .or_else(|_| fs_read_scoped(state.dir.join(format!("{}/index.html", &uri)), &state.dir))
-    .or_else(|_| std::fs::read(state.dir.join("index.html")));
+  let mime_type = MimeType::parse(&bytes, path);
+  if asset_path.ends_with(".html") {
+    bytes = inject_reload_script(bytes, &state.reload_url);
+    let (html, csp) = state.security.process_html(bytes, &[&state.reload_url]);
+    bytes = html;
+    if let Some(csp) = csp {
+      response_headers.insert(header::CONTENT_SECURITY_POLICY, csp);
+    }
+  }
 
-  match bytes {
-    Ok(mut bytes) => {
-      let mime_type = MimeType::parse_with_fallback(&bytes, uri, MimeType::OctetStream);
-      if mime_type == MimeType::Html.to_string() {
-        bytes = inject_address(bytes, &state.address);
+  if let Ok(mime_type) = HeaderValue::from_str(&mime_type) {
+    response_headers.insert(header::CONTENT_TYPE, mime_type);
+  }
+  response_headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+
+  let len = bytes.len();
+  match range(&headers, len) {
+    ByteRange::Full => (StatusCode::OK, response_headers, bytes).into_response(),
+    ByteRange::Partial(range) => {
+      if let Ok(content_range) =
+        HeaderValue::from_str(&format!("bytes {}-{}/{len}", range.start, range.end - 1))
+      {
+        response_headers.insert(header::CONTENT_RANGE, content_range);
+      }
+      (
+        StatusCode::PARTIAL_CONTENT,
+        response_headers,
+        bytes[range].to_vec(),
+      )
+        .into_response()
+    }
+    ByteRange::Unsatisfiable => {
+      if let Ok(content_range) = HeaderValue::from_str(&format!("bytes */{len}")) {
+        response_headers.insert(header::CONTENT_RANGE, content_range);
       }
-      (StatusCode::OK, [(header::CONTENT_TYPE, mime_type)], bytes)
+      (StatusCode::RANGE_NOT_SATISFIABLE, response_headers).into_response()
     }
-    Err(_) => (
-      StatusCode::NOT_FOUND,
-      [(header::CONTENT_TYPE, "text/plain".into())],
-      vec![],
-    ),
+  }
+}
+
+/// The part of a file requested with the `Range` header.
+enum ByteRange {
+  Full,
+  Partial(Range<usize>),
+  Unsatisfiable,
+}
+
+/// Parses a single `bytes` range, the whole file is served for multiple or malformed ranges.
+fn range(headers: &HeaderMap, len: usize) -> ByteRange {
+  let Some(range) = headers
+    .get(header::RANGE)
+    .and_then(|range| range.to_str().ok())
+    .and_then(|range| range.trim().strip_prefix("bytes="))
+  else {
+    return ByteRange::Full;
+  };
+  if range.contains(',') {
+    return ByteRange::Full;
+  }
+  let Some((start, end)) = range.split_once('-') else {
+    return ByteRange::Full;
+  };
+
+  let range = match (start.trim(), end.trim()) {
+    ("", suffix) => suffix
+      .parse::<usize>()
+      .ok()
+      .map(|suffix| len.saturating_sub(suffix)..len),
+    (start, "") => start.parse::<usize>().ok().map(|start| start..len),
+    (start, end) => match (start.parse::<usize>(), end.parse::<usize>()) {
+      (Ok(start), Ok(end)) if start <= end => Some(start..len.min(end.saturating_add(1))),
+      _ => None,
+    },
+  };
+
+  match range {
+    Some(range) if range.start < range.end => ByteRange::Partial(range),
+    Some(_) => ByteRange::Unsatisfiable,
+    None => ByteRange::Full,
   }
 }
 
@@ -127,7 +268,7 @@ async fn ws_handler(ws: WebSocketUpgrade, state: State<ServerState>) -> Response
   })
 }
 
-fn inject_address(html_bytes: Vec<u8>, address: &SocketAddr) -> Vec<u8> {
+fn inject_reload_script(html_bytes: Vec<u8>, reload_url: &str) -> Vec<u8> {
   fn with_html_head<F: FnOnce(&NodeRef)>(document: &mut NodeRef, f: F) {
     if let Ok(ref node) = document.select_first("head") {
       f(node.as_node())
@@ -145,7 +286,7 @@ fn inject_address(html_bytes: Vec<u8>, address: &SocketAddr) -> Vec<u8> {
     .one(String::from_utf8_lossy(&html_bytes).into_owned())
     .document_node;
   with_html_head(&mut document, |head| {
-    let script = RELOAD_SCRIPT.replace("{{reload_url}}", &format!("ws://{address}/__tauri_cli"));
+    let script = RELOAD_SCRIPT.replace("{{reload_url}}", reload_url);
     let script_el = NodeRef::new_element(QualName::new(None, ns!(html), "script".into()), None);
     script_el.append(NodeRef::new_text(script));
     head.prepend(script_el);
@@ -185,3 +326,30 @@ fn watch<F: Fn() + Send + 'static>(dir: PathBuf, handler: F) {
     }
   });
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse(value: &str, len: usize) -> Option<Range<usize>> {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::RANGE, HeaderValue::from_str(value).unwrap());
+    match range(&headers, len) {
+      ByteRange::Full => None,
+      ByteRange::Partial(range) => Some(range),
+      ByteRange::Unsatisfiable => Some(0..0),
+    }
+  }
+
+  #[test]
+  fn byte_range() {
+    assert_eq!(parse("bytes=0-9", 100), Some(0..10));
+    assert_eq!(parse("bytes=90-200", 100), Some(90..100));
+    assert_eq!(parse("bytes=50-", 100), Some(50..100));
+    assert_eq!(parse("bytes=-10", 100), Some(90..100));
+    assert_eq!(parse("bytes=100-", 100), Some(0..0));
+    assert_eq!(parse("bytes=0-1,5-6", 100), None);
+    assert_eq!(parse("bytes=9-0", 100), None);
+    assert_eq!(parse("items=0-9", 100), None);
+  }
+}
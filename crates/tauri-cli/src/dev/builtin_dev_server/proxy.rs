@@ -0,0 +1,571 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Fronts the frontend dev server, adding the headers and CSP of the production `tauri://` protocol.
+//!
+//! Request and response bodies are streamed, except for HTML documents which are read whole to process their CSP,
+//! and WebSocket connections such as HMR clients are proxied to the frontend dev server as well.
+
+use std::{io::Read, sync::Arc};
+
+use axum::{
+  body::{Body, Bytes},
+  extract::{
+    ws::{self, WebSocket},
+    FromRequestParts, Request, State, WebSocketUpgrade,
+  },
+  http::{header, request::Parts, HeaderMap, HeaderName, HeaderValue, StatusCode},
+  response::{IntoResponse, Response},
+};
+use futures_util::{SinkExt, StreamExt};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::{self, client::IntoClientRequest};
+use url::Url;
+
+use super::security::Security;
+
+/// How many chunks of a body are buffered while the other side is not reading them.
+const BODY_CHUNKS: usize = 8;
+/// The size of the chunks read from the response bodies of the frontend dev server.
+const CHUNK_SIZE: usize = 16 * 1024;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Whether the header only applies to a single connection, or no longer matches the body once it is read.
+fn is_skipped_header(name: &HeaderName) -> bool {
+  [
+    header::CONNECTION,
+    header::HOST,
+    header::ACCEPT_ENCODING,
+    header::CONTENT_ENCODING,
+    header::CONTENT_LENGTH,
+    header::TRANSFER_ENCODING,
+    header::UPGRADE,
+    header::TE,
+    header::TRAILER,
+    header::PROXY_AUTHENTICATE,
+    header::PROXY_AUTHORIZATION,
+  ]
+  .contains(name)
+}
+
+#[derive(Clone)]
+pub struct ProxyState {
+  url: Arc<Url>,
+  agent: ureq::Agent,
+  security: Arc<Security>,
+}
+
+impl ProxyState {
+  pub fn new(url: Url, security: Security) -> Self {
+    let agent = ureq::Agent::config_builder()
+      .http_status_as_error(false)
+      .max_redirects(0)
+      .build()
+      .into();
+    Self {
+      url: Arc::new(url),
+      agent,
+      security: Arc::new(security),
+    }
+  }
+
+  /// The URL of the frontend dev server for a request path.
+  fn url(&self, parts: &Parts) -> String {
+    let path = parts
+      .uri
+      .path_and_query()
+      .map(|p| p.as_str())
+      .unwrap_or("/");
+    format!("{}{path}", self.url.as_str().trim_end_matches('/'))
+  }
+}
+
+pub async fn handler(State(state): State<ProxyState>, request: Request) -> Response {
+  let (mut parts, body) = request.into_parts();
+
+  if is_websocket(&parts.headers) {
+    return match WebSocketUpgrade::from_request_parts(&mut parts, &state).await {
+      Ok(upgrade) => websocket(state, parts, upgrade).await,
+      Err(rejection) => rejection.into_response(),
+    };
+  }
+
+  let body = has_body(&parts.headers).then(|| BodyReader::new(body));
+  let state_ = state.clone();
+  let upstream = tokio::task::spawn_blocking(move || send(&state_, parts, body)).await;
+  let result = match upstream {
+    Ok(Ok(upstream)) => respond(&state, upstream).await,
+    Ok(Err(e)) => Err(e),
+    Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+  };
+  match result {
+    Ok(response) => response,
+    Err(e) => {
+      log::error!("Failed to proxy request to {}: {e}", state.url);
+      error(StatusCode::BAD_GATEWAY, e.to_string())
+    }
+  }
+}
+
+/// Whether the request has a body to forward, requests without one are sent without a body.
+fn has_body(headers: &HeaderMap) -> bool {
+  headers.contains_key(header::TRANSFER_ENCODING)
+    || headers
+      .get(header::CONTENT_LENGTH)
+      .and_then(|len| len.to_str().ok())
+      .and_then(|len| len.parse::<u64>().ok())
+      .is_some_and(|len| len > 0)
+}
+
+/// Sends the request to the frontend dev server.
+fn send(
+  state: &ProxyState,
+  parts: Parts,
+  body: Option<BodyReader>,
+) -> Result<ureq::http::Response<ureq::Body>, BoxError> {
+  let mut request = ureq::http::Request::builder()
+    .method(parts.method.clone())
+    .uri(state.url(&parts));
+  for (name, value) in parts
+    .headers
+    .iter()
+    .filter(|(name, _)| !is_skipped_header(name))
+  {
+    request = request.header(name, value);
+  }
+
+  let response = match body {
+    Some(body) => {
+      // lets the body be sent with its length instead of chunked
+      if let Some(len) = parts.headers.get(header::CONTENT_LENGTH) {
+        request = request.header(header::CONTENT_LENGTH, len);
+      }
+      state
+        .agent
+        .run(request.body(ureq::SendBody::from_owned_reader(body))?)?
+    }
+    None => state.agent.run(request.body(())?)?,
+  };
+  Ok(response)
+}
+
+/// Builds the response from the one of the frontend dev server.
+async fn respond(
+  state: &ProxyState,
+  upstream: ureq::http::Response<ureq::Body>,
+) -> Result<Response, BoxError> {
+  let (upstream, mut body) = upstream.into_parts();
+  let mut headers = HeaderMap::new();
+  for (name, value) in upstream
+    .headers
+    .iter()
+    .filter(|(name, _)| !is_skipped_header(name))
+  {
+    headers.append(name, value.clone());
+  }
+  rewrite_location(&state.url, &mut headers);
+  state.security.add_headers(&mut headers);
+
+  let is_html = headers
+    .get(header::CONTENT_TYPE)
+    .and_then(|value| value.to_str().ok())
+    .is_some_and(|value| value.starts_with("text/html"));
+  let body = if is_html {
+    let html =
+      tokio::task::spawn_blocking(move || body.with_config().limit(u64::MAX).read_to_vec())
+        .await??;
+    let (html, csp) = state.security.process_html(html, &[]);
+    if let Some(csp) = csp {
+      headers.insert(header::CONTENT_SECURITY_POLICY, csp);
+    }
+    Body::from(html)
+  } else {
+    stream_body(body)
+  };
+
+  let mut response = Response::new(body);
+  *response.status_mut() = upstream.status;
+  *response.headers_mut() = headers;
+  Ok(response)
+}
+
+/// Streams a response body of the frontend dev server as it is received, e.g. for server-sent events.
+fn stream_body(body: ureq::Body) -> Body {
+  let (tx, rx) = mpsc::channel::<std::io::Result<Bytes>>(BODY_CHUNKS);
+  tokio::task::spawn_blocking(move || {
+    let mut reader = body.into_reader();
+    let mut buf = vec![0; CHUNK_SIZE];
+    loop {
+      let chunk = match reader.read(&mut buf) {
+        Ok(0) => break,
+        Ok(n) => Ok(Bytes::copy_from_slice(&buf[..n])),
+        Err(e) => Err(e),
+      };
+      let failed = chunk.is_err();
+      // stop reading once the client is gone
+      if tx.blocking_send(chunk).is_err() || failed {
+        break;
+      }
+    }
+  });
+  Body::from_stream(futures_util::stream::unfold(rx, |mut rx| async move {
+    rx.recv().await.map(|chunk| (chunk, rx))
+  }))
+}
+
+/// Reads a request body as it is received, so it can be streamed to the frontend dev server.
+struct BodyReader {
+  rx: mpsc::Receiver<std::io::Result<Bytes>>,
+  chunk: Bytes,
+}
+
+impl BodyReader {
+  fn new(body: Body) -> Self {
+    let (tx, rx) = mpsc::channel(BODY_CHUNKS);
+    tokio::spawn(async move {
+      let mut stream = body.into_data_stream();
+      while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(std::io::Error::other);
+        let failed = chunk.is_err();
+        if tx.send(chunk).await.is_err() || failed {
+          break;
+        }
+      }
+    });
+    Self {
+      rx,
+      chunk: Bytes::new(),
+    }
+  }
+}
+
+impl Read for BodyReader {
+  fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+    while self.chunk.is_empty() {
+      match self.rx.blocking_recv() {
+        Some(chunk) => self.chunk = chunk?,
+        None => return Ok(0),
+      }
+    }
+    let len = buf.len().min(self.chunk.len());
+    buf[..len].copy_from_slice(&self.chunk[..len]);
+    self.chunk = self.chunk.slice(len..);
+    Ok(len)
+  }
+}
+
+/// Whether the request opens a WebSocket connection.
+fn is_websocket(headers: &HeaderMap) -> bool {
+  headers
+    .get(header::UPGRADE)
+    .and_then(|upgrade| upgrade.to_str().ok())
+    .is_some_and(|upgrade| upgrade.eq_ignore_ascii_case("websocket"))
+}
+
+/// Connects to the frontend dev server with the same path and subprotocols, then relays the messages both ways.
+async fn websocket(state: ProxyState, parts: Parts, upgrade: WebSocketUpgrade) -> Response {
+  let scheme = if state.url.scheme() == "https" {
+    "wss"
+  } else {
+    "ws"
+  };
+  let mut url = state.url(&parts);
+  url.replace_range(..state.url.scheme().len(), scheme);
+
+  let mut request = match url.as_str().into_client_request() {
+    Ok(request) => request,
+    Err(e) => return error(StatusCode::BAD_GATEWAY, e.to_string()),
+  };
+  if let Some(protocols) = parts.headers.get(header::SEC_WEBSOCKET_PROTOCOL) {
+    request
+      .headers_mut()
+      .insert(header::SEC_WEBSOCKET_PROTOCOL, protocols.clone());
+  }
+
+  let (upstream, response) = match tokio_tungstenite::connect_async(request).await {
+    Ok(connected) => connected,
+    Err(e) => {
+      log::error!("Failed to proxy WebSocket connection to {url}: {e}");
+      return error(StatusCode::BAD_GATEWAY, e.to_string());
+    }
+  };
+
+  // the subprotocol picked by the frontend dev server
+  let protocol = response
+    .headers()
+    .get(header::SEC_WEBSOCKET_PROTOCOL)
+    .and_then(|protocol| protocol.to_str().ok())
+    .map(ToString::to_string);
+  let upgrade = match protocol {
+    Some(protocol) => upgrade.protocols([protocol]),
+    None => upgrade,
+  };
+
+  upgrade.on_upgrade(move |client: WebSocket| async move {
+    let (mut client_tx, mut client_rx) = client.split();
+    let (mut upstream_tx, mut upstream_rx) = upstream.split();
+
+    let to_upstream = async {
+      while let Some(Ok(message)) = client_rx.next().await {
+        if upstream_tx.send(to_tungstenite(message)).await.is_err() {
+          break;
+        }
+      }
+      let _ = upstream_tx.close().await;
+    };
+    let to_client = async {
+      while let Some(Ok(message)) = upstream_rx.next().await {
+        let Some(message) = from_tungstenite(message) else {
+          continue;
+        };
+        if client_tx.send(message).await.is_err() {
+          break;
+        }
+      }
+      let _ = client_tx.close().await;
+    };
+
+    tokio::select! {
+      _ = to_upstream => {}
+      _ = to_client => {}
+    }
+  })
+}
+
+fn to_tungstenite(message: ws::Message) -> tungstenite::Message {
+  match message {
+    ws::Message::Text(text) => tungstenite::Message::text(text.as_str()),
+    ws::Message::Binary(data) => tungstenite::Message::Binary(data),
+    ws::Message::Ping(data) => tungstenite::Message::Ping(data),
+    ws::Message::Pong(data) => tungstenite::Message::Pong(data),
+    ws::Message::Close(frame) => {
+      tungstenite::Message::Close(frame.map(|frame| tungstenite::protocol::CloseFrame {
+        code: frame.code.into(),
+        reason: frame.reason.as_str().into(),
+      }))
+    }
+  }
+}
+
+fn from_tungstenite(message: tungstenite::Message) -> Option<ws::Message> {
+  let message = match message {
+    tungstenite::Message::Text(text) => ws::Message::Text(text.as_str().into()),
+    tungstenite::Message::Binary(data) => ws::Message::Binary(data),
+    tungstenite::Message::Ping(data) => ws::Message::Ping(data),
+    tungstenite::Message::Pong(data) => ws::Message::Pong(data),
+    tungstenite::Message::Close(frame) => ws::Message::Close(frame.map(|frame| ws::CloseFrame {
+      code: frame.code.into(),
+      reason: frame.reason.as_str().into(),
+    })),
+    // raw frames are only produced when writing
+    tungstenite::Message::Frame(_) => return None,
+  };
+  Some(message)
+}
+
+/// Makes redirects to the frontend dev server go through the proxy.
+fn rewrite_location(url: &Url, headers: &mut HeaderMap) {
+  let origin = url.origin().ascii_serialization();
+  let location = headers
+    .get(header::LOCATION)
+    .and_then(|location| location.to_str().ok())
+    .and_then(|location| location.strip_prefix(&origin))
+    .map(|path| if path.is_empty() { "/" } else { path })
+    .and_then(|path| HeaderValue::from_str(path).ok());
+  if let Some(location) = location {
+    headers.insert(header::LOCATION, location);
+  }
+}
+
+fn error(status: StatusCode, message: String) -> Response {
+  (status, [(header::CONTENT_TYPE, "text/plain")], message).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use axum::{
+    response::Html,
+    routing::{get, post},
+    Router,
+  };
+  use std::{sync::Mutex, time::Duration};
+  use tauri_utils::config::Config;
+  use tokio::{net::TcpListener, sync::oneshot};
+
+  async fn serve(router: Router) -> Url {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap())
+      .parse()
+      .unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    url
+  }
+
+  /// Serves the frontend dev server behind the proxy, returning the URLs of both.
+  async fn proxy(frontend: Router) -> (Url, Url) {
+    let frontend = serve(frontend).await;
+    let state = ProxyState::new(frontend.clone(), Security::new(&Config::default(), None));
+    let proxy = serve(Router::new().fallback(handler).with_state(state)).await;
+    (frontend, proxy)
+  }
+
+  fn agent() -> ureq::Agent {
+    ureq::Agent::config_builder()
+      .http_status_as_error(false)
+      .max_redirects(0)
+      .timeout_global(Some(Duration::from_secs(10)))
+      .build()
+      .into()
+  }
+
+  #[tokio::test]
+  async fn streams_responses() {
+    // the second event is only sent once the first one went through the proxy
+    let (sent_tx, sent_rx) = oneshot::channel::<()>();
+    let sent_rx = Arc::new(Mutex::new(Some(sent_rx)));
+    let frontend = Router::new().route(
+      "/events",
+      get(move || {
+        let sent_rx = sent_rx.lock().unwrap().take().unwrap();
+        async move {
+          let events = futures_util::stream::once(async {
+            Ok::<_, std::io::Error>(Bytes::from("data: first\n\n"))
+          })
+          .chain(futures_util::stream::once(async move {
+            let _ = sent_rx.await;
+            Ok(Bytes::from("data: second\n\n"))
+          }));
+          (
+            [(header::CONTENT_TYPE, "text/event-stream")],
+            Body::from_stream(events),
+          )
+        }
+      }),
+    );
+    let (_, proxy) = proxy(frontend).await;
+
+    let body = tokio::task::spawn_blocking(move || {
+      let response = agent()
+        .get(proxy.join("events").unwrap().as_str())
+        .call()
+        .unwrap();
+      let mut reader = response.into_body().into_reader();
+      let mut body = Vec::new();
+      let mut buf = [0; 64];
+      while !body.ends_with(b"first\n\n") {
+        let n = reader.read(&mut buf).unwrap();
+        assert_ne!(n, 0, "the response ended early");
+        body.extend_from_slice(&buf[..n]);
+      }
+      sent_tx.send(()).unwrap();
+      reader.read_to_end(&mut body).unwrap();
+      body
+    })
+    .await
+    .unwrap();
+    assert_eq!(body, b"data: first\n\ndata: second\n\n");
+  }
+
+  #[tokio::test]
+  async fn forwards_requests() {
+    let frontend = Router::new()
+      .route(
+        "/",
+        get(|| async { Html("<html><head></head><body></body></html>") }),
+      )
+      .route("/echo", post(|body: Bytes| async move { body }))
+      .route(
+        "/redirect",
+        get(|headers: HeaderMap| async move {
+          let host = headers[header::HOST].to_str().unwrap().to_string();
+          (
+            StatusCode::FOUND,
+            [(header::LOCATION, format!("http://{host}/target?a=b"))],
+          )
+        }),
+      );
+    let (_, proxy) = proxy(frontend).await;
+
+    let (html, echo, location) = tokio::task::spawn_blocking(move || {
+      let agent = agent();
+      let html = agent
+        .get(proxy.as_str())
+        .call()
+        .unwrap()
+        .body_mut()
+        .read_to_string()
+        .unwrap();
+      let echo = agent
+        .post(proxy.join("echo").unwrap().as_str())
+        .send("a streamed request body")
+        .unwrap()
+        .body_mut()
+        .read_to_string()
+        .unwrap();
+      let redirect = agent
+        .get(proxy.join("redirect").unwrap().as_str())
+        .call()
+        .unwrap();
+      assert_eq!(redirect.status(), StatusCode::FOUND);
+      let location = redirect.headers()[header::LOCATION]
+        .to_str()
+        .unwrap()
+        .to_string();
+      (html, echo, location)
+    })
+    .await
+    .unwrap();
+
+    assert!(html.contains("<body></body>"), "{html}");
+    assert_eq!(echo, "a streamed request body");
+    assert_eq!(location, "/target?a=b");
+  }
+
+  #[tokio::test]
+  async fn proxies_websockets() {
+    let frontend = Router::new().route(
+      "/hmr",
+      get(|upgrade: WebSocketUpgrade| async move {
+        upgrade
+          .protocols(["vite-hmr"])
+          .on_upgrade(|mut socket| async move {
+            while let Some(Ok(message)) = socket.recv().await {
+              if let ws::Message::Text(text) = message {
+                let reply = format!("echo: {}", text.as_str());
+                if socket.send(ws::Message::Text(reply.into())).await.is_err() {
+                  break;
+                }
+              }
+            }
+          })
+      }),
+    );
+    let (_, proxy) = proxy(frontend).await;
+
+    let mut request = format!(
+      "ws://{}/hmr",
+      &proxy[url::Position::BeforeHost..url::Position::AfterPort]
+    )
+    .into_client_request()
+    .unwrap();
+    request.headers_mut().insert(
+      header::SEC_WEBSOCKET_PROTOCOL,
+      HeaderValue::from_static("vite-hmr"),
+    );
+    let (mut socket, response) = tokio_tungstenite::connect_async(request).await.unwrap();
+    assert_eq!(
+      response.headers()[header::SEC_WEBSOCKET_PROTOCOL],
+      "vite-hmr"
+    );
+
+    socket
+      .send(tungstenite::Message::text("update"))
+      .await
+      .unwrap();
+    let reply = socket.next().await.unwrap().unwrap();
+    assert_eq!(reply, tungstenite::Message::text("echo: update"));
+  }
+}
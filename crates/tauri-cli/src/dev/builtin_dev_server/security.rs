@@ -0,0 +1,290 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{collections::HashMap, path::PathBuf};
+
+use axum::http::{response::Builder, HeaderMap, HeaderValue};
+use tauri_utils::{
+  assets::{replace_csp_nonce, SCRIPT_NONCE_TOKEN, STYLE_NONCE_TOKEN},
+  config::{
+    Config, Csp, CspDirectiveSources, DisabledCspModificationKind, HeaderAddition, HeaderConfig,
+    PatternKind,
+  },
+  html::{inject_nonce_token, inline_script_hashes, parse as parse_html, serialize_node},
+  pattern::isolation_iframe_sources,
+};
+
+/// The `app.security` headers and CSP, applied the same way the production `tauri://` protocol does.
+#[derive(Clone)]
+pub struct Security {
+  headers: HeaderMap,
+  csp: Option<Csp>,
+  dangerous_disable_asset_csp_modification: DisabledCspModificationKind,
+  /// The file the build script writes the isolation schema of the app to,
+  /// read for every document since each build generates a new schema.
+  isolation_schema_path: Option<PathBuf>,
+}
+
+impl Security {
+  /// `isolation_schema_path` is the file the build script writes the isolation schema to,
+  /// see [`tauri_utils::pattern::dev_isolation_schema_file_name`].
+  pub fn new(config: &Config, isolation_schema_path: Option<PathBuf>) -> Self {
+    let security = &config.app.security;
+    Self {
+      headers: configured_headers(security.headers.as_ref()),
+      csp: security.dev_csp.clone().or_else(|| security.csp.clone()),
+      dangerous_disable_asset_csp_modification: security
+        .dangerous_disable_asset_csp_modification
+        .clone(),
+      isolation_schema_path: match security.pattern {
+        PatternKind::Isolation { .. } => isolation_schema_path,
+        PatternKind::Brownfield => None,
+      },
+    }
+  }
+
+  /// Adds the configured headers to a response, replacing the ones with the same name.
+  pub fn add_headers(&self, headers: &mut HeaderMap) {
+    for (name, value) in &self.headers {
+      headers.insert(name, value.clone());
+    }
+  }
+
+  /// Adds the nonces of the CSP to an HTML document, returning it with the `Content-Security-Policy` header value.
+  ///
+  /// `connect_src` are the sources the dev server itself needs the document to connect to, such as its reload websocket.
+  /// The document is left untouched when no CSP is configured.
+  pub fn process_html(
+    &self,
+    html: Vec<u8>,
+    connect_src: &[&str],
+  ) -> (Vec<u8>, Option<HeaderValue>) {
+    let Some(csp) = &self.csp else {
+      return (html, None);
+    };
+
+    let document = parse_html(String::from_utf8_lossy(&html).into_owned());
+    inject_nonce_token(&document, &self.dangerous_disable_asset_csp_modification);
+    let script_hashes = if self
+      .dangerous_disable_asset_csp_modification
+      .can_modify("script-src")
+    {
+      inline_script_hashes(&document)
+    } else {
+      Vec::new()
+    };
+    let mut html = String::from_utf8_lossy(&serialize_node(&document)).into_owned();
+
+    let mut csp_map: HashMap<String, CspDirectiveSources> = csp.clone().into();
+    if self
+      .dangerous_disable_asset_csp_modification
+      .can_modify("script-src")
+    {
+      replace_csp_nonce(
+        &mut html,
+        SCRIPT_NONCE_TOKEN,
+        &mut csp_map,
+        "script-src",
+        script_hashes,
+      );
+    }
+    if self
+      .dangerous_disable_asset_csp_modification
+      .can_modify("style-src")
+    {
+      replace_csp_nonce(
+        &mut html,
+        STYLE_NONCE_TOKEN,
+        &mut csp_map,
+        "style-src",
+        Vec::new(),
+      );
+    }
+
+    if let Some(schema) = self.isolation_schema() {
+      csp_map
+        .entry("default-src".into())
+        .or_default()
+        .extend(isolation_iframe_sources(&schema).to_vec());
+    }
+
+    for source in connect_src {
+      allow_connect_src(&mut csp_map, source);
+    }
+
+    let csp = HeaderValue::from_str(&Csp::DirectiveMap(csp_map).to_string()).ok();
+    (html.into_bytes(), csp)
+  }
+
+  /// The isolation schema of the last build of the app, if it uses the isolation pattern.
+  fn isolation_schema(&self) -> Option<String> {
+    let path = self.isolation_schema_path.as_ref()?;
+    match std::fs::read_to_string(path) {
+      Ok(schema) => Some(schema.trim().to_string()),
+      Err(error) => {
+        log::warn!(
+          "failed to read the isolation schema from {}, the isolation iframe is not allowed by the CSP: {error}",
+          path.display()
+        );
+        None
+      }
+    }
+  }
+}
+
+fn configured_headers(headers: Option<&HeaderConfig>) -> HeaderMap {
+  Builder::new()
+    .add_configured_headers(headers)
+    .headers_ref()
+    .cloned()
+    .unwrap_or_default()
+}
+
+/// Adds a source to the `connect-src` directive, which inherits the `default-src` sources when it is not set.
+fn allow_connect_src(csp: &mut HashMap<String, CspDirectiveSources>, source: &str) {
+  if !csp.contains_key("connect-src") {
+    let Some(default_src) = csp.get("default-src").cloned() else {
+      return;
+    };
+    csp.insert("connect-src".into(), default_src);
+  }
+  if let Some(sources) = csp.get_mut("connect-src") {
+    if !sources.contains(source) {
+      sources.push(source);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn security(csp: &str, pattern: PatternKind, isolation_schema_path: Option<PathBuf>) -> Security {
+    let mut config = Config::default();
+    config.app.security.csp = Some(Csp::Policy(csp.into()));
+    config.app.security.pattern = pattern;
+    Security::new(&config, isolation_schema_path)
+  }
+
+  fn directives(csp: &HeaderValue) -> HashMap<String, Vec<String>> {
+    HashMap::<String, CspDirectiveSources>::from(Csp::Policy(csp.to_str().unwrap().into()))
+      .into_iter()
+      .map(|(directive, sources)| (directive, sources.into()))
+      .collect()
+  }
+
+  fn nonces(html: &str) -> Vec<String> {
+    html
+      .split("nonce=\"")
+      .skip(1)
+      .map(|part| format!("'nonce-{}'", &part[..part.find('"').unwrap()]))
+      .collect()
+  }
+
+  #[test]
+  fn process_html() {
+    let security = security(
+      "default-src 'self' ipc:; style-src 'unsafe-inline'",
+      PatternKind::Brownfield,
+      None,
+    );
+    let (html, csp) = security.process_html(
+      br#"<html><head><script>console.log('inline')</script><script src="http://localhost:5173/main.js"></script><style>body {}</style></head><body></body></html>"#.to_vec(),
+      &["ws://127.0.0.1:1430/__tauri_cli"],
+    );
+    let html = String::from_utf8(html).unwrap();
+    let csp = directives(&csp.unwrap());
+
+    let nonces = nonces(&html);
+    assert_eq!(nonces.len(), 2, "{html}");
+    assert!(!html.contains(SCRIPT_NONCE_TOKEN) && !html.contains(STYLE_NONCE_TOKEN));
+    assert_eq!(
+      csp["script-src"],
+      [
+        "'self'",
+        nonces[0].as_str(),
+        "'sha256-UaOGrfnTUkOcfSx5zbfJdpt0OIURo3vZfzq63FFzVPM='",
+      ]
+    );
+    assert_eq!(
+      csp["style-src"],
+      ["'unsafe-inline'", "'self'", nonces[1].as_str()]
+    );
+    assert_eq!(
+      csp["connect-src"],
+      ["'self'", "ipc:", "ws://127.0.0.1:1430/__tauri_cli"]
+    );
+    assert_eq!(csp["default-src"], ["'self'", "ipc:"]);
+  }
+
+  #[test]
+  fn process_html_without_csp() {
+    let security = Security::new(&Config::default(), None);
+    let html = b"<html><head><style>body {}</style></head></html>".to_vec();
+    assert_eq!(security.process_html(html.clone(), &[]), (html, None));
+  }
+
+  #[test]
+  fn process_html_allows_the_isolation_iframe() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir
+      .path()
+      .join(tauri_utils::pattern::dev_isolation_schema_file_name("app"));
+    let security = security(
+      "default-src 'self'",
+      PatternKind::Isolation {
+        dir: "../isolation".into(),
+      },
+      Some(path.clone()),
+    );
+
+    // not built yet
+    let (_, csp) = security.process_html(b"<html></html>".to_vec(), &[]);
+    assert_eq!(directives(&csp.unwrap())["default-src"], ["'self'"]);
+
+    // every build generates a new schema, the CSP allows the origin the app loads the iframe from on every platform
+    for schema in ["isolation-1234", "isolation-5678"] {
+      std::fs::write(&path, schema).unwrap();
+      let (_, csp) = security.process_html(b"<html></html>".to_vec(), &[]);
+      let default_src = &directives(&csp.unwrap())["default-src"];
+      assert_eq!(default_src.len(), 4);
+      for (http_custom_protocol, https) in [(false, false), (true, false), (true, true)] {
+        let origin =
+          tauri_utils::pattern::isolation_iframe_src(schema, http_custom_protocol, https);
+        assert!(
+          default_src.contains(&origin),
+          "{origin} not in {default_src:?}"
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn allow_connect_src() {
+    let mut csp: HashMap<String, CspDirectiveSources> =
+      Csp::Policy("default-src 'self'; connect-src ipc:".into()).into();
+    allow_connect_src(&mut csp, "ws://localhost");
+    allow_connect_src(&mut csp, "ws://localhost");
+    assert_eq!(
+      Vec::from(csp["connect-src"].clone()),
+      ["ipc:", "ws://localhost"]
+    );
+
+    // `connect-src` inherits `default-src`
+    let mut csp: HashMap<String, CspDirectiveSources> =
+      Csp::Policy("default-src 'self' ipc:".into()).into();
+    allow_connect_src(&mut csp, "ws://localhost");
+    assert_eq!(
+      Vec::from(csp["connect-src"].clone()),
+      ["'self'", "ipc:", "ws://localhost"]
+    );
+    assert_eq!(Vec::from(csp["default-src"].clone()), ["'self'", "ipc:"]);
+
+    // without `default-src` every connection is already allowed
+    let mut csp: HashMap<String, CspDirectiveSources> =
+      Csp::Policy("script-src 'self'".into()).into();
+    allow_connect_src(&mut csp, "ws://localhost");
+    assert!(!csp.contains_key("connect-src"));
+  }
+}
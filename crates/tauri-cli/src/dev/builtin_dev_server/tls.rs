@@ -0,0 +1,118 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+  fs::OpenOptions,
+  io::Write,
+  net::{IpAddr, Ipv4Addr, Ipv6Addr},
+  path::{Path, PathBuf},
+};
+
+use crate::error::{Context, ErrorExt};
+
+/// The certificate and private key of the built-in dev server, in PEM format.
+pub struct Certificate {
+  pub cert: PathBuf,
+  pub key: PathBuf,
+}
+
+/// Returns the self-signed certificate used to serve the built-in dev server over HTTPS on the given address.
+///
+/// The certificate is generated on first use and stored in the local data directory,
+/// so it only has to be trusted once.
+pub fn certificate(ip: IpAddr) -> crate::Result<Certificate> {
+  let mut names = vec![
+    "localhost".to_string(),
+    Ipv4Addr::LOCALHOST.to_string(),
+    Ipv6Addr::LOCALHOST.to_string(),
+  ];
+  let name = if ip.is_loopback() || ip.is_unspecified() {
+    "localhost".to_string()
+  } else {
+    names.push(ip.to_string());
+    ip.to_string().replace(':', "_")
+  };
+
+  let dir = dirs::data_local_dir()
+    .context("failed to resolve the local data directory")?
+    .join("tauri")
+    .join("dev-server");
+  let certificate = Certificate {
+    cert: dir.join(format!("{name}.pem")),
+    key: dir.join(format!("{name}-key.pem")),
+  };
+  if certificate.cert.exists() && certificate.key.exists() {
+    restrict_permissions(&certificate.key)?;
+    return Ok(certificate);
+  }
+
+  let rcgen::CertifiedKey { cert, key_pair } = rcgen::generate_simple_self_signed(names)
+    .context("failed to generate the dev server certificate")?;
+  std::fs::create_dir_all(&dir).fs_context("failed to create directory", dir.clone())?;
+  std::fs::write(&certificate.cert, cert.pem())
+    .fs_context("failed to write certificate", certificate.cert.clone())?;
+  write_private_key(&certificate.key, key_pair.serialize_pem().as_bytes())
+    .fs_context("failed to write private key", certificate.key.clone())?;
+
+  log::info!(
+    "Generated a certificate for the built-in dev server at {}, trust it to load the app over HTTPS",
+    certificate.cert.display()
+  );
+
+  Ok(certificate)
+}
+
+/// Writes the private key so only the current user can read it.
+fn write_private_key(path: &Path, key: &[u8]) -> std::io::Result<()> {
+  let mut options = OpenOptions::new();
+  options.write(true).create(true).truncate(true);
+  #[cfg(unix)]
+  std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+  let mut file = options.open(path)?;
+  // the mode only applies to new files
+  #[cfg(unix)]
+  file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+  file.write_all(key)
+}
+
+/// Makes the private key unreadable by other users, in case it was written with the default permissions.
+fn restrict_permissions(path: &Path) -> crate::Result<()> {
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt;
+
+    let permissions = std::fs::metadata(path)
+      .fs_context("failed to read private key metadata", path.to_path_buf())?
+      .permissions();
+    if permissions.mode() & 0o077 != 0 {
+      std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600)).fs_context(
+        "failed to restrict private key permissions",
+        path.to_path_buf(),
+      )?;
+    }
+  }
+  #[cfg(not(unix))]
+  let _ = path;
+  Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+  use std::os::unix::fs::PermissionsExt;
+
+  #[test]
+  fn private_key_is_only_readable_by_the_owner() {
+    let dir = tempfile::tempdir().unwrap();
+    let key = dir.path().join("key.pem");
+
+    super::write_private_key(&key, b"key").unwrap();
+    let mode = std::fs::metadata(&key).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    std::fs::set_permissions(&key, std::fs::Permissions::from_mode(0o644)).unwrap();
+    super::restrict_permissions(&key).unwrap();
+    let mode = std::fs::metadata(&key).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+  }
+}
@@ -0,0 +1,290 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Machine-readable messages emitted by `tauri build` and `tauri bundle` with `--message-format json`.
+//!
+//! Each message is a JSON object written on its own line to stdout, with a `reason` field
+//! identifying its kind, similar to cargo's JSON messages. Human-readable logs are still written to stderr.
+
+use std::{
+  fs::File,
+  path::{Path, PathBuf},
+  sync::atomic::{AtomicBool, Ordering},
+};
+
+use clap::ValueEnum;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+static JSON: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum MessageFormat {
+  /// Human-readable logs
+  #[default]
+  Human,
+  /// JSON messages on stdout, one per line
+  Json,
+}
+
+/// Sets the format of the messages emitted by the current command.
+pub fn set_format(format: MessageFormat) {
+  JSON.store(format == MessageFormat::Json, Ordering::Relaxed);
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "reason", rename_all = "kebab-case")]
+pub enum Message<'a> {
+  /// A phase of the build started.
+  PhaseStarted {
+    phase: Phase,
+    target: Option<&'a str>,
+  },
+  /// A phase of the build finished.
+  PhaseFinished {
+    phase: Phase,
+    target: Option<&'a str>,
+    success: bool,
+  },
+  /// A warning was logged.
+  Warning { message: &'a str },
+  /// A file or directory was produced.
+  Artifact(Artifact),
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Phase {
+  BeforeBuildCommand,
+  Compile,
+  BeforeBundleCommand,
+  Bundle,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Artifact {
+  pub path: PathBuf,
+  /// `binary`, or the bundle format such as `msi`, `deb` or `app`.
+  #[serde(rename = "type")]
+  pub kind: String,
+  pub target: Option<String>,
+  /// The size in bytes, the size of all files for directories.
+  pub size: u64,
+  /// The SHA-256 of the file, `None` for directories.
+  pub sha256: Option<String>,
+  /// The updater signature of the file.
+  pub signature: Option<PathBuf>,
+}
+
+impl Artifact {
+  pub fn new(path: &Path, kind: impl Into<String>, target: Option<&str>) -> crate::Result<Self> {
+    use crate::error::ErrorExt;
+
+    let (size, sha256) = if path.is_dir() {
+      let size = walkdir::WalkDir::new(path)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum();
+      (size, None)
+    } else {
+      let mut file = File::open(path).fs_context("failed to open artifact", path.to_path_buf())?;
+      let mut hasher = Sha256::new();
+      let size = std::io::copy(&mut file, &mut hasher)
+        .fs_context("failed to read artifact", path.to_path_buf())?;
+      (size, Some(format!("{:x}", hasher.finalize())))
+    };
+
+    Ok(Self {
+      path: path.to_path_buf(),
+      kind: kind.into(),
+      target: target.map(Into::into),
+      size,
+      sha256,
+      signature: None,
+    })
+  }
+}
+
+/// Whether the JSON message format is enabled.
+pub fn is_json() -> bool {
+  JSON.load(Ordering::Relaxed)
+}
+
+/// Writes the message to stdout if the JSON message format is enabled.
+pub fn emit(message: Message<'_>) {
+  if is_json() {
+    if let Ok(line) = serde_json::to_string(&message) {
+      println!("{line}");
+    }
+  }
+}
+
+/// Runs a phase of the build, emitting its start and end.
+pub fn phase<T>(
+  phase: Phase,
+  target: Option<&str>,
+  f: impl FnOnce() -> crate::Result<T>,
+) -> crate::Result<T> {
+  emit(Message::PhaseStarted { phase, target });
+  let result = f();
+  emit(Message::PhaseFinished {
+    phase,
+    target,
+    success: result.is_ok(),
+  });
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use std::process::Command;
+
+  use serde_json::{json, Value};
+
+  use super::*;
+  use crate::CommandExt;
+
+  /// Set to run [`emit_messages_around_a_command`] in the process spawned by [`stdout_only_contains_messages`].
+  const EMIT_MESSAGES_ENV: &str = "TAURI_CLI_TEST_EMIT_MESSAGES";
+
+  #[test]
+  fn message_shapes() {
+    assert_eq!(
+      serde_json::to_value(Message::PhaseStarted {
+        phase: Phase::BeforeBuildCommand,
+        target: Some("x86_64-unknown-linux-gnu"),
+      })
+      .unwrap(),
+      json!({
+        "reason": "phase-started",
+        "phase": "before-build-command",
+        "target": "x86_64-unknown-linux-gnu",
+      })
+    );
+    assert_eq!(
+      serde_json::to_value(Message::PhaseFinished {
+        phase: Phase::BeforeBundleCommand,
+        target: None,
+        success: false,
+      })
+      .unwrap(),
+      json!({
+        "reason": "phase-finished",
+        "phase": "before-bundle-command",
+        "target": null,
+        "success": false,
+      })
+    );
+    assert_eq!(
+      serde_json::to_value(Message::Warning { message: "careful" }).unwrap(),
+      json!({ "reason": "warning", "message": "careful" })
+    );
+  }
+
+  #[test]
+  fn artifact_shape() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("app");
+    std::fs::write(&file, "hello").unwrap();
+    std::fs::write(dir.path().join("other"), "other").unwrap();
+
+    let mut artifact = Artifact::new(&file, "binary", Some("x86_64-pc-windows-msvc")).unwrap();
+    artifact.signature = Some(dir.path().join("app.sig"));
+    assert_eq!(
+      serde_json::to_value(Message::Artifact(artifact)).unwrap(),
+      json!({
+        "reason": "artifact",
+        "path": file,
+        "type": "binary",
+        "target": "x86_64-pc-windows-msvc",
+        "size": 5,
+        "sha256": "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824",
+        "signature": dir.path().join("app.sig"),
+      })
+    );
+
+    assert_eq!(
+      serde_json::to_value(Message::Artifact(
+        Artifact::new(dir.path(), "app", None).unwrap()
+      ))
+      .unwrap(),
+      json!({
+        "reason": "artifact",
+        "path": dir.path(),
+        "type": "app",
+        "target": null,
+        "size": 10,
+        "sha256": null,
+        "signature": null,
+      })
+    );
+  }
+
+  #[test]
+  fn emit_messages_around_a_command() {
+    if std::env::var_os(EMIT_MESSAGES_ENV).is_none() {
+      return;
+    }
+
+    set_format(MessageFormat::Json);
+    phase(Phase::BeforeBuildCommand, None, || {
+      #[cfg(windows)]
+      let mut command = Command::new("cmd");
+      #[cfg(windows)]
+      command.args(["/S", "/C", "echo not a message"]);
+      #[cfg(not(windows))]
+      let mut command = Command::new("sh");
+      #[cfg(not(windows))]
+      command.args(["-c", "echo not a message"]);
+
+      command
+        .piped()
+        .map_err(|error| crate::Error::CommandFailed {
+          command: "echo not a message".into(),
+          error,
+        })?;
+      Ok(())
+    })
+    .unwrap();
+  }
+
+  #[test]
+  fn stdout_only_contains_messages() {
+    let output = Command::new(std::env::current_exe().unwrap())
+      .args([
+        "helpers::message::tests::emit_messages_around_a_command",
+        "--exact",
+        "--nocapture",
+        "--quiet",
+      ])
+      .env(EMIT_MESSAGES_ENV, "1")
+      .output()
+      .unwrap();
+    assert!(output.status.success(), "{output:?}");
+
+    // the test harness prints its own lines around the ones of the test
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines = stdout.lines().collect::<Vec<_>>();
+    let first = lines.iter().position(|line| line.starts_with('{'));
+    let last = lines.iter().rposition(|line| line.starts_with('{'));
+    let (Some(first), Some(last)) = (first, last) else {
+      panic!("no message in {stdout}");
+    };
+    let reasons = lines[first..=last]
+      .iter()
+      .map(|line| {
+        let message: Value =
+          serde_json::from_str(line).unwrap_or_else(|_| panic!("`{line}` is not a JSON message"));
+        message["reason"].clone()
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(reasons, ["phase-started", "phase-finished"]);
+
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not a message"));
+  }
+}
@@ -10,6 +10,7 @@ pub mod flock;
 pub mod framework;
 pub mod fs;
 pub mod http;
+pub mod message;
 pub mod npm;
 #[cfg(target_os = "macos")]
 pub mod pbxproj;
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use super::SectionItem;
+use super::{Description, SectionItem};
 use crate::helpers::config::ConfigMetadata;
 use crate::helpers::framework;
 use std::{fs::read_to_string, path::PathBuf};
@@ -14,7 +14,7 @@ pub fn items(config: &ConfigMetadata, frontend_dir: Option<&PathBuf>) -> Vec<Sec
   } else {
     "build"
   };
-  items.push(SectionItem::new().description(format!("build-type: {bundle_or_build}")));
+  items.push(SectionItem::new().description(Description::new("build-type", bundle_or_build)));
 
   let csp = config
     .app
@@ -23,14 +23,16 @@ pub fn items(config: &ConfigMetadata, frontend_dir: Option<&PathBuf>) -> Vec<Sec
     .clone()
     .map(|c| c.to_string())
     .unwrap_or_else(|| "unset".to_string());
-  items.push(SectionItem::new().description(format!("CSP: {csp}")));
+  items.push(SectionItem::new().description(Description::new("CSP", csp)));
 
   if let Some(frontend_dist) = &config.build.frontend_dist {
-    items.push(SectionItem::new().description(format!("frontendDist: {frontend_dist}")));
+    items.push(
+      SectionItem::new().description(Description::new("frontendDist", frontend_dist.to_string())),
+    );
   }
 
   if let Some(dev_url) = &config.build.dev_url {
-    items.push(SectionItem::new().description(format!("devUrl: {dev_url}")));
+    items.push(SectionItem::new().description(Description::new("devUrl", dev_url.to_string())));
   }
 
   if let Some(frontend_dir) = frontend_dir {
@@ -38,11 +40,14 @@ pub fn items(config: &ConfigMetadata, frontend_dir: Option<&PathBuf>) -> Vec<Sec
       let (framework, bundler) = framework::infer_from_package_json(&package_json);
 
       if let Some(framework) = framework {
-        items.push(SectionItem::new().description(format!("framework: {framework}")));
+        items.push(
+          SectionItem::new().description(Description::new("framework", framework.to_string())),
+        );
       }
 
       if let Some(bundler) = bundler {
-        items.push(SectionItem::new().description(format!("bundler: {bundler}")));
+        items
+          .push(SectionItem::new().description(Description::new("bundler", bundler.to_string())));
       }
     }
   }
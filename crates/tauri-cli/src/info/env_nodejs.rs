@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use super::{ActionResult, SectionItem, VersionMetadata};
+use super::{ActionResult, Description, SectionItem, VersionMetadata};
 use colored::Colorize;
 
 use crate::helpers::{cross_command, npm::manager_version};
@@ -25,20 +25,23 @@ pub fn items(metadata: &VersionMetadata) -> Vec<SectionItem> {
               .strip_prefix('v')
               .unwrap_or_default()
               .trim();
-            ActionResult::Description(format!("node: {}{}", v, {
-              let version = semver::Version::parse(v);
-              let target_version = semver::Version::parse(node_target_ver.as_str());
-              match (version, target_version) {
-                (Ok(version), Ok(target_version)) if version < target_version => {
-                  format!(
-                    " ({}, latest: {})",
-                    "outdated".red(),
-                    target_version.to_string().green()
-                  )
+            ActionResult::Description(Description::new(
+              "node",
+              format!("{}{}", v, {
+                let version = semver::Version::parse(v);
+                let target_version = semver::Version::parse(node_target_ver.as_str());
+                match (version, target_version) {
+                  (Ok(version), Ok(target_version)) if version < target_version => {
+                    format!(
+                      " ({}, latest: {})",
+                      "outdated".red(),
+                      target_version.to_string().green()
+                    )
+                  }
+                  _ => "".into(),
                 }
-                _ => "".into(),
-              }
-            }))
+              }),
+            ))
           } else {
             ActionResult::None
           }
@@ -46,10 +49,30 @@ pub fn items(metadata: &VersionMetadata) -> Vec<SectionItem> {
         .ok()
         .unwrap_or_default()
     }),
-    SectionItem::new().action(|| manager_version("pnpm").map(|v| format!("pnpm: {v}")).into()),
-    SectionItem::new().action(|| manager_version("yarn").map(|v| format!("yarn: {v}")).into()),
-    SectionItem::new().action(|| manager_version("npm").map(|v| format!("npm: {v}")).into()),
-    SectionItem::new().action(|| manager_version("bun").map(|v| format!("bun: {v}")).into()),
-    SectionItem::new().action(|| manager_version("deno").map(|v| format!("deno: {v}")).into()),
+    SectionItem::new().action(|| {
+      manager_version("pnpm")
+        .map(|v| Description::new("pnpm", v))
+        .into()
+    }),
+    SectionItem::new().action(|| {
+      manager_version("yarn")
+        .map(|v| Description::new("yarn", v))
+        .into()
+    }),
+    SectionItem::new().action(|| {
+      manager_version("npm")
+        .map(|v| Description::new("npm", v))
+        .into()
+    }),
+    SectionItem::new().action(|| {
+      manager_version("bun")
+        .map(|v| Description::new("bun", v))
+        .into()
+    }),
+    SectionItem::new().action(|| {
+      manager_version("deno")
+        .map(|v| Description::new("deno", v))
+        .into()
+    }),
   ]
 }
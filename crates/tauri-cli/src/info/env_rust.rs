@@ -2,24 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use super::SectionItem;
-use super::Status;
+use super::{Description, SectionItem, Status};
 use colored::Colorize;
 use std::process::Command;
 
-fn component_version(component: &str) -> Option<(String, Status)> {
+fn component_version(component: &str) -> Option<(Description, Status)> {
   Command::new(component)
     .arg("-V")
     .output()
     .map(|o| String::from_utf8_lossy(o.stdout.as_slice()).to_string())
     .map(|v| {
-      format!(
-        "{component}: {}",
+      Description::new(
+        component,
         v.split('\n')
           .next()
           .unwrap()
           .strip_prefix(&format!("{component} "))
-          .unwrap_or_default()
+          .unwrap_or_default(),
       )
     })
     .map(|desc| (desc, Status::Success))
@@ -32,11 +31,10 @@ pub fn items() -> Vec<SectionItem> {
        component_version("rustc")
           .unwrap_or_else(|| {
             (
-              format!(
-                "rustc: {}\nMaybe you don't have rust installed! Visit {}",
-                "not installed!".red(),
+              Description::new("rustc", "not installed!".red().to_string()).hint(format!(
+                "Maybe you don't have rust installed! Visit {}",
                 "https://rustup.rs/".cyan()
-              ),
+              )),
               Status::Error,
             )
           }).into()
@@ -45,11 +43,10 @@ pub fn items() -> Vec<SectionItem> {
         component_version("cargo")
           .unwrap_or_else(|| {
             (
-              format!(
-                "Cargo: {}\nMaybe you don't have rust installed! Visit {}",
-                "not installed!".red(),
+              Description::new("Cargo", "not installed!".red().to_string()).hint(format!(
+                "Maybe you don't have rust installed! Visit {}",
                 "https://rustup.rs/".cyan()
-              ),
+              )),
               Status::Error,
             )
           }).into()
@@ -58,11 +55,10 @@ pub fn items() -> Vec<SectionItem> {
         component_version("rustup")
             .unwrap_or_else(|| {
               (
-                format!(
-                  "rustup: {}\nIf you have rust installed some other way, we recommend uninstalling it\nthen use rustup instead. Visit {}",
-                  "not installed!".red(),
+                Description::new("rustup", "not installed!".red().to_string()).hint(format!(
+                  "If you have rust installed some other way, we recommend uninstalling it\nthen use rustup instead. Visit {}",
                   "https://rustup.rs/".cyan()
-                ),
+                )),
                 Status::Warning,
               )
             }).into()
@@ -73,8 +69,8 @@ pub fn items() -> Vec<SectionItem> {
             .output()
             .map(|o| String::from_utf8_lossy(o.stdout.as_slice()).to_string())
             .map(|v| {
-              format!(
-                "Rust toolchain: {}",
+              Description::new(
+                "Rust toolchain",
                 v.split('\n')
                   .next()
                   .unwrap()
@@ -84,9 +80,9 @@ pub fn items() -> Vec<SectionItem> {
             .ok()
             .unwrap_or_else(|| {
               (
-                format!(
-                  "Rust toolchain: couldn't be detected!\nMaybe you don't have rustup installed? if so, Visit {}", "https://rustup.rs/".cyan()
-                ),
+                Description::new("Rust toolchain", "couldn't be detected!").hint(format!(
+                  "Maybe you don't have rustup installed? if so, Visit {}", "https://rustup.rs/".cyan()
+                )),
                 Status::Warning,
               )
             }).into()
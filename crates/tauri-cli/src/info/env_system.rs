@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use super::{SectionItem, Status};
+use super::{Description, SectionItem, Status};
 #[cfg(windows)]
 use crate::error::Context;
 use colored::Colorize;
@@ -177,9 +177,156 @@ fn pkg_conf_version(package: &str) -> Option<String> {
   target_os = "openbsd",
   target_os = "netbsd"
 ))]
-fn webkit2gtk_ver() -> Option<String> {
-  pkg_conf_version("webkit2gtk-4.1")
+struct SystemLibrary {
+  name: &'static str,
+  /// The pkg-config names of the library, the first one found is used.
+  pkg_config: &'static [&'static str],
+  /// Whether the library is only required by some features, such as the system tray.
+  optional: Option<&'static str>,
+  /// The package to install, for each package manager.
+  packages: &'static [(PackageManager, &'static str)],
 }
+
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "openbsd",
+  target_os = "netbsd"
+))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PackageManager {
+  Apt,
+  Dnf,
+  Pacman,
+  Zypper,
+  Apk,
+  Xbps,
+  Emerge,
+}
+
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "openbsd",
+  target_os = "netbsd"
+))]
+impl PackageManager {
+  fn install_command(&self) -> &'static str {
+    match self {
+      Self::Apt => "sudo apt install",
+      Self::Dnf => "sudo dnf install",
+      Self::Pacman => "sudo pacman -S",
+      Self::Zypper => "sudo zypper in",
+      Self::Apk => "sudo apk add",
+      Self::Xbps => "sudo xbps-install -S",
+      Self::Emerge => "sudo emerge",
+    }
+  }
+
+  /// Finds the package manager of the distribution from `/etc/os-release`.
+  fn detect() -> Option<Self> {
+    Self::from_os_release(&std::fs::read_to_string("/etc/os-release").ok()?)
+  }
+
+  /// Finds the package manager from the `ID` and `ID_LIKE` fields of an os-release file.
+  fn from_os_release(os_release: &str) -> Option<Self> {
+    let mut ids = Vec::new();
+    for line in os_release.lines() {
+      if let Some(value) = line
+        .strip_prefix("ID=")
+        .or_else(|| line.strip_prefix("ID_LIKE="))
+      {
+        ids.extend(
+          value
+            .trim_matches('"')
+            .split_whitespace()
+            .map(str::to_string),
+        );
+      }
+    }
+
+    ids.iter().find_map(|id| match id.as_str() {
+      "debian" | "ubuntu" => Some(Self::Apt),
+      "fedora" | "rhel" | "centos" => Some(Self::Dnf),
+      "arch" => Some(Self::Pacman),
+      "suse" | "opensuse" => Some(Self::Zypper),
+      "alpine" => Some(Self::Apk),
+      "void" => Some(Self::Xbps),
+      "gentoo" => Some(Self::Emerge),
+      id if id.starts_with("opensuse") => Some(Self::Zypper),
+      _ => None,
+    })
+  }
+}
+
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "openbsd",
+  target_os = "netbsd"
+))]
+const SYSTEM_LIBRARIES: &[SystemLibrary] = &[
+  SystemLibrary {
+    name: "webkit2gtk-4.1",
+    pkg_config: &["webkit2gtk-4.1"],
+    optional: None,
+    packages: &[
+      (PackageManager::Apt, "libwebkit2gtk-4.1-dev"),
+      (PackageManager::Dnf, "webkit2gtk4.1-devel"),
+      (PackageManager::Pacman, "webkit2gtk-4.1"),
+      (PackageManager::Zypper, "webkit2gtk3-devel"),
+      (PackageManager::Apk, "webkit2gtk-4.1-dev"),
+      (PackageManager::Xbps, "webkit2gtk-devel"),
+      (PackageManager::Emerge, "net-libs/webkit-gtk:4.1"),
+    ],
+  },
+  SystemLibrary {
+    name: "libsoup-3.0",
+    pkg_config: &["libsoup-3.0"],
+    optional: None,
+    packages: &[
+      (PackageManager::Apt, "libsoup-3.0-dev"),
+      (PackageManager::Dnf, "libsoup3-devel"),
+      (PackageManager::Pacman, "libsoup3"),
+      (PackageManager::Zypper, "libsoup-devel"),
+      (PackageManager::Apk, "libsoup3-dev"),
+      (PackageManager::Xbps, "libsoup3-devel"),
+      (PackageManager::Emerge, "net-libs/libsoup:3.0"),
+    ],
+  },
+  SystemLibrary {
+    name: "rsvg2",
+    pkg_config: &["librsvg-2.0"],
+    optional: None,
+    packages: &[
+      (PackageManager::Apt, "librsvg2-dev"),
+      (PackageManager::Dnf, "librsvg2-devel"),
+      (PackageManager::Pacman, "librsvg"),
+      (PackageManager::Zypper, "librsvg-devel"),
+      (PackageManager::Apk, "librsvg-dev"),
+      (PackageManager::Xbps, "librsvg-devel"),
+      (PackageManager::Emerge, "gnome-base/librsvg"),
+    ],
+  },
+  SystemLibrary {
+    name: "appindicator",
+    pkg_config: &["ayatana-appindicator3-0.1", "appindicator3-0.1"],
+    optional: Some("the system tray"),
+    packages: &[
+      (PackageManager::Apt, "libayatana-appindicator3-dev"),
+      (PackageManager::Dnf, "libappindicator-gtk3-devel"),
+      (PackageManager::Pacman, "libappindicator-gtk3"),
+      (PackageManager::Zypper, "libappindicator3-1"),
+      (PackageManager::Apk, "libayatana-appindicator-dev"),
+      (PackageManager::Xbps, "libappindicator"),
+      (PackageManager::Emerge, "dev-libs/libayatana-appindicator"),
+    ],
+  },
+];
+
 #[cfg(any(
   target_os = "linux",
   target_os = "dragonfly",
@@ -187,8 +334,42 @@ fn webkit2gtk_ver() -> Option<String> {
   target_os = "openbsd",
   target_os = "netbsd"
 ))]
-fn rsvg2_ver() -> Option<String> {
-  pkg_conf_version("librsvg-2.0")
+fn system_library_item(library: &'static SystemLibrary) -> SectionItem {
+  SectionItem::new().action(move || {
+    if let Some(version) = library.pkg_config.iter().find_map(|p| pkg_conf_version(p)) {
+      return (Description::new(library.name, version), Status::Success).into();
+    }
+
+    let install = PackageManager::detect().and_then(|manager| {
+      library
+        .packages
+        .iter()
+        .find(|(m, _)| *m == manager)
+        .map(|(_, package)| format!("{} {package}", manager.install_command()))
+    });
+    let hint = match install {
+      Some(install) => format!("Run `{}`", install.cyan()),
+      None => format!(
+        "Visit {} to learn more about tauri prerequisites",
+        "https://v2.tauri.app/start/prerequisites/".cyan()
+      ),
+    };
+    match library.optional {
+      Some(feature) => (
+        Description::new(
+          library.name,
+          format!("{} (required for {feature})", "not installed".yellow()),
+        )
+        .hint(hint),
+        Status::Warning,
+      ),
+      None => (
+        Description::new(library.name, "not installed".red().to_string()).hint(hint),
+        Status::Error,
+      ),
+    }
+    .into()
+  })
 }
 
 #[cfg(target_os = "macos")]
@@ -244,28 +425,28 @@ fn de_and_session() -> String {
 }
 
 pub fn items() -> Vec<SectionItem> {
-  vec![
+  #[allow(unused_mut)]
+  let mut items = vec![
     SectionItem::new().action(|| {
       let os_info = os_info::get();
-      format!(
-        "OS: {} {} {} ({:?}){}",
+      Description::new("OS", format!(
+        "{} {} {} ({:?}){}",
         os_info.os_type(),
         os_info.version(),
         os_info.architecture().unwrap_or("Unknown Architecture"),
         os_info.bitness(),
         de_and_session(),
-      ).into()
+      )).into()
     }),
     #[cfg(windows)]
     SectionItem::new().action(|| {
-      let error = format!(
-          "Webview2: {}\nVisit {}",
-          "not installed!".red(),
+      let error = Description::new("WebView2", "not installed!".red().to_string()).hint(format!(
+          "Visit {}",
           "https://developer.microsoft.com/en-us/microsoft-edge/webview2/".cyan()
-        );
+        ));
       webview2_version()
         .map(|v| {
-          v.map(|v| (format!("WebView2: {v}"), Status::Success))
+          v.map(|v| (Description::new("WebView2", v), Status::Success))
             .unwrap_or_else(|| (error.clone(), Status::Error))
         })
         .unwrap_or_else(|_| (error, Status::Error)).into()
@@ -275,85 +456,32 @@ pub fn items() -> Vec<SectionItem> {
       let build_tools = build_tools_version().unwrap_or_default();
       if build_tools.is_empty() {
         (
-            format!(
+            Description::new("MSVC", "not installed!".red().to_string()).hint(format!(
               "Couldn't detect any Visual Studio or VS Build Tools instance with MSVC and SDK components. Download from {}",
               "https://aka.ms/vs/17/release/vs_BuildTools.exe".cyan()
-            ),
+            )),
             Status::Error,
           ).into()
       } else {
         (
-          format!(
-            "MSVC: {}{}",
-            if build_tools.len() > 1 {
-              format!("\n  {} ", "-".cyan())
-            } else {
-              "".into()
-            },
-            build_tools.join(format!("\n  {} ", "-".cyan()).as_str()),
-          ),
+          Description::new("MSVC", build_tools.join("\n")),
           Status::Success,
         ).into()
       }
     }),
-    #[cfg(any(
-      target_os = "linux",
-      target_os = "dragonfly",
-      target_os = "freebsd",
-      target_os = "openbsd",
-      target_os = "netbsd"
-    ))]
-    SectionItem::new().action(|| {
-          webkit2gtk_ver()
-            .map(|v| (format!("webkit2gtk-4.1: {v}"), Status::Success))
-            .unwrap_or_else(|| {
-              (
-                format!(
-                  "webkit2gtk-4.1: {}\nVisit {} to learn more about tauri prerequisites",
-                  "not installed".red(),
-                  "https://v2.tauri.app/start/prerequisites/".cyan()
-                ),
-                Status::Error,
-              )
-            }).into()
-      },
-    ),
-    #[cfg(any(
-      target_os = "linux",
-      target_os = "dragonfly",
-      target_os = "freebsd",
-      target_os = "openbsd",
-      target_os = "netbsd"
-    ))]
-    SectionItem::new().action(|| {
-          rsvg2_ver()
-            .map(|v| (format!("rsvg2: {v}"), Status::Success))
-            .unwrap_or_else(|| {
-              (
-                format!(
-                  "rsvg2: {}\nVisit {} to learn more about tauri prerequisites",
-                  "not installed".red(),
-                  "https://v2.tauri.app/start/prerequisites/".cyan()
-                ),
-                Status::Error,
-              )
-            }).into()
-      },
-    ),
     #[cfg(target_os = "macos")]
     SectionItem::new().action(|| {
         if is_xcode_command_line_tools_installed() {
           (
-            "Xcode Command Line Tools: installed".into(),
+            Description::new("Xcode Command Line Tools", "installed"),
             Status::Success,
           )
         } else {
           (
-            format!(
-              "Xcode Command Line Tools: {}\n Run `{}`",
-              "not installed!".red(),
+            Description::new("Xcode Command Line Tools", "not installed!".red().to_string()).hint(format!(
+              "Run `{}`",
               "xcode-select --install".cyan()
-            ),
+            )),
             Status::Error,
           )
         }.into()
@@ -361,9 +489,74 @@ pub fn items() -> Vec<SectionItem> {
     ),
     #[cfg(target_os = "macos")]
     SectionItem::new().action(|| {
-      xcode_version().map(|v| (format!("Xcode: {v}"), Status::Success)).unwrap_or_else(|| {
-          (format!("Xcode: {}", "not installed!".red()), Status::Error)
+      xcode_version().map(|v| (Description::new("Xcode", v), Status::Success)).unwrap_or_else(|| {
+          (Description::new("Xcode", "not installed!".red().to_string()), Status::Error)
       }).into()
     }),
-  ]
+  ];
+
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+  ))]
+  items.extend(SYSTEM_LIBRARIES.iter().map(system_library_item));
+
+  items
+}
+
+#[cfg(all(
+  test,
+  any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+  )
+))]
+mod tests {
+  use super::PackageManager;
+
+  #[test]
+  fn package_manager_from_os_release() {
+    for (os_release, manager) in [
+      (
+        "NAME=\"Ubuntu\"\nID=ubuntu\nID_LIKE=debian\n",
+        Some(PackageManager::Apt),
+      ),
+      (
+        "NAME=\"Linux Mint\"\nID=linuxmint\nID_LIKE=\"ubuntu debian\"\n",
+        Some(PackageManager::Apt),
+      ),
+      ("ID=fedora\n", Some(PackageManager::Dnf)),
+      (
+        "ID=\"rocky\"\nID_LIKE=\"rhel centos fedora\"\n",
+        Some(PackageManager::Dnf),
+      ),
+      ("ID=manjaro\nID_LIKE=arch\n", Some(PackageManager::Pacman)),
+      (
+        "ID=\"opensuse-tumbleweed\"\nID_LIKE=\"opensuse suse\"\n",
+        Some(PackageManager::Zypper),
+      ),
+      ("ID=\"opensuse-leap\"\n", Some(PackageManager::Zypper)),
+      ("ID=alpine\n", Some(PackageManager::Apk)),
+      ("ID=void\n", Some(PackageManager::Xbps)),
+      ("ID=gentoo\n", Some(PackageManager::Emerge)),
+      // `VERSION_ID` and `PLATFORM_ID` are not `ID`
+      (
+        "VERSION_ID=\"debian\"\nPLATFORM_ID=\"platform:f39\"\nID=nixos\n",
+        None,
+      ),
+      ("", None),
+    ] {
+      assert_eq!(
+        PackageManager::from_os_release(os_release),
+        manager,
+        "{os_release}"
+      );
+    }
+  }
 }
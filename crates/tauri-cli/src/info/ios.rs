@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use super::SectionItem;
+use super::{Description, SectionItem};
 
 use colored::Colorize;
 
@@ -11,15 +11,15 @@ pub fn items() -> Vec<SectionItem> {
     let teams = cargo_mobile2::apple::teams::find_development_teams().unwrap_or_default();
 
     if teams.is_empty() {
-      "Developer Teams: None".red().to_string().into()
+      Description::new("Developer Teams", "None".red().to_string()).into()
     } else {
-      format!(
-        "Developer Teams: {}",
+      Description::new(
+        "Developer Teams",
         teams
           .iter()
           .map(|t| format!("{} (ID: {})", t.name, t.id))
           .collect::<Vec<String>>()
-          .join(", ")
+          .join(", "),
       )
       .into()
     }
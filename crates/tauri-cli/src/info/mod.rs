@@ -7,10 +7,10 @@ use crate::{
   helpers::app_paths::{resolve_frontend_dir, resolve_tauri_dir},
   Result,
 };
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use colored::{ColoredString, Colorize};
 use dialoguer::{theme::ColorfulTheme, Confirm};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
 use tauri_utils::platform::Target;
 
@@ -42,7 +42,8 @@ fn version_metadata() -> Result<VersionMetadata> {
     .context("failed to parse version metadata")
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Status {
   Neutral = 0,
   #[default]
@@ -78,25 +79,80 @@ impl Display for Status {
   }
 }
 
+/// What an item reports: a name, its value and a hint to fix it, e.g. `rustc: not installed!` and where to get it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Description {
+  name: String,
+  /// Only shown in the human-readable output, after the name.
+  icon: Option<String>,
+  /// One entry per line for lists of values.
+  value: String,
+  hint: Option<String>,
+}
+
+impl Description {
+  fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+    Self {
+      name: name.into(),
+      icon: None,
+      value: value.into(),
+      hint: None,
+    }
+  }
+
+  fn icon(mut self, icon: impl Into<String>) -> Self {
+    self.icon = Some(icon.into());
+    self
+  }
+
+  fn hint(mut self, hint: impl Into<String>) -> Self {
+    self.hint = Some(hint.into());
+    self
+  }
+}
+
+impl Display for Description {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.name.bold())?;
+    if let Some(icon) = &self.icon {
+      write!(f, " {}", icon.bold())?;
+    }
+    write!(f, ":")?;
+    if self.value.contains('\n') {
+      for line in self.value.lines() {
+        write!(f, "\n        {} {line}", "-".cyan())?;
+      }
+    } else {
+      write!(f, " {}", self.value)?;
+    }
+    if let Some(hint) = &self.hint {
+      for line in hint.lines() {
+        write!(f, "\n      {line}")?;
+      }
+    }
+    Ok(())
+  }
+}
+
 #[derive(Default)]
 pub enum ActionResult {
   Full {
-    description: String,
+    description: Description,
     status: Status,
   },
-  Description(String),
+  Description(Description),
   #[default]
   None,
 }
 
-impl From<String> for ActionResult {
-  fn from(value: String) -> Self {
+impl From<Description> for ActionResult {
+  fn from(value: Description) -> Self {
     ActionResult::Description(value)
   }
 }
 
-impl From<(String, Status)> for ActionResult {
-  fn from(value: (String, Status)) -> Self {
+impl From<(Description, Status)> for ActionResult {
+  fn from(value: (Description, Status)) -> Self {
     ActionResult::Full {
       description: value.0,
       status: value.1,
@@ -104,14 +160,14 @@ impl From<(String, Status)> for ActionResult {
   }
 }
 
-impl From<Option<String>> for ActionResult {
-  fn from(value: Option<String>) -> Self {
+impl From<Option<Description>> for ActionResult {
+  fn from(value: Option<Description>) -> Self {
     value.map(ActionResult::Description).unwrap_or_default()
   }
 }
 
-impl From<Option<(String, Status)>> for ActionResult {
-  fn from(value: Option<(String, Status)>) -> Self {
+impl From<Option<(Description, Status)>> for ActionResult {
+  fn from(value: Option<(Description, Status)>) -> Self {
     value
       .map(|v| ActionResult::Full {
         description: v.0,
@@ -123,7 +179,7 @@ impl From<Option<(String, Status)>> for ActionResult {
 
 pub struct SectionItem {
   /// If description is none, the item is skipped
-  description: Option<String>,
+  description: Option<Description>,
   status: Status,
   action: Option<Box<dyn FnMut() -> ActionResult>>,
   action_if_err: Option<Box<dyn FnMut() -> ActionResult>>,
@@ -131,13 +187,10 @@ pub struct SectionItem {
 
 impl Display for SectionItem {
   fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-    let desc = self
-      .description
-      .as_ref()
-      .map(|s| s.replace('\n', "\n      "))
-      .unwrap_or_default();
-    let (first, second) = desc.split_once(':').unwrap();
-    write!(f, "{} {}:{}", self.status, first.bold(), second)
+    match &self.description {
+      Some(description) => write!(f, "{} {description}", self.status),
+      None => Ok(()),
+    }
   }
 }
 
@@ -161,8 +214,8 @@ impl SectionItem {
   //   self
   // }
 
-  fn description<S: AsRef<str>>(mut self, description: S) -> Self {
-    self.description = Some(description.as_ref().to_string());
+  fn description(mut self, description: Description) -> Self {
+    self.description = Some(description);
     self
   }
 
@@ -206,7 +259,7 @@ impl SectionItem {
         let confirmed = Confirm::with_theme(&ColorfulTheme::default())
           .with_prompt(format!(
             "{}\n  Run the automatic fix?",
-            description.replace('\n', "\n  ")
+            description.to_string().replace('\n', "\n  ")
           ))
           .interact()
           .unwrap_or(false);
@@ -227,7 +280,8 @@ struct Section<'a> {
 }
 
 impl Section<'_> {
-  fn display(&mut self) {
+  /// Runs the actions of the items, returning the status of the section.
+  fn run(&mut self) -> Status {
     let mut status = Status::Neutral;
 
     for item in &mut self.items {
@@ -237,6 +291,12 @@ impl Section<'_> {
       }
     }
 
+    status
+  }
+
+  fn display(&mut self) {
+    let status = self.run();
+
     let status_str = format!("[{status}]");
     let status = status.color(status_str);
 
@@ -250,18 +310,100 @@ impl Section<'_> {
   }
 }
 
+/// A section of the machine-readable output.
+#[derive(Serialize)]
+struct SectionReport {
+  label: String,
+  status: Status,
+  items: Vec<ItemReport>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct ItemReport {
+  status: Status,
+  name: String,
+  value: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  hint: Option<String>,
+}
+
+impl SectionReport {
+  fn new(section: &mut Section<'_>) -> Self {
+    let status = section.run();
+    let items = section
+      .items
+      .iter()
+      .filter_map(|item| {
+        let description = item.description.clone()?;
+        Some(ItemReport {
+          status: item.status,
+          name: description.name,
+          value: description.value,
+          hint: description.hint,
+        })
+      })
+      .collect();
+    Self {
+      label: section.label.to_string(),
+      status,
+      items,
+    }
+  }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MismatchedPackage {
+  crate_name: String,
+  crate_version: String,
+  npm_name: String,
+  npm_version: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Report {
+  mismatched_packages: Vec<MismatchedPackage>,
+  sections: Vec<SectionReport>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+  /// Colored, human-readable tree
+  #[default]
+  Human,
+  /// JSON document
+  Json,
+  /// TOML document
+  Toml,
+}
+
 #[derive(Debug, Parser)]
 #[clap(
   about = "Show a concise list of information about the environment, Rust, Node.js and their versions as well as a few relevant project configurations"
 )]
 pub struct Options {
   /// Interactive mode to apply automatic fixes.
-  #[clap(long)]
+  #[clap(long, conflicts_with = "format")]
   pub interactive: bool,
+  /// The output format.
+  #[clap(long, value_enum, default_value_t = Format::Human)]
+  pub format: Format,
+  /// Exit with an error if the versions of the `tauri` crates and the `@tauri-apps/*` packages do not match.
+  #[clap(long)]
+  pub check: bool,
 }
 
 pub fn command(options: Options) -> Result<()> {
-  let Options { interactive } = options;
+  let Options {
+    interactive,
+    format,
+    check,
+  } = options;
+
+  if format != Format::Human {
+    colored::control::set_override(false);
+  }
 
   let frontend_dir = resolve_frontend_dir();
   let tauri_dir = resolve_tauri_dir();
@@ -315,35 +457,155 @@ pub fn command(options: Options) -> Result<()> {
     };
   }
 
-  environment.display();
-
-  packages.display();
-
-  plugins.display();
-
-  if let (Some(frontend_dir), Some(tauri_dir)) = (&frontend_dir, &tauri_dir) {
-    if let Err(error) = plugins::check_mismatched_packages(frontend_dir, tauri_dir) {
-      println!("\n{}: {error}", "Error".bright_red().bold());
-    }
-  }
-
-  app.display();
+  let installed_packages = match (&frontend_dir, &tauri_dir) {
+    (Some(frontend_dir), Some(tauri_dir)) => Some(plugins::installed_tauri_packages(
+      frontend_dir,
+      tauri_dir,
+      crate::helpers::npm::PackageManager::from_project(frontend_dir),
+    )),
+    _ => None,
+  };
+  let mismatch = installed_packages
+    .as_ref()
+    .and_then(|installed| installed.check_mismatched().err());
 
   // iOS
+  #[allow(unused_mut)]
+  let mut ios: Option<Section> = None;
   #[cfg(target_os = "macos")]
   {
     if let Some(p) = &tauri_dir {
       if p.join("gen/apple").exists() {
-        let mut ios = Section {
+        let mut section = Section {
           label: "iOS",
           interactive,
           items: Vec::new(),
         };
-        ios.items.extend(ios::items());
-        ios.display();
+        section.items.extend(ios::items());
+        ios.replace(section);
+      }
+    }
+  }
+
+  if format == Format::Human {
+    environment.display();
+
+    packages.display();
+
+    plugins.display();
+
+    // with --check the mismatch is returned as the command error instead
+    if !check {
+      if let Some(error) = &mismatch {
+        println!("\n{}: {error}", "Error".bright_red().bold());
       }
     }
+
+    app.display();
+
+    if let Some(ios) = &mut ios {
+      ios.display();
+    }
+  } else {
+    let report = Report {
+      mismatched_packages: installed_packages
+        .as_ref()
+        .map(|installed| {
+          installed
+            .mismatched()
+            .into_iter()
+            .map(|p| MismatchedPackage {
+              crate_name: p.crate_name.clone(),
+              crate_version: p.crate_version.to_string(),
+              npm_name: p.npm_name.clone(),
+              npm_version: p.npm_version.to_string(),
+            })
+            .collect()
+        })
+        .unwrap_or_default(),
+      sections: [environment, packages, plugins, app]
+        .into_iter()
+        .chain(ios)
+        .map(|mut section| SectionReport::new(&mut section))
+        .collect(),
+    };
+    let output = match format {
+      Format::Json => {
+        serde_json::to_string_pretty(&report).context("failed to serialize info as JSON")?
+      }
+      _ => toml::to_string_pretty(&report).context("failed to serialize info as TOML")?,
+    };
+    println!("{output}");
+  }
+
+  if check {
+    if let Some(error) = mismatch {
+      return Err(error);
+    }
   }
 
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn section_report() {
+    let mut section = Section {
+      label: "Environment",
+      interactive: false,
+      items: vec![
+        SectionItem::new().description(Description::new("CSP", "default-src 'self' ipc:")),
+        SectionItem::new().action(|| {
+          (
+            Description::new("rustc", "not installed!")
+              .icon("🦀")
+              .hint("Visit https://rustup.rs/"),
+            Status::Error,
+          )
+            .into()
+        }),
+        SectionItem::new().action(|| None::<Description>.into()),
+      ],
+    };
+
+    let report = SectionReport::new(&mut section);
+    assert_eq!(report.label, "Environment");
+    assert_eq!(report.status, Status::Error);
+    assert_eq!(
+      report.items,
+      [
+        ItemReport {
+          status: Status::Neutral,
+          name: "CSP".into(),
+          value: "default-src 'self' ipc:".into(),
+          hint: None,
+        },
+        ItemReport {
+          status: Status::Error,
+          name: "rustc".into(),
+          value: "not installed!".into(),
+          hint: Some("Visit https://rustup.rs/".into()),
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn display_description() {
+    colored::control::set_override(false);
+
+    assert_eq!(
+      Description::new("tauri", "2.0.0").icon("🦀").to_string(),
+      "tauri 🦀: 2.0.0"
+    );
+    assert_eq!(
+      Description::new("MSVC", "Build Tools 2019\nBuild Tools 2022")
+        .hint("Update them\nfrom the installer")
+        .to_string(),
+      "MSVC:\n        - Build Tools 2019\n        - Build Tools 2022\n      Update them\n      from the installer"
+    );
+  }
+}
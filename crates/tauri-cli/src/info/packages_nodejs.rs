@@ -2,8 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use super::SectionItem;
-use super::VersionMetadata;
+use super::{Description, SectionItem, VersionMetadata};
 use colored::Colorize;
 use serde::Deserialize;
 use std::path::PathBuf;
@@ -109,7 +108,7 @@ pub fn package_manager(frontend_dir: &PathBuf) -> PackageManager {
   let found = PackageManager::all_from_project(frontend_dir);
 
   if found.is_empty() {
-    println!(
+    eprintln!(
       "{}: no lock files found, defaulting to npm",
       "WARNING".yellow()
     );
@@ -119,7 +118,7 @@ pub fn package_manager(frontend_dir: &PathBuf) -> PackageManager {
   let pkg_manager = found[0];
 
   if found.len() > 1 {
-    println!(
+    eprintln!(
           "{}: Only one package manager should be used, but found {}.\n         Please remove unused package manager lock files, will use {} for now!",
           "WARNING".yellow(),
           found.iter().map(ToString::to_string).collect::<Vec<_>>().join(" and "),
@@ -168,13 +167,11 @@ pub fn nodejs_section_item(
       .unwrap_or_default()
       .unwrap_or_default();
 
-    if version.is_empty() {
-      format!("{} {}: not installed!", package, " ⱼₛ".black().on_yellow())
+    let value = if version.is_empty() {
+      "not installed!".to_string()
     } else {
       format!(
-        "{} {}: {}{}",
-        package,
-        " ⱼₛ".black().on_yellow(),
+        "{}{}",
         version,
         if !(version.is_empty() || latest_ver.is_empty()) {
           let version = semver::Version::parse(version.as_str()).unwrap();
@@ -189,7 +186,9 @@ pub fn nodejs_section_item(
           "".into()
         }
       )
-    }
-    .into()
+    };
+    Description::new(&package, value)
+      .icon(" ⱼₛ".black().on_yellow().to_string())
+      .into()
   })
 }
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use super::{ActionResult, SectionItem};
+use super::{ActionResult, Description, SectionItem};
 use crate::helpers::cargo_manifest::{
   cargo_manifest_and_lock, crate_latest_version, crate_version, CrateVersion,
 };
@@ -35,25 +35,29 @@ pub fn items(frontend_dir: Option<&PathBuf>, tauri_dir: Option<&Path>) -> Vec<Se
           let (package, version) = out.split_once(' ').unwrap_or_default();
           let version = version.strip_suffix('\n').unwrap_or(version);
           let latest_version = crate_latest_version(package).unwrap_or_default();
-          format!(
-            "{package} 🦀: {version}{}",
-            if !(version.is_empty() || latest_version.is_empty()) {
-              let current_version = semver::Version::parse(version).unwrap();
-              let target_version = semver::Version::parse(latest_version.as_str()).unwrap();
+          Description::new(
+            package,
+            format!(
+              "{version}{}",
+              if !(version.is_empty() || latest_version.is_empty()) {
+                let current_version = semver::Version::parse(version).unwrap();
+                let target_version = semver::Version::parse(latest_version.as_str()).unwrap();
 
-              if current_version < target_version {
-                format!(
-                  " ({}, latest: {})",
-                  "outdated".yellow(),
-                  latest_version.green()
-                )
+                if current_version < target_version {
+                  format!(
+                    " ({}, latest: {})",
+                    "outdated".yellow(),
+                    latest_version.green()
+                  )
+                } else {
+                  "".into()
+                }
               } else {
                 "".into()
               }
-            } else {
-              "".into()
-            }
+            ),
           )
+          .icon("🦀")
           .into()
         } else {
           ActionResult::None
@@ -88,13 +92,17 @@ pub fn rust_section_item(dep: &str, crate_version: CrateVersion) -> SectionItem
     _ => None,
   };
 
-  SectionItem::new().description(format!(
-    "{} {}: {}{}",
-    dep,
-    "🦀",
-    crate_version,
-    version_suffix
-      .map(|s| format!(",{s}"))
-      .unwrap_or_else(|| "".into())
-  ))
+  SectionItem::new().description(
+    Description::new(
+      dep,
+      format!(
+        "{}{}",
+        crate_version,
+        version_suffix
+          .map(|s| format!(",{s}"))
+          .unwrap_or_else(|| "".into())
+      ),
+    )
+    .icon("🦀"),
+  )
 }
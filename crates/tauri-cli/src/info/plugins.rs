@@ -40,6 +40,27 @@ impl InstalledPackages {
       })
       .collect()
   }
+
+  /// Errors if the NPM package and Rust crate versions are not on the same major/minor releases.
+  pub fn check_mismatched(&self) -> crate::Result<()> {
+    let mismatched_packages = self.mismatched();
+    if mismatched_packages.is_empty() {
+      return Ok(());
+    }
+    let mismatched_text = mismatched_packages
+      .iter()
+      .map(
+        |InstalledPackage {
+           crate_name,
+           crate_version,
+           npm_name,
+           npm_version,
+         }| format!("{crate_name} (v{crate_version}) : {npm_name} (v{npm_version})"),
+      )
+      .collect::<Vec<_>>()
+      .join("\n");
+    Err(Error::GenericError(format!("Found version mismatched Tauri packages. Make sure the NPM package and Rust crate versions are on the same major/minor releases:\n{mismatched_text}")))
+  }
 }
 
 pub fn installed_tauri_packages(
@@ -139,26 +160,10 @@ pub fn items(
 }
 
 pub fn check_mismatched_packages(frontend_dir: &Path, tauri_path: &Path) -> crate::Result<()> {
-  let installed_packages = installed_tauri_packages(
+  installed_tauri_packages(
     frontend_dir,
     tauri_path,
     PackageManager::from_project(frontend_dir),
-  );
-  let mismatched_packages = installed_packages.mismatched();
-  if mismatched_packages.is_empty() {
-    return Ok(());
-  }
-  let mismatched_text = mismatched_packages
-    .iter()
-    .map(
-      |InstalledPackage {
-         crate_name,
-         crate_version,
-         npm_name,
-         npm_version,
-       }| format!("{crate_name} (v{crate_version}) : {npm_name} (v{npm_version})"),
-    )
-    .collect::<Vec<_>>()
-    .join("\n");
-  Err(Error::GenericError(format!("Found version mismatched Tauri packages. Make sure the NPM package and Rust crate versions are on the same major/minor releases:\n{mismatched_text}")))
+  )
+  .check_mismatched()
 }
@@ -25,7 +25,11 @@ use tauri_bundler::{
   IosSettings, MacOsSettings, PackageSettings, Position, RpmSettings, Size, UpdaterSettings,
   WindowsSettings,
 };
-use tauri_utils::config::{parse::is_configuration_file, DeepLinkProtocol, RunnerConfig, Updater};
+use tauri_utils::config::{
+  dev::{is_runtime_change, CHANNEL_ENV_VAR},
+  parse::is_configuration_file,
+  DeepLinkProtocol, RunnerConfig, Updater,
+};
 
 use super::{AppSettings, DevProcess, ExitReason};
 use crate::{
@@ -40,10 +44,12 @@ use tauri_utils::{display_path, platform::Target as TargetPlatform};
 
 mod cargo_config;
 mod desktop;
+mod dev_config;
 pub mod installation;
 pub mod manifest;
 use crate::helpers::config::custom_sign_settings;
 use cargo_config::Config as CargoConfig;
+use dev_config::{config_update, is_capability_path, ConfigChannel};
 use manifest::{rewrite_manifest, Manifest};
 
 #[derive(Debug, Default, Clone)]
@@ -64,7 +70,7 @@ impl From<crate::build::Options> for Options {
     Self {
       runner: options.runner,
       debug: options.debug,
-      target: options.target,
+      target: options.targets.into_iter().next(),
       features: options.features,
       args: options.args,
       config: options.config,
@@ -219,10 +225,14 @@ impl Rust {
       Ok(())
     } else {
       let merge_configs = options.config.iter().map(|c| &c.0).collect::<Vec<_>>();
+      let config_channel = ConfigChannel::new()?;
+      // read by the app on startup to receive the configuration changes that do not require a rebuild
+      std::env::set_var(CHANNEL_ENV_VAR, config_channel.address().to_string());
       self.run_dev_watcher(
         config,
         &options.additional_watch_folders,
         &merge_configs,
+        Some(&config_channel),
         |rust: &mut Rust, _config| {
           let on_exit = on_exit.clone();
           rust
@@ -282,6 +292,7 @@ impl Rust {
       config,
       &options.additional_watch_folders,
       &merge_configs,
+      None,
       |_rust: &mut Rust, config| runner(config),
       dirs,
     )
@@ -513,6 +524,7 @@ impl Rust {
     config: &mut ConfigMetadata,
     additional_watch_folders: &[PathBuf],
     merge_configs: &[&serde_json::Value],
+    config_channel: Option<&ConfigChannel>,
     run: F,
     dirs: &Dirs,
   ) -> crate::Result<()> {
@@ -564,16 +576,27 @@ impl Rust {
 
           if let Some(event_path) = event.paths.first() {
             if !ignore_matcher.is_ignore(event_path, event_path.is_dir()) {
-              if is_configuration_file(self.app_settings.target_platform, event_path)
-                && reload_config(config, merge_configs, dirs.tauri).is_ok()
-              {
-                let (manifest, modified) = rewrite_manifest(config, dirs.tauri)?;
-                if modified {
-                  *self.app_settings.manifest.lock().unwrap() = manifest;
-                  // no need to run the watcher logic, the manifest was modified
-                  // and it will trigger the watcher again
-                  continue;
+              if is_configuration_file(self.app_settings.target_platform, event_path) {
+                let previous_config = Config::clone(config);
+                if reload_config(config, merge_configs, dirs.tauri).is_ok() {
+                  if is_runtime_change(&previous_config, config)
+                    && send_config_update(config_channel, config, dirs, event_path)
+                  {
+                    continue;
+                  }
+
+                  let (manifest, modified) = rewrite_manifest(config, dirs.tauri)?;
+                  if modified {
+                    *self.app_settings.manifest.lock().unwrap() = manifest;
+                    // no need to run the watcher logic, the manifest was modified
+                    // and it will trigger the watcher again
+                    continue;
+                  }
                 }
+              } else if is_capability_path(event_path, dirs.tauri)
+                && send_config_update(config_channel, config, dirs, event_path)
+              {
+                continue;
               }
 
               log::info!(
@@ -582,6 +605,9 @@ impl Rust {
               );
 
               child.kill().context("failed to kill app process")?;
+              if let Some(config_channel) = config_channel {
+                config_channel.disconnect();
+              }
 
               // wait for the process to exit
               // note that on mobile, kill() already waits for the process to exit (duct implementation)
@@ -595,6 +621,38 @@ impl Rust {
   }
 }
 
+/// Applies the configuration and capabilities to the running app, returns `false` if it must be rebuilt instead.
+fn send_config_update(
+  config_channel: Option<&ConfigChannel>,
+  config: &Config,
+  dirs: &Dirs,
+  event_path: &Path,
+) -> bool {
+  let Some(config_channel) = config_channel.filter(|channel| channel.is_connected()) else {
+    return false;
+  };
+
+  let update = match config_update(config, dirs.tauri) {
+    Ok(update) => update,
+    Err(e) => {
+      log::warn!("Failed to read the capabilities, rebuilding application: {e}");
+      return false;
+    }
+  };
+
+  log::info!(
+    "File {} changed. Applying configuration to the running application...",
+    display_path(event_path.strip_prefix(dirs.frontend).unwrap_or(event_path))
+  );
+  match config_channel.send(&update) {
+    Ok(()) => true,
+    Err(e) => {
+      log::warn!("Failed to apply the configuration, rebuilding application: {e}");
+      false
+    }
+  }
+}
+
 // Taken from https://github.com/rust-lang/cargo/blob/70898e522116f6c23971e2a554b2dc85fd4c84cd/src/cargo/util/toml/mod.rs#L1008-L1065
 /// Enum that allows for the parsing of `field.workspace = true` in a Cargo.toml
 ///
@@ -850,12 +908,32 @@ impl AppSettings for RustAppSettings {
       None
     };
 
+    let mut bundle_config = config.bundle.clone();
+    if config.build.asset_archive {
+      // the build script writes the frontend archive to the target directory,
+      // the app reads it from the path computed by `tauri_utils::assets::archive::resource_path`
+      let file_name = tauri_utils::assets::archive::file_name(&self.cargo_package_settings.name);
+      let archive = self.out_dir(options, tauri_dir)?.join(&file_name);
+      let source = display_path(tauri_utils::assets::archive::relative_path(
+        &archive, tauri_dir,
+      ));
+      match bundle_config
+        .resources
+        .get_or_insert_with(|| BundleResources::List(Vec::new()))
+      {
+        BundleResources::List(paths) => paths.push(source),
+        BundleResources::Map(map) => {
+          map.insert(source, file_name);
+        }
+      }
+    }
+
     let mut settings = tauri_config_to_bundle_settings(
       self,
       features,
       config,
       tauri_dir,
-      config.bundle.clone(),
+      bundle_config,
       updater_settings,
       arch64bits,
     )?;
@@ -1162,6 +1240,11 @@ impl RustAppSettings {
     })
   }
 
+  /// The target triple the application is compiled for.
+  pub fn target_triple(&self) -> &str {
+    &self.target_triple
+  }
+
   fn target<'a>(&'a self, options: &'a Options) -> Option<&'a str> {
     options
       .target
@@ -1172,6 +1255,17 @@ impl RustAppSettings {
   pub fn out_dir(&self, options: &Options, tauri_dir: &Path) -> crate::Result<PathBuf> {
     get_target_dir(self.target(options), options, tauri_dir)
   }
+
+  /// The file the build script writes the isolation schema of a development build to.
+  pub fn dev_isolation_schema_path(
+    &self,
+    options: &Options,
+    tauri_dir: &Path,
+  ) -> crate::Result<PathBuf> {
+    let file_name =
+      tauri_utils::pattern::dev_isolation_schema_file_name(&self.cargo_package_settings.name);
+    Ok(self.out_dir(options, tauri_dir)?.join(file_name))
+  }
 }
 
 #[derive(Deserialize)]
@@ -0,0 +1,320 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Applies configuration changes to the running application without rebuilding it.
+//!
+//! See [`tauri_utils::config::dev`] for the protocol.
+
+use std::{
+  collections::BTreeMap,
+  ffi::OsStr,
+  io::{BufRead, BufReader, Write},
+  net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+  path::Path,
+  sync::{Arc, Mutex},
+  time::Duration,
+};
+
+use tauri_utils::{
+  acl::{
+    capability::{Capability, CapabilityFile},
+    get_capabilities,
+  },
+  config::{
+    dev::{ConfigUpdate, ConfigUpdateReply},
+    parse::EXTENSIONS_SUPPORTED,
+    Config,
+  },
+};
+
+use crate::error::{Context, Error};
+
+/// The capabilities directory, relative to the Tauri directory.
+const CAPABILITIES_DIR: &str = "capabilities";
+/// The folder of the capabilities directory that contains the generated schemas.
+const CAPABILITIES_SCHEMA_FOLDER_NAME: &str = "schemas";
+
+/// How long the application has to apply an update, it recreates the windows that changed.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The connection of the running application.
+struct Connection {
+  writer: TcpStream,
+  reader: BufReader<TcpStream>,
+}
+
+impl Connection {
+  fn new(stream: TcpStream) -> std::io::Result<Self> {
+    stream.set_read_timeout(Some(REPLY_TIMEOUT))?;
+    Ok(Self {
+      reader: BufReader::new(stream.try_clone()?),
+      writer: stream,
+    })
+  }
+
+  fn send(&mut self, update: &ConfigUpdate) -> crate::Result<()> {
+    let mut line = serde_json::to_vec(update).expect("failed to serialize config update");
+    line.push(b'\n');
+    self
+      .writer
+      .write_all(&line)
+      .and_then(|()| self.writer.flush())
+      .context("failed to send the configuration to the application")?;
+
+    let mut reply = String::new();
+    let read = self
+      .reader
+      .read_line(&mut reply)
+      .context("the application did not reply to the configuration update")?;
+    if read == 0 {
+      return Err(Error::GenericError(
+        "the application closed the dev configuration channel".into(),
+      ));
+    }
+
+    match serde_json::from_str::<ConfigUpdateReply>(&reply)
+      .context("invalid reply to the configuration update")?
+    {
+      ConfigUpdateReply::Applied => Ok(()),
+      ConfigUpdateReply::Failed { error } => Err(Error::GenericError(error)),
+    }
+  }
+}
+
+/// The local socket the running application connects to.
+pub struct ConfigChannel {
+  address: SocketAddr,
+  connection: Arc<Mutex<Option<Connection>>>,
+}
+
+impl ConfigChannel {
+  pub fn new() -> crate::Result<Self> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+      .context("failed to bind the dev configuration channel")?;
+    let address = listener
+      .local_addr()
+      .context("failed to get the address of the dev configuration channel")?;
+    let connection = Arc::new(Mutex::new(None));
+
+    let connection_ = connection.clone();
+    std::thread::spawn(move || {
+      // the latest application that connected is the one currently running
+      for stream in listener.incoming().flatten() {
+        log::debug!("Application connected to the dev configuration channel");
+        match Connection::new(stream) {
+          Ok(connection) => {
+            connection_.lock().unwrap().replace(connection);
+          }
+          Err(e) => log::debug!("Failed to set up the dev configuration channel: {e}"),
+        }
+      }
+    });
+
+    Ok(Self {
+      address,
+      connection,
+    })
+  }
+
+  pub fn address(&self) -> SocketAddr {
+    self.address
+  }
+
+  /// Whether an application is connected.
+  pub fn is_connected(&self) -> bool {
+    self.connection.lock().unwrap().is_some()
+  }
+
+  /// Sends an update to the running application and waits for it to be applied.
+  ///
+  /// The connection is dropped if the update could not be delivered or applied,
+  /// since the application is rebuilt then.
+  pub fn send(&self, update: &ConfigUpdate) -> crate::Result<()> {
+    let mut connection = self.connection.lock().unwrap();
+    let Some(stream) = connection.as_mut() else {
+      return Err(Error::GenericError(
+        "the application is not connected to the dev configuration channel".into(),
+      ));
+    };
+
+    let result = stream.send(update);
+    if result.is_err() {
+      connection.take();
+    }
+    result
+  }
+
+  /// Forgets the current connection, must be called when the application is killed.
+  pub fn disconnect(&self) {
+    self.connection.lock().unwrap().take();
+  }
+}
+
+/// Whether the path is in the capabilities directory.
+pub fn is_capability_path(path: &Path, tauri_dir: &Path) -> bool {
+  path.starts_with(tauri_dir.join(CAPABILITIES_DIR))
+}
+
+fn is_capability_file(path: &Path) -> bool {
+  path
+    .extension()
+    .and_then(OsStr::to_str)
+    .is_some_and(|e| EXTENSIONS_SUPPORTED.contains(&e))
+    && path
+      .parent()
+      .and_then(Path::file_name)
+      .is_some_and(|name| name != CAPABILITIES_SCHEMA_FOLDER_NAME)
+}
+
+/// Reads the capabilities of the application the same way `tauri-build` does with its default options.
+fn load_capabilities(
+  config: &Config,
+  tauri_dir: &Path,
+) -> crate::Result<BTreeMap<String, Capability>> {
+  let mut capabilities = BTreeMap::new();
+  for entry in walkdir::WalkDir::new(tauri_dir.join(CAPABILITIES_DIR))
+    .into_iter()
+    .flatten()
+  {
+    let path = entry.path();
+    if !entry.file_type().is_file() || !is_capability_file(path) {
+      continue;
+    }
+
+    let capability = CapabilityFile::load(path)
+      .with_context(|| format!("failed to read capability {}", path.display()))?;
+    let list = match capability {
+      CapabilityFile::Capability(capability) => vec![capability],
+      CapabilityFile::List(capabilities) | CapabilityFile::NamedList { capabilities } => {
+        capabilities
+      }
+    };
+    for capability in list {
+      if capabilities.contains_key(&capability.identifier) {
+        return Err(Error::GenericError(format!(
+          "capability with identifier `{}` already exists",
+          capability.identifier
+        )));
+      }
+      capabilities.insert(capability.identifier.clone(), capability);
+    }
+  }
+
+  get_capabilities(config, capabilities, None).map_err(|e| Error::GenericError(format!("{e:#}")))
+}
+
+/// The update to send to the application for the given configuration.
+pub fn config_update(config: &Config, tauri_dir: &Path) -> crate::Result<ConfigUpdate> {
+  Ok(ConfigUpdate {
+    windows: config.app.windows.clone(),
+    capabilities: load_capabilities(config, tauri_dir)?,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::time::Instant;
+  use tauri_utils::config::CapabilityEntry;
+
+  fn capability(identifier: &str) -> String {
+    format!(r#"{{ "identifier": "{identifier}", "windows": ["main"], "permissions": [] }}"#)
+  }
+
+  fn write(path: &Path, contents: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+  }
+
+  #[test]
+  fn load_capabilities() {
+    let tauri_dir = tempfile::tempdir().unwrap();
+    let capabilities_dir = tauri_dir.path().join(CAPABILITIES_DIR);
+    write(&capabilities_dir.join("main.json"), &capability("main"));
+    write(
+      &capabilities_dir.join("nested/list.json"),
+      &format!("[{}, {}]", capability("first"), capability("second")),
+    );
+    // the generated schemas and other files are not capabilities
+    write(
+      &capabilities_dir
+        .join(CAPABILITIES_SCHEMA_FOLDER_NAME)
+        .join("desktop-schema.json"),
+      "{}",
+    );
+    write(&capabilities_dir.join("README.md"), "# Capabilities");
+
+    let mut config = Config::default();
+    let capabilities = super::load_capabilities(&config, tauri_dir.path()).unwrap();
+    assert_eq!(
+      capabilities.keys().collect::<Vec<_>>(),
+      ["first", "main", "second"]
+    );
+
+    // only the capabilities enabled in the configuration are used
+    config.app.security.capabilities = vec![CapabilityEntry::Reference("main".into())];
+    let capabilities = super::load_capabilities(&config, tauri_dir.path()).unwrap();
+    assert_eq!(capabilities.keys().collect::<Vec<_>>(), ["main"]);
+
+    config.app.security.capabilities = vec![CapabilityEntry::Reference("missing".into())];
+    assert!(super::load_capabilities(&config, tauri_dir.path()).is_err());
+
+    write(
+      &capabilities_dir.join("duplicate.json"),
+      &capability("main"),
+    );
+    let error = super::load_capabilities(&Config::default(), tauri_dir.path()).unwrap_err();
+    assert!(error.to_string().contains("already exists"), "{error}");
+  }
+
+  /// Connects a fake application to the channel, answering each update with the given replies.
+  fn connect(channel: &ConfigChannel, replies: Vec<ConfigUpdateReply>) {
+    let stream = TcpStream::connect(channel.address()).unwrap();
+    std::thread::spawn(move || {
+      let mut writer = stream.try_clone().unwrap();
+      let mut lines = BufReader::new(stream).lines();
+      for reply in replies {
+        let line = lines.next().unwrap().unwrap();
+        serde_json::from_str::<ConfigUpdate>(&line).unwrap();
+        writeln!(writer, "{}", serde_json::to_string(&reply).unwrap()).unwrap();
+      }
+    });
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !channel.is_connected() {
+      assert!(Instant::now() < deadline, "the application did not connect");
+      std::thread::sleep(Duration::from_millis(10));
+    }
+  }
+
+  #[test]
+  fn send() {
+    let update = ConfigUpdate {
+      windows: Vec::new(),
+      capabilities: BTreeMap::new(),
+    };
+    let channel = ConfigChannel::new().unwrap();
+    assert!(channel.send(&update).is_err());
+
+    connect(
+      &channel,
+      vec![
+        ConfigUpdateReply::Applied,
+        ConfigUpdateReply::Failed {
+          error: "unknown permission".into(),
+        },
+      ],
+    );
+    channel.send(&update).unwrap();
+    let error = channel.send(&update).unwrap_err();
+    assert_eq!(error.to_string(), "unknown permission");
+    // the application is rebuilt after a failure
+    assert!(!channel.is_connected());
+
+    // the application exited
+    connect(&channel, Vec::new());
+    assert!(channel.send(&update).is_err());
+    assert!(!channel.is_connected());
+  }
+}
@@ -244,6 +244,12 @@ where
       verbosity_level(verbosity_number.saturating_sub(1)).to_level_filter(),
     )
     .format(|f, record| {
+      if record.level() == Level::Warn {
+        helpers::message::emit(helpers::message::Message::Warning {
+          message: &record.args().to_string(),
+        });
+      }
+
       let mut is_command_output = false;
       if let Some(action) = record.key_values().get("action".into()) {
         let action = action.to_cow_str().unwrap();
@@ -327,7 +333,12 @@ pub trait CommandExt {
 impl CommandExt for Command {
   fn piped(&mut self) -> std::io::Result<ExitStatus> {
     self.stdin(os_pipe::dup_stdin()?);
-    self.stdout(os_pipe::dup_stdout()?);
+    // stdout is reserved to the messages of `--message-format json`
+    if helpers::message::is_json() {
+      self.stdout(os_pipe::dup_stderr()?);
+    } else {
+      self.stdout(os_pipe::dup_stdout()?);
+    }
     self.stderr(os_pipe::dup_stderr()?);
 
     let program = self.get_program().to_string_lossy().into_owned();
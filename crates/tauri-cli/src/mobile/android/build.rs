@@ -96,7 +96,7 @@ impl From<Options> for BuildOptions {
     Self {
       runner: None,
       debug: options.debug,
-      target: None,
+      targets: Vec::new(),
       features: options.features,
       bundles: None,
       no_bundle: false,
@@ -106,6 +106,7 @@ impl From<Options> for BuildOptions {
       skip_stapling: false,
       ignore_version_mismatches: options.ignore_version_mismatches,
       no_sign: false,
+      message_format: Default::default(),
     }
   }
 }
@@ -151,9 +152,9 @@ pub fn run(
         .unwrap_or(Target::DEFAULT_KEY),
     )
     .unwrap();
-  build_options.target = Some(first_target.triple.into());
+  build_options.targets = vec![first_target.triple.into()];
 
-  let interface = AppInterface::new(tauri_config, build_options.target.clone(), dirs.tauri)?;
+  let interface = AppInterface::new(tauri_config, Some(first_target.triple.into()), dirs.tauri)?;
   interface.build_options(&mut build_options.args, &mut build_options.features, true);
 
   let app = get_app(MobileTarget::Android, tauri_config, &interface, dirs.tauri);
@@ -257,7 +258,7 @@ fn run_build(
 
   let interface_options = InterfaceOptions {
     debug: build_options.debug,
-    target: build_options.target.clone(),
+    target: build_options.targets.first().cloned(),
     args: build_options.args.clone(),
     ..Default::default()
   };
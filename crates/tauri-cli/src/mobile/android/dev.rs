@@ -124,6 +124,8 @@ impl From<Options> for DevOptions {
       no_dev_server_wait: options.no_dev_server_wait,
       no_dev_server: options.no_dev_server,
       port: options.port,
+      dev_server_proxy: false,
+      dev_server_https: false,
       release_mode: options.release_mode,
       host: options.host.0.unwrap_or_default(),
     }
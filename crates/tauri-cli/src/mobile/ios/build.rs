@@ -145,7 +145,7 @@ impl From<Options> for BuildOptions {
     Self {
       runner: None,
       debug: options.debug,
-      target: None,
+      targets: Vec::new(),
       features: options.features,
       bundles: None,
       no_bundle: false,
@@ -155,6 +155,7 @@ impl From<Options> for BuildOptions {
       skip_stapling: false,
       ignore_version_mismatches: options.ignore_version_mismatches,
       no_sign: false,
+      message_format: Default::default(),
     }
   }
 }
@@ -174,27 +175,26 @@ pub fn command(options: Options, noise_level: NoiseLevel) -> Result<BuiltApplica
 
 pub fn run(options: Options, noise_level: NoiseLevel, dirs: &Dirs) -> Result<BuiltApplication> {
   let mut build_options: BuildOptions = options.clone().into();
-  build_options.target = Some(
-    Target::all()
-      .get(
-        options
-          .targets
-          .as_ref()
-          .and_then(|t| t.first())
-          .map(|t| t.as_str())
-          .unwrap_or(Target::DEFAULT_KEY),
-      )
-      .unwrap()
-      .triple
-      .into(),
-  );
+  let target = Target::all()
+    .get(
+      options
+        .targets
+        .as_ref()
+        .and_then(|t| t.first())
+        .map(|t| t.as_str())
+        .unwrap_or(Target::DEFAULT_KEY),
+    )
+    .unwrap()
+    .triple
+    .to_string();
+  build_options.targets = vec![target.clone()];
 
   let tauri_config = get_tauri_config(
     tauri_utils::platform::Target::Ios,
     &options.config.iter().map(|c| &c.0).collect::<Vec<_>>(),
     dirs.tauri,
   )?;
-  let interface = AppInterface::new(&tauri_config, build_options.target.clone(), dirs.tauri)?;
+  let interface = AppInterface::new(&tauri_config, Some(target), dirs.tauri)?;
   interface.build_options(&mut build_options.args, &mut build_options.features, true);
 
   let app = get_app(MobileTarget::Ios, &tauri_config, &interface, dirs.tauri);
@@ -373,7 +373,7 @@ fn run_build(
   let out_dir = app_settings.out_dir(
     &InterfaceOptions {
       debug: build_options.debug,
-      target: build_options.target.clone(),
+      target: build_options.targets.first().cloned(),
       args: build_options.args.clone(),
       ..Default::default()
     },
@@ -132,6 +132,8 @@ impl From<Options> for DevOptions {
       no_dev_server: options.no_dev_server,
       no_dev_server_wait: options.no_dev_server_wait,
       port: options.port,
+      dev_server_proxy: false,
+      dev_server_https: false,
       host: options.host.0.unwrap_or_default(),
     }
   }
@@ -9,14 +9,16 @@ use std::{ffi::OsStr, str::FromStr};
 
 use crate::{
   embedded_assets::{
-    ensure_out_dir, AssetOptions, CspHashes, EmbeddedAssets, EmbeddedAssetsResult,
+    ensure_out_dir, target_dir, AssetOptions, CspHashes, EmbeddedAssets, EmbeddedAssetsError,
+    EmbeddedAssetsResult,
   },
   image::CachedIcon,
+  snapshot::ContextSnapshot,
 };
 use base64::Engine;
 use proc_macro2::TokenStream;
 use quote::quote;
-use sha2::{Digest, Sha256};
+use sha2::{Digest, Sha256, Sha384};
 use syn::Expr;
 use tauri_utils::{
   acl::{
@@ -24,8 +26,11 @@ use tauri_utils::{
     CAPABILITIES_FILE_NAME,
   },
   assets::AssetKey,
-  config::{Config, FrontendDist, PatternKind},
-  html::{inject_nonce_token, parse as parse_html, serialize_node as serialize_html_node, NodeRef},
+  config::{BundleResources, Config, FrontendDist, PatternKind},
+  html::{
+    inject_nonce_token, inject_subresource_integrity, parse as parse_html,
+    serialize_node as serialize_html_node, NodeRef,
+  },
   platform::Target,
   tokens::{map_lit, str_lit},
 };
@@ -45,48 +50,63 @@ pub struct ContextData {
 }
 
 fn inject_script_hashes(document: &NodeRef, key: &AssetKey, csp_hashes: &mut CspHashes) {
-  if let Ok(inline_script_elements) = document.select("script:not(:empty)") {
-    let mut scripts = Vec::new();
-    for inline_script_el in inline_script_elements {
-      let script = inline_script_el.as_node().text_contents();
-      let mut hasher = Sha256::new();
-      hasher.update(tauri_utils::html::normalize_script_for_csp(
-        script.as_bytes(),
-      ));
-      let hash = hasher.finalize();
-      scripts.push(format!(
-        "'sha256-{}'",
-        base64::engine::general_purpose::STANDARD.encode(hash)
-      ));
-    }
-    csp_hashes
-      .inline_scripts
-      .entry(key.clone().into())
-      .or_default()
-      .append(&mut scripts);
+  csp_hashes
+    .inline_scripts
+    .entry(key.clone().into())
+    .or_default()
+    .extend(tauri_utils::html::inline_script_hashes(document));
+}
+
+/// Computes the `integrity` attribute value of a file referenced by the HTML asset at `path`.
+///
+/// Only local files are considered: URLs with a scheme or host are ignored.
+fn subresource_integrity_metadata(key: &AssetKey, path: &Path, url: &str) -> Option<String> {
+  if url.is_empty() || url.starts_with("//") || url.contains(':') {
+    return None;
   }
+  let url = url.split(['?', '#']).next().unwrap_or_default();
+
+  let file = if let Some(absolute) = url.strip_prefix('/') {
+    // the asset root is `path` without the components of the asset key
+    let depth = key.as_ref().matches('/').count();
+    path.ancestors().nth(depth)?.join(absolute)
+  } else {
+    path.parent()?.join(url)
+  };
+
+  let contents = std::fs::read(file).ok()?;
+  Some(format!(
+    "sha384-{}",
+    base64::engine::general_purpose::STANDARD.encode(Sha384::digest(contents))
+  ))
 }
 
 fn map_core_assets(
   options: &AssetOptions,
 ) -> impl Fn(&AssetKey, &Path, &mut Vec<u8>, &mut CspHashes) -> EmbeddedAssetsResult<()> {
   let csp = options.csp;
+  let subresource_integrity = options.subresource_integrity;
   let dangerous_disable_asset_csp_modification =
     options.dangerous_disable_asset_csp_modification.clone();
   move |key, path, input, csp_hashes| {
-    if path.extension() == Some(OsStr::new("html")) {
-      #[allow(clippy::collapsible_if)]
-      if csp {
-        let document = parse_html(String::from_utf8_lossy(input).into_owned());
+    if path.extension() == Some(OsStr::new("html")) && (csp || subresource_integrity) {
+      let document = parse_html(String::from_utf8_lossy(input).into_owned());
 
+      if csp {
         inject_nonce_token(&document, &dangerous_disable_asset_csp_modification);
 
         if dangerous_disable_asset_csp_modification.can_modify("script-src") {
           inject_script_hashes(&document, key, csp_hashes);
         }
+      }
 
-        *input = serialize_html_node(&document);
+      if subresource_integrity {
+        inject_subresource_integrity(&document, |url| {
+          subresource_integrity_metadata(key, path, url)
+        });
       }
+
+      *input = serialize_html_node(&document);
     }
     Ok(())
   }
@@ -132,8 +152,59 @@ fn map_isolation(
   }
 }
 
+/// The path the asset archive is written to, and its path relative to the resource directory of the bundled app.
+///
+/// The archive is written to the target directory so the CLI can add it to the bundle resources.
+fn asset_archive_path(
+  config: &Config,
+  config_parent: &Path,
+) -> EmbeddedAssetsResult<(PathBuf, String)> {
+  let package_name =
+    std::env::var("CARGO_PKG_NAME").map_err(|_| EmbeddedAssetsError::EnvVar("CARGO_PKG_NAME"))?;
+  let target_dir = target_dir()?;
+  let file_name = tauri_utils::assets::archive::file_name(&package_name);
+  let path = target_dir.join(&file_name);
+
+  let resource_path = match &config.bundle.resources {
+    // the CLI maps the archive to the root of the resource directory
+    Some(BundleResources::Map(_)) => file_name,
+    _ => tauri_utils::assets::archive::resource_path(&path, config_parent)
+      .display()
+      .to_string()
+      .replace('\\', "/"),
+  };
+
+  Ok((path, resource_path))
+}
+
+/// Writes the isolation schema of a development build to the target directory,
+/// where the dev server reads it to allow the isolation iframe in its CSP.
+#[cfg(feature = "isolation")]
+fn write_dev_isolation_schema(schema: &str) -> EmbeddedAssetsResult<()> {
+  use std::io::Write;
+
+  let package_name =
+    std::env::var("CARGO_PKG_NAME").map_err(|_| EmbeddedAssetsError::EnvVar("CARGO_PKG_NAME"))?;
+  let path = target_dir()?.join(tauri_utils::pattern::dev_isolation_schema_file_name(
+    &package_name,
+  ));
+  crate::embedded_assets::write_cache_file(&path, |file| file.write_all(schema.as_bytes()))
+}
+
 /// Build a `tauri::Context` for including in application code.
 pub fn context_codegen(data: ContextData) -> EmbeddedAssetsResult<TokenStream> {
+  context_codegen_with_snapshot(data, None)
+}
+
+/// Build a `tauri::Context` for including in application code,
+/// writing a snapshot of it to `snapshot_path` (relative to the config directory) if set.
+///
+/// The snapshot is a stable JSON description of the config, embedded assets, CSP, resolved ACL and icons,
+/// suitable for committing and reviewing changes to the generated code.
+pub fn context_codegen_with_snapshot(
+  data: ContextData,
+  snapshot_path: Option<&Path>,
+) -> EmbeddedAssetsResult<TokenStream> {
   let ContextData {
     dev,
     config,
@@ -154,13 +225,17 @@ pub fn context_codegen(data: ContextData) -> EmbeddedAssetsResult<TokenStream> {
 
   let mut options = AssetOptions::new(config.app.security.pattern.clone())
     .freeze_prototype(config.app.security.freeze_prototype)
+    .subresource_integrity(config.app.security.subresource_integrity)
+    .verify_integrity(config.app.security.verify_asset_integrity)
     .dangerous_disable_asset_csp_modification(
       config
         .app
         .security
         .dangerous_disable_asset_csp_modification
         .clone(),
-    );
+    )
+    .compression(config.build.asset_compression.clone());
+
   let csp = if dev {
     config
       .app
@@ -174,6 +249,17 @@ pub fn context_codegen(data: ContextData) -> EmbeddedAssetsResult<TokenStream> {
   if csp.is_some() {
     options = options.with_csp();
   }
+  let mut snapshot = snapshot_path
+    .is_some()
+    .then(|| ContextSnapshot::new(&config, csp));
+  if config.app.security.encrypt_assets {
+    #[cfg(feature = "asset-encryption")]
+    {
+      options = options.encrypt(true);
+    }
+    #[cfg(not(feature = "asset-encryption"))]
+    return Err(EmbeddedAssetsError::AssetEncryptionDisabled);
+  }
 
   let assets = if let Some(assets) = assets {
     quote!(#assets)
@@ -205,7 +291,16 @@ pub fn context_codegen(data: ContextData) -> EmbeddedAssetsResult<TokenStream> {
       },
       None => Default::default(),
     };
-    quote!(#assets)
+    if let Some(snapshot) = &mut snapshot {
+      snapshot.assets(&assets);
+    }
+    // mobile apps cannot map their bundle resources, so the assets are always embedded there
+    if config.build.asset_archive && target.is_desktop() {
+      let (path, resource_path) = asset_archive_path(&config, &config_parent)?;
+      assets.archive(&path, &resource_path)?
+    } else {
+      quote!(#assets)
+    }
   };
 
   let out_dir = ensure_out_dir()?;
@@ -220,6 +315,9 @@ pub fn context_codegen(data: ContextData) -> EmbeddedAssetsResult<TokenStream> {
         "icons/icon.ico",
       );
       if icon_path.exists() {
+        if let Some(snapshot) = &mut snapshot {
+          snapshot.icon("default_window_icon", &config_parent, &icon_path);
+        }
         let icon = CachedIcon::new(&root, &icon_path)?;
         quote!(::std::option::Option::Some(#icon))
       } else {
@@ -229,6 +327,9 @@ pub fn context_codegen(data: ContextData) -> EmbeddedAssetsResult<TokenStream> {
           |i| i.ends_with(".png"),
           "icons/icon.png",
         );
+        if let Some(snapshot) = &mut snapshot {
+          snapshot.icon("default_window_icon", &config_parent, &icon_path);
+        }
         let icon = CachedIcon::new(&root, &icon_path)?;
         quote!(::std::option::Option::Some(#icon))
       }
@@ -240,6 +341,9 @@ pub fn context_codegen(data: ContextData) -> EmbeddedAssetsResult<TokenStream> {
         |i| i.ends_with(".png"),
         "icons/icon.png",
       );
+      if let Some(snapshot) = &mut snapshot {
+        snapshot.icon("default_window_icon", &config_parent, &icon_path);
+      }
       let icon = CachedIcon::new(&root, &icon_path)?;
       quote!(::std::option::Option::Some(#icon))
     }
@@ -261,6 +365,9 @@ pub fn context_codegen(data: ContextData) -> EmbeddedAssetsResult<TokenStream> {
       );
     }
 
+    if let Some(snapshot) = &mut snapshot {
+      snapshot.icon("app_icon", &config_parent, &icon_path);
+    }
     let icon = CachedIcon::new_raw(&root, &icon_path)?;
     quote!(::std::option::Option::Some(#icon.to_vec()))
   } else {
@@ -291,6 +398,9 @@ pub fn context_codegen(data: ContextData) -> EmbeddedAssetsResult<TokenStream> {
   let with_tray_icon_code = if target.is_desktop() {
     if let Some(tray) = &config.app.tray_icon {
       let tray_icon_icon_path = config_parent.join(&tray.icon_path);
+      if let Some(snapshot) = &mut snapshot {
+        snapshot.icon("tray_icon", &config_parent, &tray_icon_icon_path);
+      }
       let icon = CachedIcon::new(&root, &tray_icon_icon_path)?;
       quote!(context.set_tray_icon(::std::option::Option::Some(#icon));)
     } else {
@@ -382,6 +492,9 @@ pub fn context_codegen(data: ContextData) -> EmbeddedAssetsResult<TokenStream> {
       }
 
       let schema = options.isolation_schema;
+      if dev {
+        write_dev_isolation_schema(&schema)?;
+      }
 
       quote!(#root::Pattern::Isolation {
         assets: ::std::sync::Arc::new(#assets),
@@ -418,6 +531,11 @@ pub fn context_codegen(data: ContextData) -> EmbeddedAssetsResult<TokenStream> {
 
   let resolved = Resolved::resolve(&acl, capabilities, target).expect("failed to resolve ACL");
 
+  if let (Some(snapshot), Some(snapshot_path)) = (&mut snapshot, snapshot_path) {
+    snapshot.acl(&resolved);
+    snapshot.write(&config_parent.join(snapshot_path))?;
+  }
+
   let acl_tokens = map_lit(
     quote! { ::std::collections::BTreeMap },
     &acl,
@@ -509,3 +627,91 @@ fn find_icon(
     .unwrap_or(default);
   config_parent.join(icon_path)
 }
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use tauri_utils::{assets::AssetKey, config::PatternKind};
+
+  use super::{map_core_assets, subresource_integrity_metadata, AssetOptions, CspHashes};
+
+  fn frontend_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("tauri-codegen-test").join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("pages")).unwrap();
+    std::fs::write(dir.join("main.js"), "console.log('main')").unwrap();
+    std::fs::write(dir.join("pages").join("page.js"), "console.log('page')").unwrap();
+    dir
+  }
+
+  #[test]
+  fn subresource_integrity_urls() {
+    let dir = frontend_dir("subresource-integrity-urls");
+    let index = dir.join("index.html");
+    let page = dir.join("pages").join("page.html");
+    let main_integrity =
+      subresource_integrity_metadata(&AssetKey::from("/index.html"), &index, "main.js");
+
+    assert!(main_integrity
+      .as_deref()
+      .is_some_and(|i| i.starts_with("sha384-")));
+    // absolute URLs are resolved from the asset root
+    assert_eq!(
+      subresource_integrity_metadata(&AssetKey::from("/pages/page.html"), &page, "/main.js?v=1"),
+      main_integrity
+    );
+    assert!(subresource_integrity_metadata(
+      &AssetKey::from("/pages/page.html"),
+      &page,
+      "page.js#hash"
+    )
+    .is_some());
+
+    for url in [
+      "",
+      "https://tauri.app/main.js",
+      "//tauri.app/main.js",
+      "missing.js",
+    ] {
+      assert_eq!(
+        subresource_integrity_metadata(&AssetKey::from("/index.html"), &index, url),
+        None,
+        "{url}"
+      );
+    }
+  }
+
+  #[test]
+  fn inject_subresource_integrity() {
+    let dir = frontend_dir("inject-subresource-integrity");
+    let index = dir.join("index.html");
+    let key = AssetKey::from("/index.html");
+    let html = r#"<html><head><script src="main.js"></script><script src="https://tauri.app/cdn.js"></script></head><body></body></html>"#;
+
+    let expected = subresource_integrity_metadata(&key, &index, "main.js").unwrap();
+
+    let mut input = html.as_bytes().to_vec();
+    map_core_assets(&AssetOptions::new(PatternKind::Brownfield).subresource_integrity(true))(
+      &key,
+      &index,
+      &mut input,
+      &mut CspHashes::default(),
+    )
+    .unwrap();
+    let output = String::from_utf8(input).unwrap();
+    assert!(output.contains(&format!(r#"integrity="{expected}""#)));
+    assert_eq!(output.matches("integrity=").count(), 1);
+
+    // disabled
+    let mut input = html.as_bytes().to_vec();
+    map_core_assets(&AssetOptions::new(PatternKind::Brownfield))(
+      &key,
+      &index,
+      &mut input,
+      &mut CspHashes::default(),
+    )
+    .unwrap();
+    assert!(!String::from_utf8(input).unwrap().contains("integrity="));
+  }
+}
@@ -10,9 +10,13 @@ use std::{
   collections::HashMap,
   fs::File,
   path::{Path, PathBuf},
+  time::{Duration, SystemTime},
+};
+use tauri_utils::config::{AssetCompressionAlgorithm, AssetCompressionRule, PatternKind};
+use tauri_utils::{
+  assets::{AssetEncoding, AssetKey},
+  config::DisabledCspModificationKind,
 };
-use tauri_utils::config::PatternKind;
-use tauri_utils::{assets::AssetKey, config::DisabledCspModificationKind};
 use thiserror::Error;
 use walkdir::{DirEntry, WalkDir};
 
@@ -22,8 +26,163 @@ use brotli::enc::backward_references::BrotliEncoderParams;
 /// The subdirectory inside the target directory we want to place assets.
 const TARGET_PATH: &str = "tauri-codegen-assets";
 
-/// (key, (original filepath, compressed bytes))
-type Asset = (AssetKey, (PathBuf, PathBuf));
+/// (key, (original filepath, compressed bytes), uncompressed digest, encoding)
+type Asset = (AssetKey, (PathBuf, PathBuf), AssetDigest, AssetEncoding);
+
+/// How an asset is compressed.
+#[derive(Debug, Clone, Copy)]
+enum Compression {
+  None,
+  #[cfg(feature = "compression")]
+  Brotli(i32),
+  #[cfg(feature = "compression-zstd")]
+  Zstd(i32),
+}
+
+impl Compression {
+  /// The default compression, used for the assets that do not match any rule.
+  fn default_brotli() -> Self {
+    Self::from_valid(AssetCompressionAlgorithm::Brotli, None)
+  }
+
+  /// Validates the compression settings of a rule.
+  ///
+  /// Use a high compression level for release, a fast one for everything else.
+  fn new(rule: &AssetCompressionRule) -> Result<Self, EmbeddedAssetsError> {
+    let range = match rule.algorithm {
+      AssetCompressionAlgorithm::Brotli => Some(("brotli", 0, 11)),
+      AssetCompressionAlgorithm::Zstd => Some(("zstd", 1, 22)),
+      AssetCompressionAlgorithm::None => None,
+    };
+    if let (Some((algorithm, min, max)), Some(level)) = (range, rule.level) {
+      if !(min..=max).contains(&level) {
+        return Err(EmbeddedAssetsError::CompressionLevel {
+          glob: rule.glob.clone(),
+          algorithm,
+          level,
+          min,
+          max,
+        });
+      }
+    }
+
+    #[cfg(all(feature = "compression", not(feature = "compression-zstd")))]
+    if rule.algorithm == AssetCompressionAlgorithm::Zstd {
+      return Err(EmbeddedAssetsError::CompressionZstdDisabled {
+        glob: rule.glob.clone(),
+      });
+    }
+
+    Ok(Self::from_valid(rule.algorithm, rule.level))
+  }
+
+  #[allow(unused_variables)]
+  fn from_valid(algorithm: AssetCompressionAlgorithm, level: Option<i32>) -> Self {
+    match algorithm {
+      #[cfg(feature = "compression")]
+      AssetCompressionAlgorithm::Brotli => Self::Brotli(
+        // the following compression levels are hand-picked and are not min-maxed.
+        // they have a good balance of runtime vs size for the respective profile goals.
+        // see the "brotli" section of this comment https://github.com/tauri-apps/tauri/issues/3571#issuecomment-1054847558
+        level.unwrap_or(if cfg!(debug_assertions) { 2 } else { 9 }),
+      ),
+      #[cfg(feature = "compression-zstd")]
+      AssetCompressionAlgorithm::Zstd => {
+        Self::Zstd(level.unwrap_or(if cfg!(debug_assertions) { 1 } else { 19 }))
+      }
+      // assets are stored uncompressed without the `compression` feature
+      _ => Self::None,
+    }
+  }
+
+  fn encoding(self) -> AssetEncoding {
+    match self {
+      Self::None => AssetEncoding::Identity,
+      #[cfg(feature = "compression")]
+      Self::Brotli(_) => AssetEncoding::Brotli,
+      #[cfg(feature = "compression-zstd")]
+      Self::Zstd(_) => AssetEncoding::Zstd,
+    }
+  }
+
+  /// The suffix of the cached files, identifying the compression settings they were written with.
+  fn cache_suffix(self) -> String {
+    match self {
+      Self::None => "raw".into(),
+      #[cfg(feature = "compression")]
+      Self::Brotli(level) => format!("br{level}"),
+      #[cfg(feature = "compression-zstd")]
+      Self::Zstd(level) => format!("zstd{level}"),
+    }
+  }
+
+  fn compress(self, input: &[u8], out: &mut File) -> std::io::Result<()> {
+    match self {
+      Self::None => {
+        use std::io::Write;
+        out.write_all(input)
+      }
+      #[cfg(feature = "compression")]
+      Self::Brotli(level) => {
+        let settings = BrotliEncoderParams {
+          quality: level,
+          ..Default::default()
+        };
+        brotli::BrotliCompress(&mut std::io::Cursor::new(input), out, &settings).map(|_| ())
+      }
+      #[cfg(feature = "compression-zstd")]
+      Self::Zstd(level) => zstd::stream::copy_encode(input, out, level),
+    }
+  }
+}
+
+/// The compression rules from the configuration, the first rule matching an asset applies.
+struct CompressionRules(Vec<(glob::Pattern, Compression)>);
+
+impl CompressionRules {
+  fn new(rules: &[AssetCompressionRule]) -> Result<Self, EmbeddedAssetsError> {
+    rules
+      .iter()
+      .map(|rule| {
+        let pattern =
+          glob::Pattern::new(&rule.glob).map_err(|error| EmbeddedAssetsError::CompressionGlob {
+            glob: rule.glob.clone(),
+            error,
+          })?;
+        Ok((pattern, Compression::new(rule)?))
+      })
+      .collect::<Result<_, _>>()
+      .map(Self)
+  }
+
+  fn get(&self, key: &AssetKey) -> Compression {
+    let path = key.as_ref().trim_start_matches('/');
+    let options = glob::MatchOptions {
+      require_literal_separator: true,
+      ..Default::default()
+    };
+    self
+      .0
+      .iter()
+      .find(|(pattern, _)| pattern.matches_with(path, options))
+      .map(|(_, compression)| *compression)
+      .unwrap_or_else(Compression::default_brotli)
+  }
+}
+
+/// An asset read from disk and transformed by the caller, waiting to be compressed.
+struct PendingAsset {
+  key: AssetKey,
+  path: PathBuf,
+  input: Vec<u8>,
+}
+
+/// The SHA-256 hex digest and the size of the final, uncompressed contents of an asset.
+#[derive(Debug, Clone)]
+pub(crate) struct AssetDigest {
+  pub(crate) sha256: String,
+  pub(crate) size: usize,
+}
 
 /// All possible errors while reading and compressing an [`EmbeddedAssets`] directory
 #[derive(Debug, Error)]
@@ -56,9 +215,43 @@ pub enum EmbeddedAssetsError {
     error: walkdir::Error,
   },
 
+  #[cfg(feature = "asset-encryption")]
+  #[error("failed to generate random bytes for asset encryption because {0}")]
+  Csprng(getrandom::Error),
+
+  #[cfg(feature = "asset-encryption")]
+  #[error("failed to encrypt asset {path}")]
+  AssetEncrypt { path: PathBuf },
+
+  #[error("invalid asset compression glob `{glob}`: {error}")]
+  CompressionGlob {
+    glob: String,
+    error: glob::PatternError,
+  },
+
+  #[error(
+    "invalid {algorithm} compression level {level} for `{glob}`, must be between {min} and {max}"
+  )]
+  CompressionLevel {
+    glob: String,
+    algorithm: &'static str,
+    level: i32,
+    min: i32,
+    max: i32,
+  },
+
+  #[error("zstd compression of `{glob}` requires the `compression-zstd` feature of tauri")]
+  CompressionZstdDisabled { glob: String },
+
   #[error("OUT_DIR env var is not set, do you have a build script?")]
   OutDir,
 
+  #[error("{0} env var is not set, is the app built with cargo?")]
+  EnvVar(&'static str),
+
+  #[error("`app > security > encryptAssets` requires the `asset-encryption` feature of tauri")]
+  AssetEncryptionDisabled,
+
   #[error("version error: {0}")]
   Version(#[from] semver::Error),
 }
@@ -78,6 +271,15 @@ pub type EmbeddedAssetsResult<T> = Result<T, EmbeddedAssetsError>;
 pub struct EmbeddedAssets {
   assets: HashMap<AssetKey, (PathBuf, PathBuf)>,
   csp_hashes: CspHashes,
+  /// Digests of the final asset contents.
+  digests: HashMap<AssetKey, AssetDigest>,
+  /// The encoding of each asset.
+  encodings: HashMap<AssetKey, AssetEncoding>,
+  /// SHA-256 hex digests of the final asset contents, if integrity verification is enabled.
+  integrity: Option<HashMap<AssetKey, String>>,
+  /// The AES-256-GCM key used to encrypt the assets, if encryption is enabled.
+  #[cfg(feature = "asset-encryption")]
+  encryption_key: Option<[u8; 32]>,
 }
 
 pub struct EmbeddedAssetsInput(Vec<PathBuf>);
@@ -205,7 +407,12 @@ pub struct AssetOptions {
   pub(crate) csp: bool,
   pub(crate) pattern: PatternKind,
   pub(crate) freeze_prototype: bool,
+  pub(crate) subresource_integrity: bool,
+  pub(crate) verify_integrity: bool,
+  #[cfg(feature = "asset-encryption")]
+  pub(crate) encrypt: bool,
   pub(crate) dangerous_disable_asset_csp_modification: DisabledCspModificationKind,
+  pub(crate) compression: Vec<AssetCompressionRule>,
   #[cfg(feature = "isolation")]
   pub(crate) isolation_schema: String,
 }
@@ -217,7 +424,12 @@ impl AssetOptions {
       csp: false,
       pattern,
       freeze_prototype: false,
+      subresource_integrity: false,
+      verify_integrity: false,
+      #[cfg(feature = "asset-encryption")]
+      encrypt: false,
       dangerous_disable_asset_csp_modification: DisabledCspModificationKind::Flag(false),
+      compression: Vec::new(),
       #[cfg(feature = "isolation")]
       isolation_schema: format!("isolation-{}", uuid::Uuid::new_v4()),
     }
@@ -237,6 +449,40 @@ impl AssetOptions {
     self
   }
 
+  /// Instruct the asset handler to add `integrity` attributes to the scripts and stylesheets loaded by HTML files.
+  #[must_use]
+  pub fn subresource_integrity(mut self, enabled: bool) -> Self {
+    self.subresource_integrity = enabled;
+    self
+  }
+
+  /// Instruct the asset handler to embed the SHA-256 digest of each asset so it can be verified at runtime.
+  #[must_use]
+  pub fn verify_integrity(mut self, enabled: bool) -> Self {
+    self.verify_integrity = enabled;
+    self
+  }
+
+  /// Instruct the asset handler to encrypt the embedded assets with AES-256-GCM.
+  ///
+  /// The key is generated at build time and embedded in the binary, so this prevents trivially
+  /// extracting the assets from the binary but does not protect them from a determined attacker.
+  #[cfg(feature = "asset-encryption")]
+  #[must_use]
+  pub fn encrypt(mut self, encrypt: bool) -> Self {
+    self.encrypt = encrypt;
+    self
+  }
+
+  /// Instruct the asset handler to compress the assets matching each glob with the given algorithm.
+  ///
+  /// The first rule matching an asset applies, assets not matching any rule are compressed with brotli.
+  #[must_use]
+  pub fn compression(mut self, rules: Vec<AssetCompressionRule>) -> Self {
+    self.compression = rules;
+    self
+  }
+
   /// Instruct the asset handler to **NOT** modify the CSP. This is **NOT** recommended.
   pub fn dangerous_disable_asset_csp_modification(
     mut self,
@@ -262,48 +508,141 @@ impl EmbeddedAssets {
     ) -> Result<(), EmbeddedAssetsError>,
   ) -> Result<Self, EmbeddedAssetsError> {
     // we need to pre-compute all files now, so that we can inject data from all files into a few
-    let RawEmbeddedAssets { paths, csp_hashes } = RawEmbeddedAssets::new(input.into(), options)?;
+    let RawEmbeddedAssets {
+      paths,
+      mut csp_hashes,
+    } = RawEmbeddedAssets::new(input.into(), options)?;
 
-    struct CompressState {
-      csp_hashes: CspHashes,
-      assets: HashMap<AssetKey, (PathBuf, PathBuf)>,
-    }
+    // the caller-requested manipulation can update the CSP hashes, so it runs sequentially
+    let pending = paths
+      .into_iter()
+      .map(|(prefix, entry)| Self::read_file(&prefix, entry.path(), &mut map, &mut csp_hashes))
+      .collect::<Result<Vec<_>, _>>()?;
+
+    #[cfg(feature = "asset-encryption")]
+    let encryption_key = if options.encrypt {
+      Some(Self::encryption_key()?)
+    } else {
+      None
+    };
 
-    let CompressState { assets, csp_hashes } = paths.into_iter().try_fold(
-      CompressState {
-        csp_hashes,
-        assets: HashMap::new(),
-      },
-      move |mut state, (prefix, entry)| {
-        let (key, asset) =
-          Self::compress_file(&prefix, entry.path(), &mut map, &mut state.csp_hashes)?;
-        state.assets.insert(key, asset);
-        Result::<_, EmbeddedAssetsError>::Ok(state)
-      },
+    let rules = CompressionRules::new(&options.compression)?;
+    let cache_dir = ensure_cache_dir()?;
+    let compressed = Self::compress_files(
+      pending,
+      &rules,
+      &cache_dir,
+      #[cfg(feature = "asset-encryption")]
+      encryption_key.as_ref(),
     )?;
+    // the files used by this build were just touched so they are kept
+    evict_cache(&cache_dir, SystemTime::now());
+
+    let mut assets = HashMap::new();
+    let mut digests = HashMap::new();
+    let mut encodings = HashMap::new();
+    let mut integrity = options.verify_integrity.then(HashMap::new);
+    for (key, asset, digest, encoding) in compressed {
+      if let Some(integrity) = &mut integrity {
+        integrity.insert(key.clone(), digest.sha256.clone());
+      }
+      digests.insert(key.clone(), digest);
+      encodings.insert(key.clone(), encoding);
+      assets.insert(key, asset);
+    }
 
-    Ok(Self { assets, csp_hashes })
+    Ok(Self {
+      assets,
+      csp_hashes,
+      digests,
+      encodings,
+      integrity,
+      #[cfg(feature = "asset-encryption")]
+      encryption_key,
+    })
   }
 
-  /// Use highest compression level for release, the fastest one for everything else
-  #[cfg(feature = "compression")]
-  fn compression_settings() -> BrotliEncoderParams {
-    let mut settings = BrotliEncoderParams::default();
-
-    // the following compression levels are hand-picked and are not min-maxed.
-    // they have a good balance of runtime vs size for the respective profile goals.
-    // see the "brotli" section of this comment https://github.com/tauri-apps/tauri/issues/3571#issuecomment-1054847558
-    if cfg!(debug_assertions) {
-      settings.quality = 2
-    } else {
-      settings.quality = 9
+  /// Reads the asset encryption key from the target directory, generating it if it does not exist.
+  ///
+  /// The key is persisted so the encrypted files can be reused across builds.
+  #[cfg(feature = "asset-encryption")]
+  fn encryption_key() -> Result<[u8; 32], EmbeddedAssetsError> {
+    let out_dir = ensure_out_dir()?.join(TARGET_PATH);
+    std::fs::create_dir_all(&out_dir).map_err(|_| EmbeddedAssetsError::OutDir)?;
+    let path = out_dir.join("asset-encryption.key");
+
+    let read_key = || {
+      std::fs::read(&path)
+        .and_then(|key| {
+          <[u8; 32]>::try_from(key).map_err(|_| {
+            std::io::Error::new(
+              std::io::ErrorKind::InvalidData,
+              "the asset encryption key must be 32 bytes long",
+            )
+          })
+        })
+        .map_err(|error| EmbeddedAssetsError::AssetRead {
+          path: path.clone(),
+          error,
+        })
+    };
+
+    if path.exists() {
+      return read_key();
     }
 
-    settings
+    let mut key = [0u8; 32];
+    getrandom::fill(&mut key).map_err(EmbeddedAssetsError::Csprng)?;
+
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(format!(".{}.tmp", uuid::Uuid::new_v4()));
+    let tmp_path = path.with_file_name(tmp_name);
+    let result = std::fs::write(&tmp_path, key)
+      // unlike a rename, linking fails if another build created the key in the meantime
+      .and_then(|()| std::fs::hard_link(&tmp_path, &path));
+    let _ = std::fs::remove_file(&tmp_path);
+
+    match result {
+      Ok(()) => Ok(key),
+      Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => read_key(),
+      Err(error) => Err(EmbeddedAssetsError::AssetWrite { path, error }),
+    }
   }
 
-  /// Compress a file and spit out the information in a [`HashMap`] friendly form.
-  fn compress_file(
+  /// Encrypts a compressed asset file, returning the path of the encrypted file.
+  #[cfg(feature = "asset-encryption")]
+  fn encrypt_file(path: &Path, key: &[u8; 32]) -> Result<PathBuf, EmbeddedAssetsError> {
+    // the key fingerprint is part of the file name so a new key never reuses stale files
+    let fingerprint = &crate::checksum(key).map_err(EmbeddedAssetsError::Hex)?[..16];
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(format!(".{fingerprint}.enc"));
+    let out_path = path.with_file_name(file_name);
+
+    if !touch_cache_file(&out_path) {
+      let input = std::fs::read(path).map_err(|error| EmbeddedAssetsError::AssetRead {
+        path: path.to_owned(),
+        error,
+      })?;
+
+      let mut nonce = [0u8; tauri_utils::assets::ASSET_NONCE_SIZE];
+      getrandom::fill(&mut nonce).map_err(EmbeddedAssetsError::Csprng)?;
+
+      let encrypted = tauri_utils::assets::encrypt_asset(key, &nonce, &input).ok_or_else(|| {
+        EmbeddedAssetsError::AssetEncrypt {
+          path: path.to_owned(),
+        }
+      })?;
+      write_cache_file(&out_path, |out_file| {
+        use std::io::Write;
+        out_file.write_all(&encrypted)
+      })?;
+    }
+
+    Ok(out_path)
+  }
+
+  /// Read a file and apply the caller-requested manipulation.
+  fn read_file(
     prefix: &Path,
     path: &Path,
     map: &mut impl FnMut(
@@ -313,7 +652,7 @@ impl EmbeddedAssets {
       &mut CspHashes,
     ) -> Result<(), EmbeddedAssetsError>,
     csp_hashes: &mut CspHashes,
-  ) -> Result<Asset, EmbeddedAssetsError> {
+  ) -> Result<PendingAsset, EmbeddedAssetsError> {
     let mut input = std::fs::read(path).map_err(|error| EmbeddedAssetsError::AssetRead {
       path: path.to_owned(),
       error,
@@ -331,78 +670,191 @@ impl EmbeddedAssets {
     // perform any caller-requested input manipulation
     map(&key, path, &mut input, csp_hashes)?;
 
-    // we must canonicalize the base of our paths to allow long paths on windows
-    let out_dir = std::env::var("OUT_DIR")
-      .map_err(|_| EmbeddedAssetsError::OutDir)
-      .map(PathBuf::from)
-      .and_then(|p| p.canonicalize().map_err(|_| EmbeddedAssetsError::OutDir))
-      .map(|p| p.join(TARGET_PATH))?;
+    Ok(PendingAsset {
+      key,
+      path: path.to_owned(),
+      input,
+    })
+  }
+
+  /// Compress the files on all available cores.
+  fn compress_files(
+    pending: Vec<PendingAsset>,
+    rules: &CompressionRules,
+    cache_dir: &Path,
+    #[cfg(feature = "asset-encryption")] encryption_key: Option<&[u8; 32]>,
+  ) -> Result<Vec<Asset>, EmbeddedAssetsError> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
-    // make sure that our output directory is created
-    std::fs::create_dir_all(&out_dir).map_err(|_| EmbeddedAssetsError::OutDir)?;
+    let next = AtomicUsize::new(0);
+    let threads = std::thread::available_parallelism()
+      .map(|n| n.get())
+      .unwrap_or(1)
+      .min(pending.len());
+
+    let results = std::thread::scope(|scope| {
+      let workers = (0..threads)
+        .map(|_| {
+          scope.spawn(|| {
+            let mut results = Vec::new();
+            // files have very different sizes, so each worker takes the next file when it is done
+            while let Some(file) = pending.get(next.fetch_add(1, Ordering::Relaxed)) {
+              #[allow(unused_mut)]
+              let mut asset = Self::compress_file(file, rules.get(&file.key), cache_dir);
+              #[cfg(feature = "asset-encryption")]
+              if let (Ok((_, (_, output), _, _)), Some(encryption_key)) =
+                (&mut asset, encryption_key)
+              {
+                match Self::encrypt_file(output, encryption_key) {
+                  Ok(path) => *output = path,
+                  Err(error) => {
+                    results.push(Err(error));
+                    continue;
+                  }
+                }
+              }
+              results.push(asset);
+            }
+            results
+          })
+        })
+        .collect::<Vec<_>>();
+
+      workers
+        .into_iter()
+        .flat_map(|worker| worker.join().expect("asset compression thread panicked"))
+        .collect::<Vec<_>>()
+    });
+
+    results.into_iter().collect()
+  }
+
+  /// Compress a file and spit out the information in a [`HashMap`] friendly form.
+  ///
+  /// The compressed file is named after the content hash and compression settings,
+  /// so unchanged assets reuse the output of previous builds.
+  fn compress_file(
+    file: &PendingAsset,
+    compression: Compression,
+    cache_dir: &Path,
+  ) -> Result<Asset, EmbeddedAssetsError> {
+    let PendingAsset { key, path, input } = file;
+
+    let digest = AssetDigest {
+      sha256: Sha256::digest(input)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect::<String>(),
+      size: input.len(),
+    };
 
     // get a hash of the input - allows for caching existing files
-    let hash = crate::checksum(&input).map_err(EmbeddedAssetsError::Hex)?;
+    let hash = crate::checksum(input).map_err(EmbeddedAssetsError::Hex)?;
 
-    // use the content hash to determine filename, keep extensions that exist
+    // use the content hash and compression settings to determine filename, keep extensions that exist
+    let suffix = compression.cache_suffix();
     let out_path = if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-      out_dir.join(format!("{hash}.{ext}"))
+      cache_dir.join(format!("{hash}-{suffix}.{ext}"))
     } else {
-      out_dir.join(hash)
+      cache_dir.join(format!("{hash}-{suffix}"))
     };
 
-    // only compress and write to the file if it doesn't already exist.
-    if !out_path.exists() {
-      #[allow(unused_mut)]
-      let mut out_file =
-        File::create(&out_path).map_err(|error| EmbeddedAssetsError::AssetWrite {
-          path: out_path.clone(),
-          error,
-        })?;
+    // only compress and write to the file if it isn't cached already.
+    if !touch_cache_file(&out_path) {
+      // entirely write input to the output file path with compression
+      write_cache_file(&out_path, |out_file| compression.compress(input, out_file))?;
+    }
 
-      #[cfg(not(feature = "compression"))]
-      {
-        use std::io::Write;
-        out_file
-          .write_all(&input)
-          .map_err(|error| EmbeddedAssetsError::AssetWrite {
-            path: path.to_owned(),
-            error,
-          })?;
-      }
+    Ok((
+      key.clone(),
+      (path.clone(), out_path),
+      digest,
+      compression.encoding(),
+    ))
+  }
 
-      #[cfg(feature = "compression")]
-      {
-        let mut input = std::io::Cursor::new(input);
-        // entirely write input to the output file path with compression
-        brotli::BrotliCompress(&mut input, &mut out_file, &Self::compression_settings()).map_err(
-          |error| EmbeddedAssetsError::AssetWrite {
-            path: path.to_owned(),
-            error,
-          },
-        )?;
-      }
-    }
+  /// The digests of the embedded assets.
+  pub(crate) fn digests(&self) -> &HashMap<AssetKey, AssetDigest> {
+    &self.digests
+  }
+
+  /// The encodings of the embedded assets.
+  pub(crate) fn encodings(&self) -> &HashMap<AssetKey, AssetEncoding> {
+    &self.encodings
+  }
 
-    Ok((key, (path.into(), out_path)))
+  /// The CSP hashes of the embedded assets.
+  pub(crate) fn csp_hashes(&self) -> &CspHashes {
+    &self.csp_hashes
   }
 }
 
-impl ToTokens for EmbeddedAssets {
-  fn to_tokens(&self, tokens: &mut TokenStream) {
-    let mut assets = TokenStream::new();
-    for (key, (input, output)) in &self.assets {
-      let key: &str = key.as_ref();
-      let input = input.display().to_string();
-      let output = output.display().to_string();
+impl EmbeddedAssets {
+  /// Writes the assets to an archive shipped as a bundle resource instead of embedding them,
+  /// returning the tokens of the `ArchiveAssets` that read it.
+  ///
+  /// `resource_path` is the path of the archive relative to the resource directory of the bundled app.
+  pub(crate) fn archive(
+    &self,
+    path: &Path,
+    resource_path: &str,
+  ) -> EmbeddedAssetsResult<TokenStream> {
+    let mut assets = self
+      .assets
+      .iter()
+      .map(|(key, (input, output))| (key.as_ref().to_string(), input, output))
+      .collect::<Vec<_>>();
+    assets.sort();
 
-      // add original asset as a compiler dependency, rely on dead code elimination to clean it up
-      assets.append_all(quote!(#key => {
+    // the output file names are content hashes, so the id changes whenever an asset changes
+    let mut fingerprint = String::new();
+    for (key, _, output) in &assets {
+      fingerprint.push_str(key);
+      fingerprint.push('\0');
+      fingerprint.push_str(&output.file_name().unwrap_or_default().to_string_lossy());
+      fingerprint.push('\0');
+    }
+    let id = crate::checksum(fingerprint.as_bytes()).map_err(EmbeddedAssetsError::Hex)?;
+
+    tauri_utils::assets::archive::write(
+      path,
+      &id,
+      assets
+        .iter()
+        .map(|(key, _, output)| (key.clone(), output.to_path_buf())),
+    )
+    .map_err(|error| EmbeddedAssetsError::AssetWrite {
+      path: path.to_path_buf(),
+      error,
+    })?;
+
+    // add original assets as compiler dependencies
+    let mut dependencies = TokenStream::new();
+    for (_, input, _) in &assets {
+      let input = input.display().to_string();
+      dependencies.append_all(quote!(
         const _: &[u8] = include_bytes!(#input);
-        include_bytes!(#output)
-      },));
+      ));
     }
 
+    let file_name = path
+      .file_name()
+      .unwrap_or_default()
+      .to_string_lossy()
+      .into_owned();
+    let (global_hashes, html_hashes) = self.csp_hashes_tokens();
+    let builder_calls = self.builder_calls();
+
+    Ok(quote! {{
+        #dependencies
+        #[allow(unused_imports)]
+        use ::tauri::utils::assets::{ArchiveAssets, AssetEncoding, CspHash, phf, phf::phf_map};
+        ArchiveAssets::new(#file_name, #resource_path, #id, &[#global_hashes], phf_map! { #html_hashes })#builder_calls
+    }})
+  }
+
+  /// The tokens of the global and per HTML file CSP hashes.
+  fn csp_hashes_tokens(&self) -> (TokenStream, TokenStream) {
     let mut global_hashes = TokenStream::new();
     for script_hash in &self.csp_hashes.scripts {
       let hash = script_hash.as_str();
@@ -425,15 +877,144 @@ impl ToTokens for EmbeddedAssets {
       html_hashes.append_all(quote!(#key => &[#value],));
     }
 
+    (global_hashes, html_hashes)
+  }
+
+  /// The builder calls setting the integrity digests, encodings and encryption key.
+  fn builder_calls(&self) -> TokenStream {
+    let mut builder_calls = TokenStream::new();
+
+    // the default encoding depends on the `compression` feature of the app, so it is always set
+    if !self.encodings.is_empty() {
+      let mut encodings = TokenStream::new();
+      for (key, encoding) in &self.encodings {
+        let key: &str = key.as_ref();
+        let encoding = match encoding {
+          AssetEncoding::Brotli => quote!(AssetEncoding::Brotli),
+          AssetEncoding::Zstd => quote!(AssetEncoding::Zstd),
+          _ => quote!(AssetEncoding::Identity),
+        };
+        encodings.append_all(quote!(#key => #encoding,));
+      }
+      builder_calls.append_all(quote!(.with_encodings(phf_map! { #encodings })));
+    }
+
+    if let Some(integrity) = &self.integrity {
+      let mut digests = TokenStream::new();
+      for (key, digest) in integrity {
+        let key: &str = key.as_ref();
+        let digest = digest.as_str();
+        digests.append_all(quote!(#key => #digest,));
+      }
+      builder_calls.append_all(quote!(.with_integrity(phf_map! { #digests })));
+    }
+
+    #[cfg(feature = "asset-encryption")]
+    if let Some(encryption_key) = &self.encryption_key {
+      let key = encryption_key.iter();
+      builder_calls.append_all(quote!(.with_encryption_key(&[#(#key),*])));
+    }
+
+    builder_calls
+  }
+}
+
+impl ToTokens for EmbeddedAssets {
+  fn to_tokens(&self, tokens: &mut TokenStream) {
+    let mut assets = TokenStream::new();
+    for (key, (input, output)) in &self.assets {
+      let key: &str = key.as_ref();
+      let input = input.display().to_string();
+      let output = output.display().to_string();
+
+      // add original asset as a compiler dependency, rely on dead code elimination to clean it up
+      assets.append_all(quote!(#key => {
+        const _: &[u8] = include_bytes!(#input);
+        include_bytes!(#output)
+      },));
+    }
+
+    let (global_hashes, html_hashes) = self.csp_hashes_tokens();
+    let builder_calls = self.builder_calls();
+
     // we expect phf related items to be in path when generating the path code
     tokens.append_all(quote! {{
         #[allow(unused_imports)]
-        use ::tauri::utils::assets::{CspHash, EmbeddedAssets, phf, phf::phf_map};
-        EmbeddedAssets::new(phf_map! { #assets }, &[#global_hashes], phf_map! { #html_hashes })
+        use ::tauri::utils::assets::{AssetEncoding, CspHash, EmbeddedAssets, phf, phf::phf_map};
+        EmbeddedAssets::new(phf_map! { #assets }, &[#global_hashes], phf_map! { #html_hashes })#builder_calls
     }});
   }
 }
 
+/// The profile directory of the target directory, e.g. `target/release`.
+pub(crate) fn target_dir() -> EmbeddedAssetsResult<PathBuf> {
+  // OUT_DIR is `<target dir>/build/<package>-<hash>/out`
+  ensure_out_dir()?
+    .ancestors()
+    .nth(3)
+    .map(Path::to_path_buf)
+    .ok_or(EmbeddedAssetsError::OutDir)
+}
+
+/// The directory of compressed assets, shared by the packages and builds of a profile.
+fn ensure_cache_dir() -> EmbeddedAssetsResult<PathBuf> {
+  let cache_dir = target_dir()?.join(TARGET_PATH);
+  std::fs::create_dir_all(&cache_dir).map_err(|_| EmbeddedAssetsError::OutDir)?;
+  Ok(cache_dir)
+}
+
+/// Cached files that were not used by any build for this long are removed.
+const CACHE_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Marks a cached file as used so it is not evicted, returning `false` if it does not exist.
+fn touch_cache_file(path: &Path) -> bool {
+  File::options()
+    .write(true)
+    .open(path)
+    .and_then(|file| file.set_modified(SystemTime::now()))
+    .is_ok()
+}
+
+/// Writes a file to the cache, moving it in place once complete as other builds can read it concurrently.
+pub(crate) fn write_cache_file(
+  path: &Path,
+  write: impl FnOnce(&mut File) -> std::io::Result<()>,
+) -> EmbeddedAssetsResult<()> {
+  let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+  tmp_name.push(format!(".{}.tmp", uuid::Uuid::new_v4()));
+  let tmp_path = path.with_file_name(tmp_name);
+
+  let result = File::create(&tmp_path)
+    .and_then(|mut file| write(&mut file))
+    .and_then(|()| std::fs::rename(&tmp_path, path));
+  if result.is_err() {
+    let _ = std::fs::remove_file(&tmp_path);
+  }
+  result.map_err(|error| EmbeddedAssetsError::AssetWrite {
+    path: path.to_path_buf(),
+    error,
+  })
+}
+
+/// Removes the cached files, including leftover temporary files, that were not used since [`CACHE_MAX_AGE`].
+fn evict_cache(cache_dir: &Path, now: SystemTime) {
+  let Ok(entries) = std::fs::read_dir(cache_dir) else {
+    return;
+  };
+  for entry in entries.flatten() {
+    let is_stale = entry
+      .metadata()
+      .ok()
+      .filter(|metadata| metadata.is_file())
+      .and_then(|metadata| metadata.modified().ok())
+      .and_then(|modified| now.duration_since(modified).ok())
+      .is_some_and(|age| age > CACHE_MAX_AGE);
+    if is_stale {
+      let _ = std::fs::remove_file(entry.path());
+    }
+  }
+}
+
 pub(crate) fn ensure_out_dir() -> EmbeddedAssetsResult<PathBuf> {
   let out_dir = std::env::var("OUT_DIR")
     .map_err(|_| EmbeddedAssetsError::OutDir)
@@ -444,3 +1025,226 @@ pub(crate) fn ensure_out_dir() -> EmbeddedAssetsResult<PathBuf> {
   std::fs::create_dir_all(&out_dir).map_err(|_| EmbeddedAssetsError::OutDir)?;
   Ok(out_dir)
 }
+
+/// Points `OUT_DIR` to a directory in the system temp dir, as the tests do not run in a build script.
+#[cfg(test)]
+pub(crate) fn set_test_out_dir() {
+  static ONCE: std::sync::Once = std::sync::Once::new();
+  ONCE.call_once(|| {
+    // OUT_DIR is `<target dir>/build/<package>-<hash>/out`
+    let out_dir = std::env::temp_dir()
+      .join("tauri-codegen-test")
+      .join("target")
+      .join("debug")
+      .join("build")
+      .join("app")
+      .join("out");
+    std::fs::create_dir_all(&out_dir).unwrap();
+    std::env::set_var("OUT_DIR", out_dir);
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    fs::File,
+    path::PathBuf,
+    time::{Duration, SystemTime},
+  };
+
+  use tauri_utils::{
+    assets::{AssetEncoding, AssetKey},
+    config::{AssetCompressionAlgorithm, AssetCompressionRule},
+  };
+
+  use super::{
+    evict_cache, Compression, CompressionRules, EmbeddedAssets, EmbeddedAssetsError, PendingAsset,
+    CACHE_MAX_AGE,
+  };
+
+  fn cache_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir()
+      .join("tauri-codegen-test")
+      .join("cache")
+      .join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  fn rule(
+    glob: &str,
+    algorithm: AssetCompressionAlgorithm,
+    level: Option<i32>,
+  ) -> AssetCompressionRule {
+    AssetCompressionRule {
+      glob: glob.into(),
+      algorithm,
+      level,
+    }
+  }
+
+  fn encoding(rules: &CompressionRules, key: &str) -> AssetEncoding {
+    rules.get(&AssetKey::from(key)).encoding()
+  }
+
+  #[test]
+  fn compression_rules() {
+    let rules = CompressionRules::new(&[
+      rule("**/*.png", AssetCompressionAlgorithm::None, None),
+      rule("*.wasm", AssetCompressionAlgorithm::None, None),
+      // never reached for PNG files, the first matching rule applies
+      rule("images/*", AssetCompressionAlgorithm::Brotli, Some(11)),
+    ])
+    .unwrap();
+
+    assert_eq!(encoding(&rules, "/logo.png"), AssetEncoding::Identity);
+    assert_eq!(
+      encoding(&rules, "/images/nested/logo.png"),
+      AssetEncoding::Identity
+    );
+    assert_eq!(
+      encoding(&rules, "/images/logo.png"),
+      AssetEncoding::Identity
+    );
+    assert_eq!(encoding(&rules, "/images/logo.svg"), AssetEncoding::DEFAULT);
+    assert_eq!(
+      rules
+        .get(&AssetKey::from("/images/logo.svg"))
+        .cache_suffix(),
+      if cfg!(feature = "compression") {
+        "br11"
+      } else {
+        "raw"
+      }
+    );
+    assert_eq!(encoding(&rules, "/app.wasm"), AssetEncoding::Identity);
+    // `*` does not match path separators
+    assert_eq!(encoding(&rules, "/pkg/app.wasm"), AssetEncoding::DEFAULT);
+    // assets that do not match any rule fall back to brotli
+    assert_eq!(encoding(&rules, "/index.html"), AssetEncoding::DEFAULT);
+    assert_eq!(
+      rules.get(&AssetKey::from("/index.html")).cache_suffix(),
+      Compression::default_brotli().cache_suffix()
+    );
+  }
+
+  #[test]
+  fn compression_rules_validation() {
+    assert!(matches!(
+      CompressionRules::new(&[rule("[", AssetCompressionAlgorithm::None, None)]),
+      Err(EmbeddedAssetsError::CompressionGlob { .. })
+    ));
+
+    for (algorithm, level) in [
+      (AssetCompressionAlgorithm::Brotli, -1),
+      (AssetCompressionAlgorithm::Brotli, 12),
+      (AssetCompressionAlgorithm::Zstd, 0),
+      (AssetCompressionAlgorithm::Zstd, 23),
+    ] {
+      assert!(matches!(
+        CompressionRules::new(&[rule("*.js", algorithm, Some(level))]),
+        Err(EmbeddedAssetsError::CompressionLevel { .. })
+      ));
+    }
+
+    assert!(CompressionRules::new(&[
+      rule("*.js", AssetCompressionAlgorithm::Brotli, Some(0)),
+      rule("*.css", AssetCompressionAlgorithm::Brotli, Some(11)),
+      // the level is ignored for uncompressed assets
+      rule("*.png", AssetCompressionAlgorithm::None, Some(100)),
+    ])
+    .is_ok());
+
+    let zstd = CompressionRules::new(&[rule("*.js", AssetCompressionAlgorithm::Zstd, Some(22))]);
+    #[cfg(feature = "compression-zstd")]
+    assert_eq!(encoding(&zstd.unwrap(), "/main.js"), AssetEncoding::Zstd);
+    #[cfg(all(feature = "compression", not(feature = "compression-zstd")))]
+    assert!(matches!(
+      zstd,
+      Err(EmbeddedAssetsError::CompressionZstdDisabled { .. })
+    ));
+    #[cfg(not(feature = "compression"))]
+    assert_eq!(
+      encoding(&zstd.unwrap(), "/main.js"),
+      AssetEncoding::Identity
+    );
+  }
+
+  fn pending(contents: &str) -> PendingAsset {
+    PendingAsset {
+      key: AssetKey::from("/main.js"),
+      path: PathBuf::from("main.js"),
+      input: contents.as_bytes().to_vec(),
+    }
+  }
+
+  #[test]
+  fn cache_reuse() {
+    let cache_dir = cache_dir("reuse");
+    let compression = Compression::default_brotli();
+
+    let (_, (_, first), digest, _) =
+      EmbeddedAssets::compress_file(&pending("console.log(1)"), compression, &cache_dir).unwrap();
+    assert_eq!(digest.size, 14);
+
+    // the cached file is reused instead of being compressed again
+    std::fs::write(&first, "cached").unwrap();
+    let (_, (_, second), _, _) =
+      EmbeddedAssets::compress_file(&pending("console.log(1)"), compression, &cache_dir).unwrap();
+    assert_eq!(first, second);
+    assert_eq!(std::fs::read_to_string(&second).unwrap(), "cached");
+
+    // different contents or compression settings use another file
+    let (_, (_, other), _, _) =
+      EmbeddedAssets::compress_file(&pending("console.log(2)"), compression, &cache_dir).unwrap();
+    assert_ne!(first, other);
+    #[cfg(feature = "compression")]
+    {
+      let (_, (_, uncompressed), _, _) =
+        EmbeddedAssets::compress_file(&pending("console.log(1)"), Compression::None, &cache_dir)
+          .unwrap();
+      assert_ne!(first, uncompressed);
+      assert_eq!(
+        std::fs::read_to_string(&uncompressed).unwrap(),
+        "console.log(1)"
+      );
+    }
+
+    // no temporary file is left behind
+    let expected = if cfg!(feature = "compression") { 3 } else { 2 };
+    assert_eq!(std::fs::read_dir(&cache_dir).unwrap().count(), expected);
+  }
+
+  #[test]
+  fn cache_eviction() {
+    let cache_dir = cache_dir("eviction");
+    let compression = Compression::None;
+
+    let (_, (_, used), _, _) =
+      EmbeddedAssets::compress_file(&pending("used"), compression, &cache_dir).unwrap();
+    let (_, (_, stale), _, _) =
+      EmbeddedAssets::compress_file(&pending("stale"), compression, &cache_dir).unwrap();
+    let leftover = cache_dir.join("asset.js.tmp");
+    std::fs::write(&leftover, "").unwrap();
+
+    let now = SystemTime::now();
+    let old = now - CACHE_MAX_AGE - Duration::from_secs(60);
+    for path in [&used, &stale, &leftover] {
+      File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(old)
+        .unwrap();
+    }
+
+    // using a cached file marks it as recently used
+    EmbeddedAssets::compress_file(&pending("used"), compression, &cache_dir).unwrap();
+    evict_cache(&cache_dir, now);
+
+    assert!(used.exists());
+    assert!(!stale.exists());
+    assert!(!leftover.exists());
+  }
+}
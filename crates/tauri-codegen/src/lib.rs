@@ -10,7 +10,7 @@
   html_favicon_url = "https://github.com/tauri-apps/tauri/raw/dev/.github/icon.png"
 )]
 
-pub use self::context::{context_codegen, ContextData};
+pub use self::context::{context_codegen, context_codegen_with_snapshot, ContextData};
 use crate::embedded_assets::{ensure_out_dir, EmbeddedAssetsError};
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens, TokenStreamExt};
@@ -26,6 +26,7 @@ use tauri_utils::write_if_changed;
 mod context;
 pub mod embedded_assets;
 pub mod image;
+mod snapshot;
 #[doc(hidden)]
 pub mod vendor;
 
@@ -0,0 +1,451 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! A stable, human-readable description of the generated `Context`.
+//!
+//! The snapshot is meant to be committed so changes to what ends up in the binary
+//! (configuration, embedded assets, CSP, ACL and icons) show up when reviewing a diff.
+
+use std::{
+  collections::{BTreeMap, BTreeSet, HashMap},
+  path::Path,
+};
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use tauri_utils::{
+  acl::{
+    resolved::{Resolved, ResolvedCommand, ResolvedScope},
+    ExecutionContext,
+  },
+  config::{Config, Csp},
+};
+
+use crate::embedded_assets::{EmbeddedAssets, EmbeddedAssetsError, EmbeddedAssetsResult};
+
+#[derive(Debug, Default, Serialize)]
+struct ConfigSnapshot {
+  sha256: String,
+  identifier: String,
+  product_name: Option<String>,
+  version: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct AssetSnapshot {
+  size: usize,
+  sha256: String,
+  /// The `Content-Encoding` of the embedded bytes, if they are compressed.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  encoding: Option<&'static str>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct HtmlCspSnapshot {
+  script_src: BTreeSet<String>,
+  style_src: BTreeSet<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct CspSnapshot {
+  policy: Option<BTreeMap<String, Vec<String>>>,
+  html: BTreeMap<String, HtmlCspSnapshot>,
+}
+
+#[derive(Debug, Serialize)]
+struct ScopeSnapshot {
+  allow: Vec<JsonValue>,
+  deny: Vec<JsonValue>,
+}
+
+impl From<&ResolvedScope> for ScopeSnapshot {
+  fn from(scope: &ResolvedScope) -> Self {
+    Self {
+      allow: scope.allow.iter().map(canonicalize).collect(),
+      deny: scope.deny.iter().map(canonicalize).collect(),
+    }
+  }
+}
+
+#[derive(Debug, Serialize)]
+struct CommandSnapshot {
+  context: String,
+  windows: Vec<String>,
+  webviews: Vec<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  scope: Option<ScopeSnapshot>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct AclSnapshot {
+  app_acl: bool,
+  allowed_commands: BTreeMap<String, Vec<CommandSnapshot>>,
+  denied_commands: BTreeMap<String, Vec<CommandSnapshot>>,
+  global_scope: BTreeMap<String, ScopeSnapshot>,
+}
+
+#[derive(Debug, Serialize)]
+struct IconSnapshot {
+  path: String,
+  sha256: String,
+}
+
+/// Collects the parts of the generated context that are relevant for review.
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct ContextSnapshot {
+  config: ConfigSnapshot,
+  assets: BTreeMap<String, AssetSnapshot>,
+  csp: CspSnapshot,
+  acl: AclSnapshot,
+  icons: BTreeMap<String, IconSnapshot>,
+}
+
+impl ContextSnapshot {
+  pub(crate) fn new(config: &Config, csp: Option<&Csp>) -> Self {
+    let config_json = serde_json::to_value(config)
+      .map(|value| canonicalize(&value))
+      .unwrap_or_default();
+    Self {
+      config: ConfigSnapshot {
+        sha256: sha256_hex(config_json.to_string().as_bytes()),
+        identifier: config.identifier.clone(),
+        product_name: config.product_name.clone(),
+        version: config.version.clone(),
+      },
+      csp: CspSnapshot {
+        policy: csp.map(|csp| {
+          HashMap::from(csp.clone())
+            .into_iter()
+            .filter(|(directive, _)| !directive.is_empty())
+            .map(|(directive, sources)| (directive, sources.into()))
+            .collect()
+        }),
+        html: Default::default(),
+      },
+      ..Default::default()
+    }
+  }
+
+  /// Records the embedded assets and the CSP hashes injected in each HTML file.
+  pub(crate) fn assets(&mut self, assets: &EmbeddedAssets) {
+    let csp_hashes = assets.csp_hashes();
+    let encodings = assets.encodings();
+    for (key, digest) in assets.digests() {
+      let key: &str = key.as_ref();
+      self.assets.insert(
+        key.to_string(),
+        AssetSnapshot {
+          size: digest.size,
+          sha256: digest.sha256.clone(),
+          encoding: encodings
+            .get(key)
+            .and_then(|encoding| encoding.content_encoding()),
+        },
+      );
+
+      if key.ends_with(".html") && self.csp.policy.is_some() {
+        let mut script_src = csp_hashes.scripts.iter().cloned().collect::<BTreeSet<_>>();
+        if let Some(inline_scripts) = csp_hashes.inline_scripts.get(key) {
+          script_src.extend(inline_scripts.iter().cloned());
+        }
+        self.csp.html.insert(
+          key.to_string(),
+          HtmlCspSnapshot {
+            script_src,
+            style_src: csp_hashes.styles.iter().cloned().collect(),
+          },
+        );
+      }
+    }
+  }
+
+  /// Records the resolved access control list.
+  pub(crate) fn acl(&mut self, resolved: &Resolved) {
+    let commands = |commands: &BTreeMap<String, Vec<ResolvedCommand>>| {
+      commands
+        .iter()
+        .map(|(command, resolved_commands)| {
+          let mut snapshots = resolved_commands
+            .iter()
+            .map(|resolved_command| CommandSnapshot {
+              context: match &resolved_command.context {
+                ExecutionContext::Local => "local".into(),
+                ExecutionContext::Remote { url } => url.as_str().into(),
+              },
+              windows: resolved_command
+                .windows
+                .iter()
+                .map(|w| w.as_str().to_string())
+                .collect(),
+              webviews: resolved_command
+                .webviews
+                .iter()
+                .map(|w| w.as_str().to_string())
+                .collect(),
+              scope: resolved_command
+                .scope_id
+                .and_then(|id| resolved.command_scope.get(&id))
+                .map(Into::into),
+            })
+            .collect::<Vec<_>>();
+          // the resolution order depends on the capability ordering, which is not relevant here
+          snapshots.sort_by_cached_key(|s| serde_json::to_string(s).unwrap_or_default());
+          (command.clone(), snapshots)
+        })
+        .collect()
+    };
+
+    self.acl = AclSnapshot {
+      app_acl: resolved.has_app_acl,
+      allowed_commands: commands(&resolved.allowed_commands),
+      denied_commands: commands(&resolved.denied_commands),
+      global_scope: resolved
+        .global_scope
+        .iter()
+        .map(|(key, scope)| (key.clone(), scope.into()))
+        .collect(),
+    };
+  }
+
+  /// Records an icon embedded in the context.
+  pub(crate) fn icon(&mut self, name: &str, config_parent: &Path, path: &Path) {
+    let sha256 = std::fs::read(path)
+      .map(|contents| sha256_hex(&contents))
+      .unwrap_or_default();
+    let path = path.strip_prefix(config_parent).unwrap_or(path);
+    self.icons.insert(
+      name.into(),
+      IconSnapshot {
+        path: path.display().to_string().replace('\\', "/"),
+        sha256,
+      },
+    );
+  }
+
+  /// Writes the snapshot to the given path, leaving the file untouched if it did not change.
+  pub(crate) fn write(&self, path: &Path) -> EmbeddedAssetsResult<()> {
+    let mut contents = serde_json::to_string_pretty(self).expect("failed to serialize snapshot");
+    contents.push('\n');
+
+    if let Some(parent) = path.parent() {
+      std::fs::create_dir_all(parent).map_err(|error| EmbeddedAssetsError::AssetWrite {
+        path: path.to_path_buf(),
+        error,
+      })?;
+    }
+    tauri_utils::write_if_changed(path, contents).map_err(|error| EmbeddedAssetsError::AssetWrite {
+      path: path.to_path_buf(),
+      error,
+    })
+  }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+  Sha256::digest(bytes)
+    .iter()
+    .map(|b| format!("{b:02x}"))
+    .collect()
+}
+
+/// Sorts the object keys so the value serializes the same way regardless of the map implementation.
+fn canonicalize(value: &JsonValue) -> JsonValue {
+  match value {
+    JsonValue::Object(map) => {
+      let sorted = map.iter().collect::<BTreeMap<_, _>>();
+      JsonValue::Object(
+        sorted
+          .into_iter()
+          .map(|(key, value)| (key.clone(), canonicalize(value)))
+          .collect(),
+      )
+    }
+    JsonValue::Array(values) => JsonValue::Array(values.iter().map(canonicalize).collect()),
+    value => value.clone(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{collections::BTreeMap, path::PathBuf};
+
+  use serde_json::{json, Value as JsonValue};
+  use tauri_utils::{
+    acl::{
+      resolved::{Resolved, ResolvedCommand, ResolvedScope},
+      ExecutionContext,
+    },
+    config::{Config, Csp, PatternKind},
+  };
+
+  use super::{canonicalize, sha256_hex, ContextSnapshot};
+  use crate::embedded_assets::{set_test_out_dir, AssetOptions, EmbeddedAssets};
+
+  fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir()
+      .join("tauri-codegen-test")
+      .join("snapshot")
+      .join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  fn to_json(snapshot: &ContextSnapshot) -> JsonValue {
+    serde_json::to_value(snapshot).unwrap()
+  }
+
+  #[test]
+  fn canonicalize_sorts_keys() {
+    let value = json!({ "b": 1, "a": { "d": [{ "f": 1, "e": 2 }], "c": 3 } });
+    assert_eq!(
+      canonicalize(&value).to_string(),
+      r#"{"a":{"c":3,"d":[{"e":2,"f":1}]},"b":1}"#
+    );
+  }
+
+  #[test]
+  fn config_and_csp() {
+    let config: Config = serde_json::from_value(json!({
+      "identifier": "app.tauri.snapshot",
+      "productName": "Snapshot",
+      "version": "1.0.0",
+    }))
+    .unwrap();
+    let csp = Csp::Policy("default-src 'self'; script-src 'self' https://tauri.app".into());
+
+    let snapshot = to_json(&ContextSnapshot::new(&config, Some(&csp)));
+    assert_eq!(snapshot["config"]["identifier"], "app.tauri.snapshot");
+    assert_eq!(snapshot["config"]["product_name"], "Snapshot");
+    assert_eq!(
+      snapshot["csp"]["policy"],
+      json!({
+        "default-src": ["'self'"],
+        "script-src": ["'self'", "https://tauri.app"],
+      })
+    );
+
+    // the digest only depends on the config
+    let same = to_json(&ContextSnapshot::new(&config, None));
+    assert_eq!(snapshot["config"]["sha256"], same["config"]["sha256"]);
+    assert!(same["csp"]["policy"].is_null());
+
+    let mut changed = config.clone();
+    changed.version = Some("1.0.1".into());
+    let changed = to_json(&ContextSnapshot::new(&changed, None));
+    assert_ne!(snapshot["config"]["sha256"], changed["config"]["sha256"]);
+  }
+
+  #[test]
+  fn acl_is_sorted() {
+    let command = |url: Option<&str>, window: &str| ResolvedCommand {
+      context: match url {
+        Some(url) => ExecutionContext::Remote {
+          url: url.parse().unwrap(),
+        },
+        None => ExecutionContext::Local,
+      },
+      windows: vec![glob::Pattern::new(window).unwrap()],
+      scope_id: url.is_none().then_some(1),
+      ..Default::default()
+    };
+    let resolved = |commands: Vec<ResolvedCommand>| Resolved {
+      has_app_acl: true,
+      allowed_commands: BTreeMap::from([("plugin:fs|read".to_string(), commands)]),
+      command_scope: BTreeMap::from([(
+        1,
+        ResolvedScope {
+          allow: vec![json!({ "path": "$APPDATA", "recursive": true })],
+          deny: Vec::new(),
+        },
+      )]),
+      ..Default::default()
+    };
+
+    let local = command(None, "main");
+    let remote = command(Some("https://tauri.app"), "*");
+
+    let mut first = ContextSnapshot::default();
+    first.acl(&resolved(vec![local.clone(), remote.clone()]));
+    let mut second = ContextSnapshot::default();
+    second.acl(&resolved(vec![remote, local]));
+
+    let first = to_json(&first);
+    assert_eq!(first, to_json(&second));
+    assert_eq!(first["acl"]["app_acl"], true);
+
+    let commands = first["acl"]["allowed_commands"]["plugin:fs|read"]
+      .as_array()
+      .unwrap();
+    assert_eq!(commands.len(), 2);
+    let local = commands.iter().find(|c| c["context"] == "local").unwrap();
+    assert_eq!(local["windows"], json!(["main"]));
+    assert_eq!(
+      local["scope"],
+      json!({ "allow": [{ "path": "$APPDATA", "recursive": true }], "deny": [] })
+    );
+  }
+
+  #[test]
+  fn icons_and_assets() {
+    set_test_out_dir();
+    let dir = test_dir("icons-and-assets");
+    let dist = dir.join("dist");
+    std::fs::create_dir_all(&dist).unwrap();
+    std::fs::write(dist.join("index.html"), "<html></html>").unwrap();
+    std::fs::write(dist.join("main.js"), "console.log(1)").unwrap();
+    std::fs::create_dir_all(dir.join("icons")).unwrap();
+    let icon = dir.join("icons").join("icon.png");
+    std::fs::write(&icon, b"png").unwrap();
+
+    let mut snapshot = ContextSnapshot::default();
+    snapshot.icon("default_window_icon", &dir, &icon);
+
+    let assets = EmbeddedAssets::new(
+      dist,
+      &AssetOptions::new(PatternKind::Brownfield),
+      |_, _, _, _| Ok(()),
+    )
+    .unwrap();
+    snapshot.assets(&assets);
+
+    let snapshot = to_json(&snapshot);
+    assert_eq!(
+      snapshot["icons"]["default_window_icon"],
+      json!({ "path": "icons/icon.png", "sha256": sha256_hex(b"png") })
+    );
+    assert_eq!(snapshot["assets"]["/main.js"]["size"], 14);
+    assert_eq!(
+      snapshot["assets"]["/main.js"]["sha256"],
+      sha256_hex(b"console.log(1)")
+    );
+    assert_eq!(snapshot["assets"]["/index.html"]["size"], 13);
+    // CSP hashes are only recorded when a CSP is configured
+    assert_eq!(snapshot["csp"]["html"], json!({}));
+  }
+
+  #[test]
+  fn write_if_changed() {
+    let dir = test_dir("write");
+    let path = dir.join("nested").join("context.json");
+
+    let snapshot = ContextSnapshot::default();
+    snapshot.write(&path).unwrap();
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(contents.ends_with("}\n"));
+    assert_eq!(
+      serde_json::from_str::<JsonValue>(&contents).unwrap(),
+      to_json(&snapshot)
+    );
+
+    let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    snapshot.write(&path).unwrap();
+    assert_eq!(
+      std::fs::metadata(&path).unwrap().modified().unwrap(),
+      modified
+    );
+  }
+}
@@ -0,0 +1,386 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Artifacts recorded to debug failing sessions, enabled with `--artifacts-dir`.
+//!
+//! Each session gets its own `{artifacts dir}/{session id}` directory with:
+//! - `stdout.log` and `stderr.log`: the output of the native WebDriver server and of the application it launched.
+//! - `{n}-failure.json` and `{n}-screenshot.png`: the request and error response of each failing command,
+//!   with a screenshot of the current window taken right after it failed.
+//!
+//! Errors a client expects while polling, such as `no such element` in a wait loop, are not failures,
+//! and a failure repeating the previous one of the session is recorded without a screenshot.
+
+use base64::Engine;
+use http_body_util::{BodyExt, Full};
+use hyper::{body::Bytes, Request, StatusCode};
+use hyper_util::client::legacy::{connect::HttpConnector, Client};
+use serde_json::{json, Value};
+use std::{
+  fs::File,
+  io::{Read, Write},
+  path::{Path, PathBuf},
+  str::FromStr,
+  sync::{Arc, Mutex},
+};
+
+/// Which artifacts are recorded.
+#[derive(Debug, Clone, Copy)]
+pub struct Artifacts {
+  pub logs: bool,
+  pub screenshots: bool,
+}
+
+impl Default for Artifacts {
+  fn default() -> Self {
+    Self {
+      logs: true,
+      screenshots: true,
+    }
+  }
+}
+
+impl FromStr for Artifacts {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut artifacts = Self {
+      logs: false,
+      screenshots: false,
+    };
+    for kind in s.split(',').map(str::trim).filter(|kind| !kind.is_empty()) {
+      match kind {
+        "logs" => artifacts.logs = true,
+        "screenshots" => artifacts.screenshots = true,
+        kind => {
+          return Err(format!(
+            "unknown artifact `{kind}`, expected `logs` or `screenshots`"
+          ))
+        }
+      }
+    }
+    Ok(artifacts)
+  }
+}
+
+/// The [errors](https://www.w3.org/TR/webdriver/#errors) clients poll through, which are not recorded as failures.
+const EXPECTED_ERRORS: &[&str] = &[
+  "no such element",
+  "no such alert",
+  "no such cookie",
+  "no such shadow root",
+];
+
+/// The artifacts directory of a session.
+pub fn session_dir(artifacts_dir: &Path, session: &str) -> PathBuf {
+  artifacts_dir.join(session)
+}
+
+enum LogOutput {
+  /// The session is not known yet.
+  Buffer(Vec<u8>),
+  File(File),
+}
+
+/// The output captured from a pipe, buffered until the session it belongs to is known.
+#[derive(Clone)]
+pub struct Log(Arc<Mutex<LogOutput>>);
+
+impl Log {
+  /// Captures the output of `pipe` on a separate thread, until it is closed.
+  pub fn capture(pipe: impl Read + Send + 'static) -> Self {
+    Self::tee(pipe, std::io::sink())
+  }
+
+  /// Captures the output of `pipe` like [`Self::capture`], also copying it to `output`.
+  pub fn tee(
+    mut pipe: impl Read + Send + 'static,
+    mut output: impl Write + Send + 'static,
+  ) -> Self {
+    let log = Self(Arc::new(Mutex::new(LogOutput::Buffer(Vec::new()))));
+    let log_ = log.clone();
+    std::thread::spawn(move || {
+      let mut buf = [0; 8192];
+      loop {
+        match pipe.read(&mut buf) {
+          Ok(0) | Err(_) => break,
+          Ok(n) => {
+            let _ = output.write_all(&buf[..n]);
+            log_.write(&buf[..n]);
+          }
+        }
+      }
+    });
+    log
+  }
+
+  fn write(&self, data: &[u8]) {
+    match &mut *self.0.lock().expect("poisoned log") {
+      LogOutput::Buffer(buffer) => buffer.extend_from_slice(data),
+      LogOutput::File(file) => {
+        let _ = file.write_all(data);
+      }
+    }
+  }
+
+  /// Writes the output captured so far to `path`, and the rest of the output as it comes.
+  pub fn persist(&self, path: &Path) -> std::io::Result<()> {
+    let mut output = self.0.lock().expect("poisoned log");
+    if let LogOutput::Buffer(buffer) = &*output {
+      let mut file = File::create(path)?;
+      file.write_all(buffer)?;
+      *output = LogOutput::File(file);
+    }
+    Ok(())
+  }
+}
+
+/// A command that failed.
+pub struct Failure {
+  pub method: String,
+  pub path: String,
+  pub status: StatusCode,
+  pub body: Bytes,
+}
+
+impl Failure {
+  /// The error code of the response, e.g. `no such window`.
+  pub fn error(&self) -> Option<String> {
+    let body: Value = serde_json::from_slice(&self.body).ok()?;
+    body.pointer("/value/error")?.as_str().map(Into::into)
+  }
+
+  /// Whether the client expects the command to fail while polling, so it is not worth recording.
+  pub fn is_expected(&self) -> bool {
+    self
+      .error()
+      .is_some_and(|error| EXPECTED_ERRORS.contains(&error.as_str()))
+  }
+
+  /// Identifies repeated failures.
+  pub fn signature(&self) -> String {
+    format!(
+      "{} {} {}",
+      self.method,
+      self.path,
+      self.error().unwrap_or_default()
+    )
+  }
+}
+
+/// Records a failing command of a session, with a screenshot of its current window if `screenshot` is set.
+///
+/// It is spawned so the response is not delayed by the screenshot, which then shows the window
+/// shortly after the failure. `native_url` is the base URL of the native WebDriver server of the
+/// session, `http://{host}:{port}`.
+pub async fn record_failure(
+  client: Client<HttpConnector, Full<Bytes>>,
+  screenshot: bool,
+  dir: PathBuf,
+  native_url: String,
+  session: String,
+  index: usize,
+  failure: Failure,
+) {
+  if let Err(e) = std::fs::create_dir_all(&dir) {
+    eprintln!(
+      "failed to create artifacts directory {}: {e}",
+      dir.display()
+    );
+    return;
+  }
+
+  let Failure {
+    method,
+    path,
+    status,
+    body,
+  } = failure;
+  let failure = json!({
+    "method": method,
+    "path": path,
+    "status": status.as_u16(),
+    "response": serde_json::from_slice::<Value>(&body)
+      .unwrap_or_else(|_| String::from_utf8_lossy(&body).into()),
+  });
+  let failure_path = dir.join(format!("{index:03}-failure.json"));
+  if let Err(e) = std::fs::write(
+    &failure_path,
+    serde_json::to_vec_pretty(&failure).expect("failed to serialize JSON"),
+  ) {
+    eprintln!("failed to write {}: {e}", failure_path.display());
+  }
+
+  if screenshot && !path.ends_with("/screenshot") {
+    match take_screenshot(&client, &native_url, &session).await {
+      Some(png) => {
+        let screenshot_path = dir.join(format!("{index:03}-screenshot.png"));
+        if let Err(e) = std::fs::write(&screenshot_path, png) {
+          eprintln!("failed to write {}: {e}", screenshot_path.display());
+        }
+      }
+      None => eprintln!("failed to take a screenshot of session {session}"),
+    }
+  }
+}
+
+/// Takes a screenshot of the current window of a session, as PNG.
+async fn take_screenshot(
+  client: &Client<HttpConnector, Full<Bytes>>,
+  native_url: &str,
+  session: &str,
+) -> Option<Vec<u8>> {
+  let request = Request::get(format!("{native_url}/session/{session}/screenshot"))
+    .body(Full::default())
+    .ok()?;
+  let response = client.request(request).await.ok()?;
+  if !response.status().is_success() {
+    return None;
+  }
+  let body = response.into_body().collect().await.ok()?.to_bytes();
+  let json: Value = serde_json::from_slice(&body).ok()?;
+  base64::engine::general_purpose::STANDARD
+    .decode(json.get("value")?.as_str()?)
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn failure(method: &str, path: &str, error: &str) -> Failure {
+    Failure {
+      method: method.into(),
+      path: path.into(),
+      status: StatusCode::NOT_FOUND,
+      body: serde_json::to_vec(&json!({ "value": { "error": error, "message": "" } }))
+        .unwrap()
+        .into(),
+    }
+  }
+
+  #[test]
+  fn parse_artifacts() {
+    let artifacts: Artifacts = "logs".parse().unwrap();
+    assert!(artifacts.logs && !artifacts.screenshots);
+    let artifacts: Artifacts = " screenshots , logs ".parse().unwrap();
+    assert!(artifacts.logs && artifacts.screenshots);
+    let artifacts: Artifacts = "".parse().unwrap();
+    assert!(!artifacts.logs && !artifacts.screenshots);
+    assert!("videos".parse::<Artifacts>().is_err());
+  }
+
+  #[test]
+  fn expected_failures() {
+    assert!(failure("POST", "/session/1/element", "no such element").is_expected());
+    assert!(failure("GET", "/session/1/alert/text", "no such alert").is_expected());
+    assert!(!failure("POST", "/session/1/window", "no such window").is_expected());
+
+    let mut unknown = failure("GET", "/session/1/url", "");
+    unknown.body = Bytes::from_static(b"not json");
+    assert!(!unknown.is_expected());
+    assert_eq!(unknown.error(), None);
+  }
+
+  #[test]
+  fn signature() {
+    let click = failure(
+      "POST",
+      "/session/1/element/2/click",
+      "element not interactable",
+    );
+    assert_eq!(
+      click.signature(),
+      failure(
+        "POST",
+        "/session/1/element/2/click",
+        "element not interactable"
+      )
+      .signature()
+    );
+    assert_ne!(
+      click.signature(),
+      failure(
+        "POST",
+        "/session/1/element/3/click",
+        "element not interactable"
+      )
+      .signature()
+    );
+    assert_ne!(
+      click.signature(),
+      failure(
+        "POST",
+        "/session/1/element/2/click",
+        "stale element reference"
+      )
+      .signature()
+    );
+  }
+
+  /// A writer shared with the test.
+  #[derive(Clone, Default)]
+  struct Shared(Arc<Mutex<Vec<u8>>>);
+
+  impl Write for Shared {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+      self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+      Ok(())
+    }
+  }
+
+  /// A pipe fed by the test, closed when the sender is dropped.
+  struct Pipe(std::sync::mpsc::Receiver<Vec<u8>>);
+
+  impl Read for Pipe {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+      match self.0.recv() {
+        Ok(data) => {
+          buf[..data.len()].copy_from_slice(&data);
+          Ok(data.len())
+        }
+        Err(_) => Ok(0),
+      }
+    }
+  }
+
+  /// Waits for the capture thread to read everything.
+  fn wait_for(output: &Shared, expected: &[u8]) {
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while *output.0.lock().unwrap() != expected {
+      assert!(std::time::Instant::now() < deadline, "timed out");
+      std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+  }
+
+  #[test]
+  fn log_tee_and_persist() {
+    let dir = std::env::temp_dir().join(format!("tauri-driver-log-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("stderr.log");
+
+    let (writer, reader) = std::sync::mpsc::channel();
+    let output = Shared::default();
+    let log = Log::tee(Pipe(reader), output.clone());
+
+    writer.send(b"before\n".to_vec()).unwrap();
+    wait_for(&output, b"before\n");
+    log.persist(&path).unwrap();
+
+    writer.send(b"after\n".to_vec()).unwrap();
+    drop(writer);
+    wait_for(&output, b"before\nafter\n");
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while std::fs::read(&path).unwrap() != b"before\nafter\n" {
+      assert!(std::time::Instant::now() < deadline, "timed out");
+      std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+
+    let _ = std::fs::remove_dir_all(dir);
+  }
+}
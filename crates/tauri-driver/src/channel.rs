@@ -0,0 +1,216 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The channel to the `tauri::test::webdriver` plugin of the application of a session.
+//!
+//! Every session listens on its own local port, passed to the application with the address and a random token
+//! in the `TAURI_WEBDRIVER_CHANNEL` and `TAURI_WEBDRIVER_TOKEN` environment variables. The plugin connects to it
+//! and sends the token on the first line, then every request and its response is a JSON object on a single line.
+//!
+//! The application connects once it is launched by the native WebDriver, so the first request waits for it,
+//! and a closed connection is replaced by the next one, for instance when the application is restarted.
+
+use crate::error::WebDriverError;
+use serde_json::Value;
+use std::{net::SocketAddr, time::Duration};
+use tokio::{
+  io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+  net::{
+    tcp::{OwnedReadHalf, OwnedWriteHalf},
+    TcpListener,
+  },
+  sync::Mutex,
+};
+
+/// The environment variable holding the address the plugin connects to.
+const CHANNEL_ENV: &str = "TAURI_WEBDRIVER_CHANNEL";
+
+/// The environment variable holding the token the plugin authenticates with.
+const TOKEN_ENV: &str = "TAURI_WEBDRIVER_TOKEN";
+
+/// How long a request waits for the application to connect.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a new connection has to send the token.
+const HELLO_TIMEOUT: Duration = Duration::from_secs(1);
+
+struct Connection {
+  reader: BufReader<OwnedReadHalf>,
+  writer: OwnedWriteHalf,
+}
+
+/// Why a request could not be serviced.
+pub enum ChannelError {
+  /// The request did not reach the application or its response was lost.
+  Channel(WebDriverError),
+  /// The application answered with an error.
+  Application(Value),
+}
+
+impl From<WebDriverError> for ChannelError {
+  fn from(error: WebDriverError) -> Self {
+    Self::Channel(error)
+  }
+}
+
+/// The channel to the plugin of a session.
+pub struct PluginChannel {
+  listener: TcpListener,
+  address: SocketAddr,
+  token: String,
+  connection: Mutex<Option<Connection>>,
+}
+
+impl PluginChannel {
+  /// Listens for the plugin on a free local port.
+  pub async fn bind() -> std::io::Result<Self> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    let mut token = [0u8; 32];
+    getrandom::fill(&mut token).expect("failed to get random bytes");
+    Ok(Self {
+      listener,
+      address,
+      token: token.iter().map(|b| format!("{b:02x}")).collect(),
+      connection: Default::default(),
+    })
+  }
+
+  /// The environment variables telling the plugin how to connect.
+  pub fn env(&self) -> [(&'static str, String); 2] {
+    [
+      (CHANNEL_ENV, self.address.to_string()),
+      (TOKEN_ENV, self.token.clone()),
+    ]
+  }
+
+  /// Sends a request to the plugin, returning the value of its response.
+  ///
+  /// Requests are sent one at a time.
+  pub async fn request(&self, request: Value) -> Result<Value, ChannelError> {
+    let mut connection = self.connection.lock().await;
+    let mut current = match connection.take() {
+      Some(current) => current,
+      None => tokio::time::timeout(CONNECT_TIMEOUT, self.accept())
+        .await
+        .map_err(|_| {
+          WebDriverError::unknown_error(
+            "the application did not connect to tauri-driver, is the `tauri::test::webdriver` plugin registered?",
+          )
+        })?
+        .map_err(|e| WebDriverError::unknown_error(format!("tauri-driver channel error: {e}")))?,
+    };
+
+    let response = Self::send(&mut current, &request)
+      .await
+      .map_err(|e| WebDriverError::unknown_error(format!("tauri-driver channel error: {e}")))?;
+    // only keep connections that are still in sync
+    *connection = Some(current);
+
+    let mut response: Value = serde_json::from_str(&response).map_err(|e| {
+      WebDriverError::unknown_error(format!("invalid response of the application: {e}"))
+    })?;
+    if let Some(error) = response.get_mut("error") {
+      return Err(ChannelError::Application(error.take()));
+    }
+    Ok(
+      response
+        .get_mut("value")
+        .map(Value::take)
+        .unwrap_or_default(),
+    )
+  }
+
+  async fn send(connection: &mut Connection, request: &Value) -> std::io::Result<String> {
+    let mut line = serde_json::to_vec(request).expect("failed to serialize JSON");
+    line.push(b'\n');
+    connection.writer.write_all(&line).await?;
+
+    let mut response = String::new();
+    if connection.reader.read_line(&mut response).await? == 0 {
+      return Err(std::io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(response)
+  }
+
+  /// Waits for the plugin to connect, ignoring the connections that do not send the token.
+  async fn accept(&self) -> std::io::Result<Connection> {
+    loop {
+      let (stream, _) = self.listener.accept().await?;
+      let (reader, writer) = stream.into_split();
+      let mut reader = BufReader::new(reader);
+
+      let mut hello = String::new();
+      let read = tokio::time::timeout(HELLO_TIMEOUT, reader.read_line(&mut hello)).await;
+      if matches!(read, Ok(Ok(_))) && hello.trim_end() == self.token {
+        return Ok(Connection { reader, writer });
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+  use super::*;
+  use serde_json::json;
+  use tokio::net::TcpStream;
+
+  /// Connects a fake plugin to the channel, answering every request with the given function.
+  pub(crate) async fn connect_plugin(
+    channel: &PluginChannel,
+    respond: impl Fn(Value) -> Value + Send + 'static,
+  ) {
+    connect(channel, &channel.token, respond).await;
+  }
+
+  async fn connect(
+    channel: &PluginChannel,
+    token: &str,
+    respond: impl Fn(Value) -> Value + Send + 'static,
+  ) {
+    let stream = TcpStream::connect(channel.address).await.unwrap();
+    let token = token.to_string();
+    tokio::spawn(async move {
+      let (reader, mut writer) = stream.into_split();
+      writer
+        .write_all(format!("{token}\n").as_bytes())
+        .await
+        .unwrap();
+      let mut lines = BufReader::new(reader).lines();
+      while let Ok(Some(line)) = lines.next_line().await {
+        let response = respond(serde_json::from_str(&line).unwrap());
+        let mut response = serde_json::to_vec(&response).unwrap();
+        response.push(b'\n');
+        if writer.write_all(&response).await.is_err() {
+          break;
+        }
+      }
+    });
+  }
+
+  #[tokio::test]
+  async fn request() {
+    let channel = PluginChannel::bind().await.unwrap();
+    let env = channel.env();
+    assert_eq!(env[0].1, channel.address.to_string());
+    assert_eq!(env[1].1.len(), 64);
+
+    // connections without the token are ignored
+    connect(&channel, "wrong", |_| json!({ "value": "intruder" })).await;
+    connect_plugin(&channel, |request| match request["type"].as_str() {
+      Some("ping") => json!({ "value": "pong" }),
+      _ => json!({ "error": "unknown request" }),
+    })
+    .await;
+
+    assert!(matches!(
+      channel.request(json!({ "type": "ping" })).await,
+      Ok(value) if value == "pong"
+    ));
+    assert!(matches!(
+      channel.request(json!({ "type": "other" })).await,
+      Err(ChannelError::Application(error)) if error == "unknown request"
+    ));
+  }
+}
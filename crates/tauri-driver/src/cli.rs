@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
+use crate::artifacts::Artifacts;
 use std::path::PathBuf;
 
 const HELP: &str = "\
@@ -12,9 +13,13 @@ FLAGS:
 
 OPTIONS:
   --port NUMBER           Sets the tauri-driver intermediary port
-  --native-port NUMBER    Sets the port of the underlying WebDriver
+  --native-port NUMBER    Sets the first port of the underlying WebDriver servers
   --native-host HOST      Sets the host of the underlying WebDriver (Linux only)
   --native-driver PATH    Sets the path to the native WebDriver binary
+  --max-sessions NUMBER   Sets the maximum number of concurrent sessions
+  --artifacts-dir PATH    Records artifacts of each session in this directory
+  --artifacts LIST        Sets the recorded artifacts, a comma separated list of
+                          `logs` and `screenshots` (default: logs,screenshots)
 ";
 
 #[derive(Debug, Clone)]
@@ -23,6 +28,9 @@ pub struct Args {
   pub native_port: u16,
   pub native_host: String,
   pub native_driver: Option<PathBuf>,
+  pub max_sessions: Option<usize>,
+  pub artifacts_dir: Option<PathBuf>,
+  pub artifacts: Artifacts,
 }
 
 impl From<pico_args::Arguments> for Args {
@@ -41,6 +49,30 @@ impl From<pico_args::Arguments> for Args {
       }
     };
 
+    let max_sessions = match args.opt_value_from_str("--max-sessions") {
+      Ok(max_sessions) => max_sessions,
+      Err(e) => {
+        eprintln!("Error while parsing option --max-sessions: {e}");
+        std::process::exit(1);
+      }
+    };
+
+    let artifacts_dir = match args.opt_value_from_str("--artifacts-dir") {
+      Ok(artifacts_dir) => artifacts_dir,
+      Err(e) => {
+        eprintln!("Error while parsing option --artifacts-dir: {e}");
+        std::process::exit(1);
+      }
+    };
+
+    let artifacts = match args.opt_value_from_str("--artifacts") {
+      Ok(artifacts) => artifacts.unwrap_or_default(),
+      Err(e) => {
+        eprintln!("Error while parsing option --artifacts: {e}");
+        std::process::exit(1);
+      }
+    };
+
     let parsed = Args {
       port: args.value_from_str("--port").unwrap_or(4444),
       native_port: args.value_from_str("--native-port").unwrap_or(4445),
@@ -48,6 +80,9 @@ impl From<pico_args::Arguments> for Args {
         .value_from_str("--native-host")
         .unwrap_or(String::from("127.0.0.1")),
       native_driver,
+      max_sessions,
+      artifacts_dir,
+      artifacts,
     };
 
     // be strict about accepting args, error for anything extraneous
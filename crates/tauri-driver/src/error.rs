@@ -0,0 +1,96 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! [Errors](https://www.w3.org/TR/webdriver/#errors) returned by `tauri-driver` itself.
+
+use http_body_util::Full;
+use hyper::{
+  body::Bytes,
+  header::{CONTENT_LENGTH, CONTENT_TYPE},
+  Response, StatusCode,
+};
+use serde_json::{json, Value};
+
+/// A WebDriver error, serialized as the value of an error response.
+#[derive(Debug)]
+pub struct WebDriverError {
+  status: StatusCode,
+  error: &'static str,
+  message: String,
+  data: Option<Value>,
+}
+
+impl WebDriverError {
+  pub fn new(status: StatusCode, error: &'static str, message: impl Into<String>) -> Self {
+    Self {
+      status,
+      error,
+      message: message.into(),
+      data: None,
+    }
+  }
+
+  pub fn invalid_argument(message: impl Into<String>) -> Self {
+    Self::new(StatusCode::BAD_REQUEST, "invalid argument", message)
+  }
+
+  pub fn unknown_command(message: impl Into<String>) -> Self {
+    Self::new(StatusCode::NOT_FOUND, "unknown command", message)
+  }
+
+  pub fn invalid_session_id(session: &str) -> Self {
+    Self::new(
+      StatusCode::NOT_FOUND,
+      "invalid session id",
+      format!("no active session with id {session}"),
+    )
+  }
+
+  pub fn session_not_created(message: impl Into<String>) -> Self {
+    Self::new(
+      StatusCode::INTERNAL_SERVER_ERROR,
+      "session not created",
+      message,
+    )
+  }
+
+  pub fn unknown_error(message: impl Into<String>) -> Self {
+    Self::new(StatusCode::INTERNAL_SERVER_ERROR, "unknown error", message)
+  }
+
+  /// An error returned by the application, kept as the `data` of the response.
+  pub fn application(data: Value) -> Self {
+    let message = match &data {
+      Value::String(message) => message.clone(),
+      data => data.to_string(),
+    };
+    Self {
+      data: Some(data),
+      ..Self::new(StatusCode::INTERNAL_SERVER_ERROR, "unknown error", message)
+    }
+  }
+
+  pub fn into_response(self) -> Response<Full<Bytes>> {
+    let mut value = json!({
+      "error": self.error,
+      "message": self.message,
+      "stacktrace": "",
+    });
+    if let Some(data) = self.data {
+      value["data"] = data;
+    }
+    json_response(self.status, json!({ "value": value }))
+  }
+}
+
+/// A JSON response with the given status.
+pub fn json_response(status: StatusCode, value: Value) -> Response<Full<Bytes>> {
+  let body = serde_json::to_vec(&value).expect("failed to serialize JSON");
+  Response::builder()
+    .status(status)
+    .header(CONTENT_TYPE, "application/json; charset=utf-8")
+    .header(CONTENT_LENGTH, body.len())
+    .body(Full::new(body.into()))
+    .expect("failed to build response")
+}
@@ -0,0 +1,758 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Tauri-aware [extension commands](https://www.w3.org/TR/webdriver/#dfn-extension-commands).
+//!
+//! The `/session/{session id}/tauri/*` endpoints are serviced by `tauri-driver` itself. Windows are
+//! switched through the native WebDriver, everything else is sent to the `tauri::test::webdriver`
+//! plugin of the application through the [channel](crate::channel) of the session, so the
+//! application must register that plugin in the builds it runs its end-to-end tests on.
+//!
+//! Commands are invoked as if the webview the session is focused on (or the one given in the request)
+//! had sent them, so the capabilities of that webview apply.
+//!
+//! | Method | Endpoint                       | Body                                         |
+//! |--------|--------------------------------|----------------------------------------------|
+//! | GET    | `/session/{id}/tauri/windows`  |                                              |
+//! | POST   | `/session/{id}/tauri/window`   | `{ "label": string }`                        |
+//! | POST   | `/session/{id}/tauri/invoke`   | `{ "cmd": string, "args"?: object, "webview"?: string }` |
+//! | POST   | `/session/{id}/tauri/emit`     | `{ "event": string, "payload"?: any, "target"?: string }` |
+//! | POST   | `/session/{id}/tauri/listen`   | `{ "event": string }`                        |
+//! | POST   | `/session/{id}/tauri/events`   | `{ "event": string }`                        |
+
+use crate::{
+  channel::{ChannelError, PluginChannel},
+  error::{json_response, WebDriverError},
+};
+use http_body_util::{BodyExt, Full};
+use hyper::{
+  body::Bytes,
+  header::{CONTENT_LENGTH, CONTENT_TYPE},
+  Method, Request, Response, StatusCode,
+};
+use hyper_util::client::legacy::{connect::HttpConnector, Client};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// The vendor prefix of the extension commands.
+const PREFIX: &str = "tauri";
+
+/// Returns the label of the window and webview the script runs in.
+const LABELS_SCRIPT: &str = r#"
+const metadata = window.__TAURI_INTERNALS__ && window.__TAURI_INTERNALS__.metadata;
+return metadata
+  ? { window: metadata.currentWindow.label, webview: metadata.currentWebview.label }
+  : null;
+"#;
+
+/// The path of an extension command, `/session/{session id}/tauri/{command}`.
+pub struct ExtensionPath {
+  session: String,
+  command: String,
+}
+
+impl ExtensionPath {
+  /// Parses the path of a request, `None` if it is not an extension command.
+  pub fn parse(path: &str) -> Option<Self> {
+    let mut segments = path.trim_start_matches('/').split('/');
+    match (
+      segments.next(),
+      segments.next(),
+      segments.next(),
+      segments.next(),
+      segments.next(),
+    ) {
+      (Some("session"), Some(session), Some(PREFIX), Some(command), None)
+        if !session.is_empty() =>
+      {
+        Some(Self {
+          session: session.into(),
+          command: command.into(),
+        })
+      }
+      _ => None,
+    }
+  }
+}
+
+#[derive(Deserialize)]
+struct SwitchWindow {
+  label: String,
+}
+
+#[derive(Deserialize)]
+struct Invoke {
+  cmd: String,
+  #[serde(default)]
+  args: Value,
+  /// The label of the webview invoking the command, defaults to the one the session is focused on.
+  webview: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Emit {
+  event: String,
+  #[serde(default)]
+  payload: Value,
+  /// The label of the webview, window or webview window to emit the event to.
+  target: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Event {
+  event: String,
+}
+
+/// An error response of the native WebDriver, forwarded as is.
+struct NativeError {
+  status: StatusCode,
+  body: Value,
+}
+
+enum Error {
+  WebDriver(WebDriverError),
+  Native(NativeError),
+}
+
+impl From<WebDriverError> for Error {
+  fn from(error: WebDriverError) -> Self {
+    Self::WebDriver(error)
+  }
+}
+
+impl From<ChannelError> for Error {
+  fn from(error: ChannelError) -> Self {
+    match error {
+      ChannelError::Channel(error) => Self::WebDriver(error),
+      ChannelError::Application(data) => Self::WebDriver(WebDriverError::application(data)),
+    }
+  }
+}
+
+impl Error {
+  fn into_response(self) -> Response<Full<Bytes>> {
+    match self {
+      Self::WebDriver(error) => error.into_response(),
+      Self::Native(error) => json_response(error.status, error.body),
+    }
+  }
+}
+
+/// A session of the native WebDriver.
+struct NativeSession<'a> {
+  client: &'a Client<HttpConnector, Full<Bytes>>,
+  /// `http://{host}:{port}/session/{session id}`
+  url: String,
+}
+
+impl NativeSession<'_> {
+  /// Runs a command of the native WebDriver session, returning its value.
+  async fn command(&self, method: Method, path: &str, body: Option<Value>) -> Result<Value, Error> {
+    let body = body
+      .map(|body| serde_json::to_vec(&body).expect("failed to serialize JSON"))
+      .unwrap_or_default();
+    let mut request = Request::builder()
+      .method(method)
+      .uri(format!("{}{path}", self.url))
+      .header(CONTENT_LENGTH, body.len());
+    if !body.is_empty() {
+      request = request.header(CONTENT_TYPE, "application/json; charset=utf-8");
+    }
+    let request = request
+      .body(Full::new(body.into()))
+      .map_err(|e| WebDriverError::unknown_error(e.to_string()))?;
+
+    let response = self.client.request(request).await.map_err(|e| {
+      WebDriverError::unknown_error(format!("native WebDriver request failed: {e}"))
+    })?;
+    let status = response.status();
+    let body = response
+      .into_body()
+      .collect()
+      .await
+      .map_err(|e| WebDriverError::unknown_error(e.to_string()))?
+      .to_bytes();
+    let mut body: Value = serde_json::from_slice(&body).map_err(|e| {
+      WebDriverError::unknown_error(format!("invalid native WebDriver response: {e}"))
+    })?;
+
+    if status.is_success() {
+      Ok(body.get_mut("value").map(Value::take).unwrap_or_default())
+    } else {
+      Err(Error::Native(NativeError { status, body }))
+    }
+  }
+
+  async fn execute(&self, script: &str, args: Vec<Value>) -> Result<Value, Error> {
+    self
+      .command(
+        Method::POST,
+        "/execute/sync",
+        Some(json!({ "script": script, "args": args })),
+      )
+      .await
+  }
+
+  /// Lists the native window handles with the labels of the Tauri window and webview they belong to.
+  ///
+  /// Every window is visited, the current window is restored afterwards even if visiting one failed.
+  async fn windows(&self) -> Result<Vec<Value>, Error> {
+    // the session might not have a current window anymore if it was closed
+    let current = self.command(Method::GET, "/window", None).await.ok();
+    let windows = self.visit_windows().await;
+
+    if let Some(current) = current {
+      let restored = self
+        .command(Method::POST, "/window", Some(json!({ "handle": current })))
+        .await;
+      // report why visiting the windows failed over why restoring the current one did
+      let windows = windows?;
+      restored?;
+      return Ok(windows);
+    }
+
+    windows
+  }
+
+  async fn visit_windows(&self) -> Result<Vec<Value>, Error> {
+    let handles = self.command(Method::GET, "/window/handles", None).await?;
+
+    let mut windows = Vec::new();
+    for handle in handles.as_array().into_iter().flatten() {
+      self
+        .command(Method::POST, "/window", Some(json!({ "handle": handle })))
+        .await?;
+      let labels = self.execute(LABELS_SCRIPT, Vec::new()).await?;
+      windows.push(json!({
+        "handle": handle,
+        "window": labels.get("window").cloned().unwrap_or_default(),
+        "webview": labels.get("webview").cloned().unwrap_or_default(),
+      }));
+    }
+
+    Ok(windows)
+  }
+
+  /// The label of the webview the session is focused on.
+  async fn current_webview(&self) -> Result<String, Error> {
+    let labels = self.execute(LABELS_SCRIPT, Vec::new()).await?;
+    match labels.get("webview").and_then(Value::as_str) {
+      Some(label) => Ok(label.into()),
+      None => Err(
+        WebDriverError::new(
+          StatusCode::NOT_FOUND,
+          "no such window",
+          "the current window is not a Tauri webview, switch to one with tauri/window or pass its label",
+        )
+        .into(),
+      ),
+    }
+  }
+}
+
+fn parse_body<T: serde::de::DeserializeOwned>(body: &[u8]) -> Result<T, Error> {
+  serde_json::from_slice(body).map_err(|e| WebDriverError::invalid_argument(e.to_string()).into())
+}
+
+/// Services an extension command.
+///
+/// `native_url` is the base URL of the native WebDriver, `http://{host}:{port}`.
+pub async fn handle(
+  client: &Client<HttpConnector, Full<Bytes>>,
+  native_url: &str,
+  channel: &PluginChannel,
+  method: &Method,
+  path: ExtensionPath,
+  body: &[u8],
+) -> Response<Full<Bytes>> {
+  let session = NativeSession {
+    client,
+    url: format!("{native_url}/session/{}", path.session),
+  };
+
+  match run(&session, channel, method, &path.command, body).await {
+    Ok(value) => json_response(StatusCode::OK, json!({ "value": value })),
+    Err(error) => error.into_response(),
+  }
+}
+
+async fn run(
+  session: &NativeSession<'_>,
+  channel: &PluginChannel,
+  method: &Method,
+  command: &str,
+  body: &[u8],
+) -> Result<Value, Error> {
+  match (method, command) {
+    (&Method::GET, "windows") => session.windows().await.map(Value::Array),
+    (&Method::POST, "window") => {
+      let SwitchWindow { label } = parse_body(body)?;
+      let windows = session.windows().await?;
+      // prefer the webview label, the window label is only needed for multiwebview windows
+      let window = windows
+        .iter()
+        .find(|window| window["webview"] == label.as_str())
+        .or_else(|| {
+          windows
+            .iter()
+            .find(|window| window["window"] == label.as_str())
+        });
+      let handle = match window {
+        Some(window) => window["handle"].clone(),
+        None => {
+          return Err(
+            WebDriverError::new(
+              StatusCode::NOT_FOUND,
+              "no such window",
+              format!("no window or webview with label `{label}`"),
+            )
+            .into(),
+          )
+        }
+      };
+      session
+        .command(Method::POST, "/window", Some(json!({ "handle": handle })))
+        .await?;
+      Ok(Value::Null)
+    }
+    (&Method::POST, "invoke") => {
+      let Invoke { cmd, args, webview } = parse_body(body)?;
+      let webview = match webview {
+        Some(webview) => webview,
+        None => session.current_webview().await?,
+      };
+      let request = json!({ "type": "invoke", "webview": webview, "cmd": cmd, "args": args });
+      Ok(channel.request(request).await?)
+    }
+    (&Method::POST, "emit") => {
+      let Emit {
+        event,
+        payload,
+        target,
+      } = parse_body(body)?;
+      let request = json!({ "type": "emit", "event": event, "payload": payload, "target": target });
+      Ok(channel.request(request).await?)
+    }
+    (&Method::POST, "listen") => {
+      let Event { event } = parse_body(body)?;
+      Ok(
+        channel
+          .request(json!({ "type": "listen", "event": event }))
+          .await?,
+      )
+    }
+    (&Method::POST, "events") => {
+      let Event { event } = parse_body(body)?;
+      Ok(
+        channel
+          .request(json!({ "type": "events", "event": event }))
+          .await?,
+      )
+    }
+    (method, command) => Err(
+      WebDriverError::unknown_command(format!(
+        "unknown Tauri extension command {method} {PREFIX}/{command}"
+      ))
+      .into(),
+    ),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::channel::tests::connect_plugin;
+  use hyper::{body::Incoming, server::conn::http1, service::service_fn};
+  use hyper_util::rt::{TokioExecutor, TokioIo};
+  use std::sync::{Arc, Mutex};
+  use tokio::net::TcpListener;
+
+  /// A native WebDriver session with a webview per window.
+  struct FakeDriver {
+    current: String,
+    /// The handles of the windows with their window and webview labels.
+    windows: Vec<(&'static str, &'static str, &'static str)>,
+    /// A window whose scripts fail.
+    failing: Option<&'static str>,
+  }
+
+  impl FakeDriver {
+    fn new(current: &str) -> Arc<Mutex<Self>> {
+      Arc::new(Mutex::new(Self {
+        current: current.into(),
+        windows: vec![("a", "main", "main"), ("b", "settings", "settings-webview")],
+        failing: None,
+      }))
+    }
+
+    fn respond(&mut self, method: &Method, path: &str, body: Value) -> (StatusCode, Value) {
+      let path = path.strip_prefix("/session/s").unwrap_or(path);
+      if self.failing == Some(self.current.as_str()) && path.starts_with("/execute") {
+        return (
+          StatusCode::INTERNAL_SERVER_ERROR,
+          json!({ "value": { "error": "javascript error", "message": "boom" } }),
+        );
+      }
+
+      let value = match (method, path) {
+        (&Method::GET, "/window") => json!(self.current),
+        (&Method::GET, "/window/handles") => json!(self
+          .windows
+          .iter()
+          .map(|(handle, _, _)| handle)
+          .collect::<Vec<_>>()),
+        (&Method::POST, "/window") => {
+          self.current = body["handle"].as_str().unwrap().into();
+          Value::Null
+        }
+        (&Method::POST, "/execute/sync") if body["script"] == LABELS_SCRIPT => {
+          let (_, window, webview) = self
+            .windows
+            .iter()
+            .find(|(handle, _, _)| *handle == self.current)
+            .unwrap();
+          // a page without the Tauri metadata
+          if webview.is_empty() {
+            Value::Null
+          } else {
+            json!({ "window": window, "webview": webview })
+          }
+        }
+        _ => {
+          return (
+            StatusCode::NOT_FOUND,
+            json!({ "value": { "error": "unknown command", "message": path } }),
+          )
+        }
+      };
+      (StatusCode::OK, json!({ "value": value }))
+    }
+  }
+
+  /// Serves the fake driver, returning its URL.
+  async fn serve(driver: Arc<Mutex<FakeDriver>>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+      while let Ok((stream, _)) = listener.accept().await {
+        let driver = driver.clone();
+        tokio::spawn(async move {
+          let service = service_fn(move |request: Request<Incoming>| {
+            let driver = driver.clone();
+            async move {
+              let (parts, body) = request.into_parts();
+              let body = body.collect().await?.to_bytes();
+              let body = serde_json::from_slice(&body).unwrap_or_default();
+              let (status, value) =
+                driver
+                  .lock()
+                  .unwrap()
+                  .respond(&parts.method, parts.uri.path(), body);
+              Ok::<_, hyper::Error>(json_response(status, value))
+            }
+          });
+          let _ = http1::Builder::new()
+            .serve_connection(TokioIo::new(stream), service)
+            .await;
+        });
+      }
+    });
+    url
+  }
+
+  /// A channel with a fake plugin connected, returning the requests it received.
+  async fn plugin() -> (PluginChannel, Arc<Mutex<Vec<Value>>>) {
+    let channel = PluginChannel::bind().await.unwrap();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let requests_ = requests.clone();
+    connect_plugin(&channel, move |request| {
+      requests_.lock().unwrap().push(request.clone());
+      match request["type"].as_str().unwrap() {
+        "invoke" if request["cmd"] == "denied" => json!({ "error": "command denied not allowed" }),
+        "invoke" => json!({ "value": { "webview": request["webview"], "args": request["args"] } }),
+        "events" => json!({ "value": [request["event"]] }),
+        _ => json!({ "value": null }),
+      }
+    })
+    .await;
+    (channel, requests)
+  }
+
+  /// Runs an extension command against the fake driver and plugin, returning the status and value of the response.
+  async fn call(
+    driver: &Arc<Mutex<FakeDriver>>,
+    channel: &PluginChannel,
+    method: Method,
+    command: &str,
+    body: Value,
+  ) -> (StatusCode, Value) {
+    let url = serve(driver.clone()).await;
+    let client = Client::builder(TokioExecutor::new()).build_http();
+    let path = ExtensionPath::parse(&format!("/session/s/{PREFIX}/{command}")).unwrap();
+    let body = if body.is_null() {
+      Vec::new()
+    } else {
+      serde_json::to_vec(&body).unwrap()
+    };
+
+    let response = handle(&client, &url, channel, &method, path, &body).await;
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let mut body: Value = serde_json::from_slice(&body).unwrap();
+    (status, body["value"].take())
+  }
+
+  #[test]
+  fn parse_path() {
+    let path = ExtensionPath::parse("/session/1234/tauri/invoke").unwrap();
+    assert_eq!(path.session, "1234");
+    assert_eq!(path.command, "invoke");
+    assert!(ExtensionPath::parse("session/1234/tauri/windows").is_some());
+
+    for path in [
+      "/session",
+      "/session/1234",
+      "/session/1234/tauri",
+      "/session//tauri/invoke",
+      "/session/1234/window",
+      "/session/1234/other/invoke",
+      "/session/1234/tauri/invoke/extra",
+      "/status",
+    ] {
+      assert!(ExtensionPath::parse(path).is_none(), "{path}");
+    }
+  }
+
+  #[tokio::test]
+  async fn windows() {
+    let driver = FakeDriver::new("b");
+    let (channel, _) = plugin().await;
+    let (status, value) = call(&driver, &channel, Method::GET, "windows", Value::Null).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+      value,
+      json!([
+        { "handle": "a", "window": "main", "webview": "main" },
+        { "handle": "b", "window": "settings", "webview": "settings-webview" },
+      ])
+    );
+    assert_eq!(driver.lock().unwrap().current, "b");
+  }
+
+  #[tokio::test]
+  async fn windows_restores_current_window_on_error() {
+    let driver = FakeDriver::new("b");
+    driver.lock().unwrap().failing = Some("a");
+    let (channel, _) = plugin().await;
+    let (status, value) = call(&driver, &channel, Method::GET, "windows", Value::Null).await;
+    // the error of the native driver is forwarded as is
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(value["message"], "boom");
+    assert_eq!(driver.lock().unwrap().current, "b");
+  }
+
+  #[tokio::test]
+  async fn switch_window() {
+    let driver = FakeDriver::new("a");
+    let (channel, requests) = plugin().await;
+
+    let (status, _) = call(
+      &driver,
+      &channel,
+      Method::POST,
+      "window",
+      json!({ "label": "settings-webview" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(driver.lock().unwrap().current, "b");
+
+    let (status, _) = call(
+      &driver,
+      &channel,
+      Method::POST,
+      "window",
+      json!({ "label": "main" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(driver.lock().unwrap().current, "a");
+
+    // the window label is matched when no webview has it
+    let (status, _) = call(
+      &driver,
+      &channel,
+      Method::POST,
+      "window",
+      json!({ "label": "settings" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(driver.lock().unwrap().current, "b");
+
+    let (status, value) = call(
+      &driver,
+      &channel,
+      Method::POST,
+      "window",
+      json!({ "label": "other" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(value["error"], "no such window");
+    assert_eq!(driver.lock().unwrap().current, "b");
+
+    // switching windows does not involve the application
+    assert!(requests.lock().unwrap().is_empty());
+  }
+
+  #[tokio::test]
+  async fn invoke() {
+    let driver = FakeDriver::new("b");
+    let (channel, requests) = plugin().await;
+
+    // invoked from the webview the session is focused on by default
+    let (status, value) = call(
+      &driver,
+      &channel,
+      Method::POST,
+      "invoke",
+      json!({ "cmd": "greet", "args": { "name": "tauri" } }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+      value,
+      json!({ "webview": "settings-webview", "args": { "name": "tauri" } })
+    );
+
+    let (_, value) = call(
+      &driver,
+      &channel,
+      Method::POST,
+      "invoke",
+      json!({ "cmd": "ping", "webview": "main" }),
+    )
+    .await;
+    assert_eq!(value, json!({ "webview": "main", "args": null }));
+
+    let (status, value) = call(
+      &driver,
+      &channel,
+      Method::POST,
+      "invoke",
+      json!({ "cmd": "denied" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(value["error"], "unknown error");
+    assert_eq!(value["message"], "command denied not allowed");
+    assert_eq!(value["data"], "command denied not allowed");
+
+    assert_eq!(requests.lock().unwrap()[1]["cmd"], "ping");
+  }
+
+  #[tokio::test]
+  async fn invoke_requires_a_tauri_webview() {
+    let driver = FakeDriver::new("a");
+    driver.lock().unwrap().windows[0] = ("a", "", "");
+    let (channel, requests) = plugin().await;
+
+    let (status, value) = call(
+      &driver,
+      &channel,
+      Method::POST,
+      "invoke",
+      json!({ "cmd": "greet" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(value["error"], "no such window");
+    assert!(requests.lock().unwrap().is_empty());
+  }
+
+  #[tokio::test]
+  async fn emit() {
+    let driver = FakeDriver::new("a");
+    let (channel, requests) = plugin().await;
+
+    let (status, _) = call(
+      &driver,
+      &channel,
+      Method::POST,
+      "emit",
+      json!({ "event": "ready", "payload": 1 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = call(
+      &driver,
+      &channel,
+      Method::POST,
+      "emit",
+      json!({ "event": "ready", "target": "main" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    assert_eq!(
+      *requests.lock().unwrap(),
+      vec![
+        json!({ "type": "emit", "event": "ready", "payload": 1, "target": null }),
+        json!({ "type": "emit", "event": "ready", "payload": null, "target": "main" }),
+      ]
+    );
+  }
+
+  #[tokio::test]
+  async fn listen_and_events() {
+    let driver = FakeDriver::new("a");
+    let (channel, _) = plugin().await;
+
+    let (status, value) = call(
+      &driver,
+      &channel,
+      Method::POST,
+      "listen",
+      json!({ "event": "ready" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(value, Value::Null);
+
+    let (status, value) = call(
+      &driver,
+      &channel,
+      Method::POST,
+      "events",
+      json!({ "event": "ready" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(value, json!(["ready"]));
+  }
+
+  #[tokio::test]
+  async fn invalid_commands() {
+    let driver = FakeDriver::new("a");
+    let (channel, requests) = plugin().await;
+
+    let (status, value) = call(&driver, &channel, Method::POST, "unknown", json!({})).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(value["error"], "unknown command");
+
+    let (status, value) = call(&driver, &channel, Method::GET, "invoke", Value::Null).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(value["error"], "unknown command");
+
+    for command in ["window", "invoke", "emit", "listen", "events"] {
+      let (status, value) = call(&driver, &channel, Method::POST, command, json!({})).await;
+      assert_eq!(status, StatusCode::BAD_REQUEST, "{command}");
+      assert_eq!(value["error"], "invalid argument", "{command}");
+    }
+
+    // nothing reached the application
+    assert!(requests.lock().unwrap().is_empty());
+  }
+}
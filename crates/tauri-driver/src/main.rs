@@ -11,9 +11,21 @@
   html_favicon_url = "https://github.com/tauri-apps/tauri/raw/dev/.github/icon.png"
 )]
 
+#[cfg(any(target_os = "linux", windows))]
+mod artifacts;
+#[cfg(any(target_os = "linux", windows))]
+mod channel;
 #[cfg(any(target_os = "linux", windows))]
 mod cli;
 #[cfg(any(target_os = "linux", windows))]
+mod error;
+#[cfg(any(target_os = "linux", windows))]
+mod extensions;
+#[cfg(any(target_os = "linux", windows))]
+mod options;
+#[cfg(any(target_os = "linux", windows))]
+mod pool;
+#[cfg(any(target_os = "linux", windows))]
 mod server;
 #[cfg(any(target_os = "linux", windows))]
 mod webdriver;
@@ -38,14 +50,11 @@ fn main() {
     job
   };
 
-  // start the native webdriver on the port specified in args
-  let mut driver = webdriver::native(&args);
-  let driver = driver
-    .spawn()
-    .expect("error while running native webdriver");
+  // the native webdrivers are started for each new session
+  let native_binary = webdriver::native_binary(&args);
 
   // start our webdriver intermediary node
-  if let Err(e) = server::run(args, driver) {
+  if let Err(e) = server::run(args, native_binary) {
     eprintln!("error while running server: {e}");
     std::process::exit(1);
   }
@@ -0,0 +1,180 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The `tauri:options` capability.
+//!
+//! This file is also included by `tauri-schema-generator` to generate its JSON schema,
+//! so it must only depend on `serde`, `serde_json` and `schemars`.
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// The `tauri:options` capability, describing how `tauri-driver` launches the Tauri application of a session.
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TauriOptions {
+  /// The path to the application binary.
+  pub application: PathBuf,
+  /// The arguments passed to the application.
+  #[serde(default)]
+  pub args: Vec<String>,
+  /// Additional environment variables set for the application.
+  #[serde(default)]
+  pub env: BTreeMap<String, String>,
+  /// The working directory of the application, defaults to the working directory of `tauri-driver`.
+  #[serde(default)]
+  pub cwd: Option<PathBuf>,
+  /// Where the application stores its data.
+  ///
+  /// On Linux this overrides the `XDG_*` base directories, on Windows only the webview data directory.
+  #[serde(default)]
+  pub isolated_data_dir: IsolatedDataDir,
+  /// The `webviewOptions` of the Microsoft Edge Driver. Windows only.
+  #[serde(default)]
+  pub webview_options: Option<Value>,
+  /// Fields `tauri-driver` does not know about, ignored with a warning so older clients keep working.
+  #[serde(flatten)]
+  #[schemars(skip)]
+  unknown: Map<String, Value>,
+}
+
+/// Where the application stores its data.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum IsolatedDataDir {
+  /// `true` to use a throwaway directory that is removed with the session,
+  /// `false` to use the data directories of the current user.
+  Enabled(bool),
+  /// A directory that is kept after the session, so its data can be inspected.
+  Path(PathBuf),
+}
+
+impl Default for IsolatedDataDir {
+  fn default() -> Self {
+    Self::Enabled(true)
+  }
+}
+
+impl TauriOptions {
+  /// Parses and validates the options.
+  pub fn parse(value: Value) -> Result<Self, String> {
+    let options: Self =
+      serde_json::from_value(value).map_err(|e| format!("invalid tauri:options: {e}"))?;
+    for key in options.unknown.keys() {
+      eprintln!("ignoring unknown tauri:options field `{key}`");
+    }
+    options.validate()?;
+    Ok(options)
+  }
+
+  fn validate(&self) -> Result<(), String> {
+    for key in self.env.keys() {
+      if key.is_empty() || key.contains(['=', '\0']) {
+        return Err(format!(
+          "invalid tauri:options: `{key}` is not a valid environment variable name"
+        ));
+      }
+    }
+    if let Some((key, _)) = self.env.iter().find(|(_, value)| value.contains('\0')) {
+      return Err(format!(
+        "invalid tauri:options: the value of the `{key}` environment variable contains a NUL character"
+      ));
+    }
+
+    if let Some(cwd) = &self.cwd {
+      if !cwd.is_dir() {
+        return Err(format!(
+          "invalid tauri:options: the working directory {} does not exist",
+          cwd.display()
+        ));
+      }
+    }
+
+    if let IsolatedDataDir::Path(path) = &self.isolated_data_dir {
+      if path.as_os_str().is_empty() || path.is_file() {
+        return Err(format!(
+          "invalid tauri:options: the data directory {} is not a directory",
+          path.display()
+        ));
+      }
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  fn parse(options: Value) -> Result<TauriOptions, String> {
+    let mut value = json!({ "application": "/path/to/app" });
+    value
+      .as_object_mut()
+      .unwrap()
+      .extend(options.as_object().unwrap().clone());
+    TauriOptions::parse(value)
+  }
+
+  #[test]
+  fn unknown_fields_are_ignored() {
+    let options = parse(json!({ "unknown": true, "webviewOptions": null })).unwrap();
+    assert!(options.unknown.contains_key("unknown"));
+    assert!(options.webview_options.is_none());
+    assert!(parse(json!({ "webviewOptions": { "additionalBrowserArguments": "" } })).is_ok());
+  }
+
+  #[test]
+  fn env() {
+    let options = parse(json!({ "env": { "RUST_LOG": "debug", "EMPTY": "" } })).unwrap();
+    assert_eq!(options.env["RUST_LOG"], "debug");
+
+    for name in ["", "A=B", "A\0B"] {
+      let error = parse(json!({ "env": { name: "value" } })).unwrap_err();
+      assert!(
+        error.contains("is not a valid environment variable name"),
+        "{error}"
+      );
+    }
+
+    let error = parse(json!({ "env": { "NAME": "a\0b" } })).unwrap_err();
+    assert!(error.contains("contains a NUL character"), "{error}");
+  }
+
+  #[test]
+  fn cwd() {
+    let dir = std::env::temp_dir();
+    let options = parse(json!({ "cwd": dir })).unwrap();
+    assert_eq!(options.cwd, Some(dir.clone()));
+
+    let error = parse(json!({ "cwd": dir.join("tauri-driver-missing-cwd") })).unwrap_err();
+    assert!(error.contains("does not exist"), "{error}");
+  }
+
+  #[test]
+  fn isolated_data_dir() {
+    let options = parse(json!({})).unwrap();
+    assert_eq!(options.isolated_data_dir, IsolatedDataDir::Enabled(true));
+    let options = parse(json!({ "isolatedDataDir": false })).unwrap();
+    assert_eq!(options.isolated_data_dir, IsolatedDataDir::Enabled(false));
+
+    // a missing directory is created when the session starts
+    let dir = std::env::temp_dir().join("tauri-driver-missing-data-dir");
+    let options = parse(json!({ "isolatedDataDir": dir })).unwrap();
+    assert_eq!(options.isolated_data_dir, IsolatedDataDir::Path(dir));
+
+    let error = parse(json!({ "isolatedDataDir": "" })).unwrap_err();
+    assert!(error.contains("is not a directory"), "{error}");
+
+    let file = std::env::temp_dir().join(format!("tauri-driver-data-dir-{}", std::process::id()));
+    std::fs::write(&file, "").unwrap();
+    let error = parse(json!({ "isolatedDataDir": file })).unwrap_err();
+    let _ = std::fs::remove_file(&file);
+    assert!(error.contains("is not a directory"), "{error}");
+  }
+}
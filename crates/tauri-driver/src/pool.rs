@@ -0,0 +1,548 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! A pool of native WebDriver servers.
+//!
+//! Every session gets its own native WebDriver server, listening on the first free port starting
+//! from `--native-port`, so sessions run in parallel. Unless `isolatedDataDir` says otherwise, the
+//! application of each session stores its data in a temporary directory that is removed with the session.
+//!
+//! A port is reserved in the pool before its server is started, so concurrent sessions never pick the
+//! same one, but another process can still bind it in the meantime. The server then fails to start and
+//! the session is retried on the next free port.
+
+use crate::{
+  artifacts::{self, Log},
+  channel::PluginChannel,
+  cli::Args,
+  error::WebDriverError,
+  options::{IsolatedDataDir, TauriOptions},
+  webdriver,
+};
+use http_body_util::Full;
+use hyper::{body::Bytes, Request};
+use hyper_util::client::legacy::{connect::HttpConnector, Client};
+use std::{
+  collections::HashMap,
+  net::TcpListener,
+  path::PathBuf,
+  process::{Child, ExitStatus, Stdio},
+  sync::{Arc, Mutex, MutexGuard},
+  time::{Duration, Instant},
+};
+
+/// How long to wait for a native WebDriver server to accept connections.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How many ports are tried when the native WebDriver server can not listen on the reserved one.
+const PORT_ATTEMPTS: usize = 5;
+
+/// The process of a native WebDriver server.
+struct Process {
+  child: Mutex<Child>,
+  /// The throwaway data directory of the application.
+  data_dir: Option<PathBuf>,
+}
+
+impl Process {
+  fn child(&self) -> MutexGuard<'_, Child> {
+    self
+      .child
+      .lock()
+      .expect("poisoned native webdriver process")
+  }
+
+  fn try_wait(&self) -> std::io::Result<Option<ExitStatus>> {
+    self.child().try_wait()
+  }
+
+  /// Kills the native WebDriver server and removes the application data.
+  fn kill(&self) {
+    {
+      let mut child = self.child();
+      let _ = child.kill();
+      let _ = child.wait();
+    }
+    if let Some(data_dir) = &self.data_dir {
+      let _ = std::fs::remove_dir_all(data_dir);
+    }
+  }
+}
+
+/// A native WebDriver server.
+pub struct Instance {
+  pub port: u16,
+  /// Shared with the pool while the instance is pending, so it can be killed before its session exists.
+  process: Arc<Process>,
+  /// The channel to the test plugin of the application.
+  channel: Arc<PluginChannel>,
+  /// The captured stdout and stderr of the native WebDriver server, when recording artifacts.
+  logs: Option<(Log, Log)>,
+  /// The number of failed commands, used to name their artifacts.
+  failures: usize,
+  /// The [`Failure::signature`](crate::artifacts::Failure::signature) of the last failed command.
+  last_failure: Option<String>,
+}
+
+impl Instance {
+  fn kill(self) {
+    self.process.kill();
+  }
+}
+
+#[derive(Default)]
+struct Sessions {
+  active: HashMap<String, Instance>,
+  /// The instances that are starting or waiting for their session to be created, by port.
+  ///
+  /// The process is `None` while it is being spawned.
+  pending: HashMap<u16, Option<Arc<Process>>>,
+  /// Used to name the data directories.
+  next_id: usize,
+}
+
+impl Sessions {
+  fn len(&self) -> usize {
+    self.active.len() + self.pending.len()
+  }
+
+  fn is_port_used(&self, port: u16) -> bool {
+    self.pending.contains_key(&port) || self.active.values().any(|instance| instance.port == port)
+  }
+}
+
+/// Why a native WebDriver server could not be started.
+enum SpawnError {
+  /// Another process took the port before the server could listen on it.
+  PortTaken,
+  Other(WebDriverError),
+}
+
+impl From<WebDriverError> for SpawnError {
+  fn from(error: WebDriverError) -> Self {
+    Self::Other(error)
+  }
+}
+
+/// The native WebDriver servers of the active sessions.
+#[derive(Clone)]
+pub struct Pool {
+  native_binary: Arc<PathBuf>,
+  sessions: Arc<Mutex<Sessions>>,
+}
+
+impl Pool {
+  pub fn new(native_binary: PathBuf) -> Self {
+    Self {
+      native_binary: Arc::new(native_binary),
+      sessions: Default::default(),
+    }
+  }
+
+  fn sessions(&self) -> MutexGuard<'_, Sessions> {
+    self.sessions.lock().expect("poisoned session pool")
+  }
+
+  /// The port of the native WebDriver server of a session.
+  pub fn port(&self, session: &str) -> Option<u16> {
+    self
+      .sessions()
+      .active
+      .get(session)
+      .map(|instance| instance.port)
+  }
+
+  /// The channel to the test plugin of the application of a session.
+  pub fn channel(&self, session: &str) -> Option<Arc<PluginChannel>> {
+    self
+      .sessions()
+      .active
+      .get(session)
+      .map(|instance| instance.channel.clone())
+  }
+
+  /// Whether a new session can be created.
+  pub fn ready(&self, args: &Args) -> bool {
+    let len = self.sessions().len();
+    args.max_sessions.map_or(true, |max| len < max)
+  }
+
+  /// Starts a native WebDriver server for a new session.
+  pub async fn start(
+    &self,
+    args: &Args,
+    client: &Client<HttpConnector, Full<Bytes>>,
+    options: Option<&TauriOptions>,
+  ) -> Result<Instance, WebDriverError> {
+    let mut first_port = args.native_port;
+    for attempt in 1..=PORT_ATTEMPTS {
+      let (port, id) = self.reserve(args, first_port)?;
+      match self.spawn(args, client, options, port, id).await {
+        Ok(instance) => return Ok(instance),
+        Err(error) => {
+          self.release(port);
+          match error {
+            SpawnError::PortTaken if attempt < PORT_ATTEMPTS && port < u16::MAX => {
+              first_port = port + 1;
+            }
+            SpawnError::PortTaken => {
+              return Err(WebDriverError::session_not_created(format!(
+                "native webdriver could not listen on port {port}, it is used by another process"
+              )))
+            }
+            SpawnError::Other(error) => return Err(error),
+          }
+        }
+      }
+    }
+    unreachable!("the last attempt always returns")
+  }
+
+  /// Reserves the first free port starting from `first_port` for a new instance, returning it with the id of the instance.
+  fn reserve(&self, args: &Args, first_port: u16) -> Result<(u16, usize), WebDriverError> {
+    let mut sessions = self.sessions();
+    if let Some(max) = args.max_sessions {
+      if sessions.len() >= max {
+        return Err(WebDriverError::session_not_created(format!(
+          "maximum number of sessions ({max}) reached"
+        )));
+      }
+    }
+
+    let port = (first_port..=u16::MAX)
+      .find(|port| !sessions.is_port_used(*port) && is_port_free(&args.native_host, *port))
+      .ok_or_else(|| WebDriverError::session_not_created("no free port left"))?;
+    sessions.pending.insert(port, None);
+    sessions.next_id += 1;
+    Ok((port, sessions.next_id))
+  }
+
+  async fn spawn(
+    &self,
+    args: &Args,
+    client: &Client<HttpConnector, Full<Bytes>>,
+    options: Option<&TauriOptions>,
+    port: u16,
+    id: usize,
+  ) -> Result<Instance, SpawnError> {
+    let throwaway_data_dir = || {
+      std::env::temp_dir()
+        .join("tauri-driver")
+        .join(format!("{}-{id}", std::process::id()))
+    };
+    let (data_dir, throwaway) = match options.map(|options| &options.isolated_data_dir) {
+      None | Some(IsolatedDataDir::Enabled(true)) => (Some(throwaway_data_dir()), true),
+      Some(IsolatedDataDir::Enabled(false)) => (None, false),
+      Some(IsolatedDataDir::Path(path)) => (Some(path.clone()), false),
+    };
+    if let Some(data_dir) = &data_dir {
+      std::fs::create_dir_all(data_dir).map_err(|e| {
+        WebDriverError::session_not_created(format!(
+          "failed to create the data directory {}: {e}",
+          data_dir.display()
+        ))
+      })?;
+    }
+    let data_dir_to_remove = data_dir.clone().filter(|_| throwaway);
+
+    let channel = match PluginChannel::bind().await {
+      Ok(channel) => channel,
+      Err(e) => {
+        if let Some(data_dir) = data_dir_to_remove {
+          let _ = std::fs::remove_dir_all(data_dir);
+        }
+        return Err(
+          WebDriverError::session_not_created(format!(
+            "failed to listen for the tauri-driver channel: {e}"
+          ))
+          .into(),
+        );
+      }
+    };
+
+    let mut command = webdriver::native(
+      &self.native_binary,
+      args,
+      port,
+      options,
+      data_dir.as_deref(),
+    );
+    command.envs(channel.env());
+    let capture_logs = args.artifacts_dir.is_some() && args.artifacts.logs;
+    if capture_logs {
+      command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    }
+    let mut child = match command.spawn() {
+      Ok(child) => child,
+      Err(e) => {
+        if let Some(data_dir) = data_dir_to_remove {
+          let _ = std::fs::remove_dir_all(data_dir);
+        }
+        return Err(
+          WebDriverError::session_not_created(format!("error while running native webdriver: {e}"))
+            .into(),
+        );
+      }
+    };
+    let logs = match (child.stdout.take(), child.stderr.take()) {
+      // keep showing the errors of the native driver while recording them
+      (Some(stdout), Some(stderr)) => {
+        Some((Log::capture(stdout), Log::tee(stderr, std::io::stderr())))
+      }
+      _ => None,
+    };
+    let instance = Instance {
+      port,
+      process: Arc::new(Process {
+        child: Mutex::new(child),
+        data_dir: data_dir_to_remove,
+      }),
+      channel: Arc::new(channel),
+      logs,
+      failures: 0,
+      last_failure: None,
+    };
+
+    // let `kill_all` stop the process while it is pending, unless it already ran
+    let reserved = match self.sessions().pending.get_mut(&port) {
+      Some(process) => {
+        *process = Some(instance.process.clone());
+        true
+      }
+      None => false,
+    };
+    if !reserved {
+      instance.kill();
+      return Err(WebDriverError::session_not_created("tauri-driver is shutting down").into());
+    }
+
+    let deadline = Instant::now() + STARTUP_TIMEOUT;
+    loop {
+      if let Ok(Some(status)) = instance.process.try_wait() {
+        instance.kill();
+        // the port was free when it was reserved, so someone else took it if it is used now
+        if !is_port_free(&args.native_host, port) {
+          return Err(SpawnError::PortTaken);
+        }
+        return Err(
+          WebDriverError::session_not_created(format!("native webdriver exited with {status}"))
+            .into(),
+        );
+      }
+
+      // another server might answer on a port our server failed to listen on, so it must still be running
+      if is_listening(client, args, port).await && matches!(instance.process.try_wait(), Ok(None)) {
+        return Ok(instance);
+      }
+
+      if Instant::now() > deadline {
+        instance.kill();
+        return Err(
+          WebDriverError::session_not_created(format!(
+            "native webdriver did not start listening on port {port} in time"
+          ))
+          .into(),
+        );
+      }
+
+      tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+  }
+
+  fn release(&self, port: u16) {
+    self.sessions().pending.remove(&port);
+  }
+
+  /// Registers the session created on an instance.
+  ///
+  /// The captured logs of the instance are written to the artifacts directory of the session from now on.
+  pub fn insert(&self, session: String, args: &Args, instance: Instance) {
+    if let (Some(artifacts_dir), Some((stdout, stderr))) = (&args.artifacts_dir, &instance.logs) {
+      let dir = artifacts::session_dir(artifacts_dir, &session);
+      let persisted = std::fs::create_dir_all(&dir)
+        .and_then(|()| stdout.persist(&dir.join("stdout.log")))
+        .and_then(|()| stderr.persist(&dir.join("stderr.log")));
+      if let Err(e) = persisted {
+        eprintln!("failed to write the logs of session {session}: {e}");
+      }
+    }
+
+    let mut sessions = self.sessions();
+    sessions.pending.remove(&instance.port);
+    sessions.active.insert(session, instance);
+  }
+
+  /// Counts a failed command of a session, returning its index and whether it differs from the previous failure.
+  pub fn next_failure(&self, session: &str, signature: String) -> Option<(usize, bool)> {
+    let mut sessions = self.sessions();
+    let instance = sessions.active.get_mut(session)?;
+    instance.failures += 1;
+    let repeated = instance.last_failure.as_ref() == Some(&signature);
+    instance.last_failure = Some(signature);
+    Some((instance.failures, !repeated))
+  }
+
+  /// Stops an instance whose session could not be created.
+  pub fn discard(&self, instance: Instance) {
+    self.release(instance.port);
+    instance.kill();
+  }
+
+  /// Stops the native WebDriver server of a session.
+  pub fn remove(&self, session: &str) {
+    let instance = self.sessions().active.remove(session);
+    if let Some(instance) = instance {
+      instance.kill();
+    }
+  }
+
+  /// Cleans up the sessions whose native WebDriver server exited.
+  pub fn reap(&self) {
+    let exited = {
+      let mut sessions = self.sessions();
+      let ids = sessions
+        .active
+        .iter()
+        .filter_map(|(id, instance)| {
+          (!matches!(instance.process.try_wait(), Ok(None))).then(|| id.clone())
+        })
+        .collect::<Vec<_>>();
+      ids
+        .into_iter()
+        .filter_map(|id| sessions.active.remove_entry(&id))
+        .collect::<Vec<_>>()
+    };
+
+    for (id, instance) in exited {
+      eprintln!("native webdriver of session {id} exited, cleaning up");
+      instance.kill();
+    }
+  }
+
+  /// Stops all native WebDriver servers, including the ones still starting.
+  pub fn kill_all(&self) {
+    let processes = {
+      let mut sessions = self.sessions();
+      let sessions = &mut *sessions;
+      let active = sessions
+        .active
+        .drain()
+        .map(|(_, instance)| instance.process);
+      let pending = sessions.pending.drain().filter_map(|(_, process)| process);
+      active.chain(pending).collect::<Vec<_>>()
+    };
+    for process in processes {
+      process.kill();
+    }
+  }
+}
+
+/// Whether nothing listens on the port.
+fn is_port_free(host: &str, port: u16) -> bool {
+  TcpListener::bind((host, port)).is_ok()
+}
+
+/// Whether the native WebDriver server accepts requests.
+async fn is_listening(client: &Client<HttpConnector, Full<Bytes>>, args: &Args, port: u16) -> bool {
+  let Ok(request) =
+    Request::get(format!("http://{}:{port}/status", args.native_host)).body(Full::default())
+  else {
+    return false;
+  };
+  client.request(request).await.is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use hyper_util::rt::TokioExecutor;
+
+  fn args(native_port: u16, max_sessions: Option<usize>) -> Args {
+    Args {
+      port: 4444,
+      native_port,
+      native_host: "127.0.0.1".into(),
+      native_driver: None,
+      max_sessions,
+      artifacts_dir: None,
+      artifacts: Default::default(),
+    }
+  }
+
+  fn client() -> Client<HttpConnector, Full<Bytes>> {
+    Client::builder(TokioExecutor::new()).build_http()
+  }
+
+  #[test]
+  fn reserve() {
+    let pool = Pool::new(PathBuf::new());
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let bound = listener.local_addr().unwrap().port();
+    let args = args(bound, Some(2));
+
+    let (first, first_id) = pool.reserve(&args, args.native_port).unwrap();
+    assert!(first > bound, "the bound port must be skipped");
+    let (second, second_id) = pool.reserve(&args, args.native_port).unwrap();
+    assert!(second > first, "the reserved port must be skipped");
+    assert_ne!(first_id, second_id);
+    assert!(!pool.ready(&args));
+    assert!(pool.reserve(&args, args.native_port).is_err());
+
+    pool.release(first);
+    assert!(pool.ready(&args));
+    let (port, _) = pool.reserve(&args, args.native_port).unwrap();
+    assert_eq!(port, first);
+  }
+
+  #[cfg(unix)]
+  #[tokio::test]
+  async fn start_fails_when_the_native_driver_exits() {
+    let pool = Pool::new("false".into());
+    let args = args(4445, None);
+    let error = match pool.start(&args, &client(), None).await {
+      Ok(_) => panic!("the native driver can not start"),
+      Err(error) => error,
+    };
+    let response = error.into_response();
+    assert_eq!(response.status(), hyper::StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(pool.sessions().pending.is_empty());
+  }
+
+  #[cfg(unix)]
+  #[tokio::test]
+  async fn kill_all_kills_pending_instances() {
+    use std::os::unix::fs::PermissionsExt;
+
+    // a native driver that never listens
+    let dir = std::env::temp_dir().join(format!("tauri-driver-pool-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let binary = dir.join("driver.sh");
+    std::fs::write(&binary, "#!/bin/sh\nexec sleep 30\n").unwrap();
+    std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let pool = Pool::new(binary);
+    let start = tokio::spawn({
+      let pool = pool.clone();
+      async move { pool.start(&args(4445, None), &client(), None).await }
+    });
+
+    let process = loop {
+      let process = pool.sessions().pending.values().flatten().next().cloned();
+      match process {
+        Some(process) => break process,
+        None => tokio::time::sleep(Duration::from_millis(10)).await,
+      }
+    };
+    let data_dir = process.data_dir.clone().unwrap();
+    assert!(data_dir.is_dir());
+
+    pool.kill_all();
+    assert!(matches!(process.try_wait(), Ok(Some(_))));
+    assert!(!data_dir.exists());
+    assert!(start.await.unwrap().is_err());
+    assert!(pool.sessions().pending.is_empty());
+
+    let _ = std::fs::remove_dir_all(dir);
+  }
+}
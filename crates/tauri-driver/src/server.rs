@@ -2,120 +2,276 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use crate::cli::Args;
+use crate::{
+  artifacts,
+  cli::Args,
+  error::{json_response, WebDriverError},
+  extensions,
+  options::TauriOptions,
+  pool::Pool,
+};
 use anyhow::Error;
-use futures_util::TryFutureExt;
-use http_body_util::{BodyExt, Full};
+use http_body_util::{combinators::BoxBody, BodyExt, Full};
 use hyper::{
   body::{Bytes, Incoming},
-  header::CONTENT_LENGTH,
+  header::{HeaderValue, CONTENT_LENGTH},
   http::uri::Authority,
   service::service_fn,
-  Method, Request, Response,
+  Method, Request, Response, StatusCode,
 };
 use hyper_util::{
   client::legacy::{connect::HttpConnector, Client},
   rt::{TokioExecutor, TokioIo},
   server::conn::auto,
 };
-use serde::Deserialize;
 use serde_json::{json, Map, Value};
-use std::path::PathBuf;
-use std::process::Child;
+use std::{path::PathBuf, time::Duration};
 use tokio::net::TcpListener;
 
 const TAURI_OPTIONS: &str = "tauri:options";
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct TauriOptions {
-  application: PathBuf,
-  #[serde(default)]
-  args: Vec<String>,
-  #[cfg(target_os = "windows")]
-  #[serde(default)]
-  webview_options: Option<Value>,
+/// The capabilities of the native driver launching the application.
+#[cfg(target_os = "linux")]
+fn native_capabilities(options: &TauriOptions) -> Map<String, Value> {
+  let mut map = Map::new();
+  map.insert(
+    "webkitgtk:browserOptions".into(),
+    json!({"binary": options.application, "args": options.args}),
+  );
+  map
 }
 
-impl TauriOptions {
-  #[cfg(target_os = "linux")]
-  fn into_native_object(self) -> Map<String, Value> {
-    let mut map = Map::new();
-    map.insert(
-      "webkitgtk:browserOptions".into(),
-      json!({"binary": self.application, "args": self.args}),
-    );
-    map
+/// The capabilities of the native driver launching the application.
+#[cfg(target_os = "windows")]
+fn native_capabilities(options: &TauriOptions) -> Map<String, Value> {
+  let mut ms_edge_options = Map::new();
+  ms_edge_options.insert(
+    "binary".into(),
+    json!(options.application.with_extension("exe")),
+  );
+  ms_edge_options.insert("args".into(), options.args.clone().into());
+
+  if let Some(webview_options) = options.webview_options.clone() {
+    ms_edge_options.insert("webviewOptions".into(), webview_options.into());
   }
 
-  #[cfg(target_os = "windows")]
-  fn into_native_object(self) -> Map<String, Value> {
-    let mut ms_edge_options = Map::new();
-    ms_edge_options.insert(
-      "binary".into(),
-      json!(self.application.with_extension("exe")),
-    );
-    ms_edge_options.insert("args".into(), self.args.into());
+  let mut map = Map::new();
+  map.insert("ms:edgeChromium".into(), json!(true));
+  map.insert("browserName".into(), json!("webview2"));
+  map.insert("ms:edgeOptions".into(), ms_edge_options.into());
+  map
+}
 
-    if let Some(webview_options) = self.webview_options {
-      ms_edge_options.insert("webviewOptions".into(), webview_options);
-    }
+type Body = BoxBody<Bytes, hyper::Error>;
 
-    let mut map = Map::new();
-    map.insert("ms:edgeChromium".into(), json!(true));
-    map.insert("browserName".into(), json!("webview2"));
-    map.insert("ms:edgeOptions".into(), ms_edge_options.into());
-    map
+fn boxed(body: Full<Bytes>) -> Body {
+  body.map_err(|never| match never {}).boxed()
+}
+
+fn full(body: Bytes) -> Body {
+  boxed(Full::new(body))
+}
+
+/// The id of the session a request targets, `/session/{session id}/...`.
+fn session_id(path: &str) -> Option<&str> {
+  let mut segments = path.trim_start_matches('/').split('/');
+  match (segments.next(), segments.next()) {
+    (Some("session"), Some(session)) if !session.is_empty() => Some(session),
+    _ => None,
   }
 }
 
 async fn handle(
   client: Client<HttpConnector, Full<Bytes>>,
+  pool: Pool,
   req: Request<Incoming>,
   args: Args,
-) -> Result<Response<Incoming>, Error> {
-  // manipulate a new session to convert options to the native driver format
-  let new_req: Request<Full<Bytes>> =
-    if let (&Method::POST, "/session") = (req.method(), req.uri().path()) {
-      let (mut parts, body) = req.into_parts();
+) -> Result<Response<Body>, Error> {
+  if let (&Method::POST, "/session") = (req.method(), req.uri().path()) {
+    return new_session(client, pool, req, args).await;
+  }
 
-      // get the body from the future stream and parse it as json
-      let body = body.collect().await?.to_bytes().to_vec();
-      let json: Value = serde_json::from_slice(&body)?;
+  if let (&Method::GET, "/status") = (req.method(), req.uri().path()) {
+    let ready = pool.ready(&args);
+    let message = if ready {
+      "ready to create a session"
+    } else {
+      "maximum number of sessions reached"
+    };
+    let response = json_response(
+      StatusCode::OK,
+      json!({ "value": { "ready": ready, "message": message } }),
+    );
+    return Ok(response.map(boxed));
+  }
 
-      // manipulate the json to convert from tauri option to native driver options
-      let json = map_capabilities(json);
+  // every other command targets the native driver of its session
+  let path = req.uri().path().to_string();
+  let Some(session) = session_id(&path) else {
+    let error = WebDriverError::unknown_command(format!("unknown command {path}"));
+    return Ok(error.into_response().map(boxed));
+  };
+  let Some(port) = pool.port(session) else {
+    let error = WebDriverError::invalid_session_id(session);
+    return Ok(error.into_response().map(boxed));
+  };
 
-      // serialize json and update the content-length header to be accurate
-      let bytes = serde_json::to_vec(&json)?;
-      parts.headers.insert(CONTENT_LENGTH, bytes.len().into());
+  let native_url = native_url(req.headers().get("host"), port)?;
 
-      Request::from_parts(parts, Full::new(bytes.into()))
-    } else {
-      let (parts, body) = req.into_parts();
+  // service the tauri extension commands ourselves instead of forwarding them
+  if let Some(extension) = extensions::ExtensionPath::parse(&path) {
+    let method = req.method().clone();
+    let body = req.into_body().collect().await?.to_bytes();
+    let Some(channel) = pool.channel(session) else {
+      let error = WebDriverError::invalid_session_id(session);
+      return Ok(error.into_response().map(boxed));
+    };
+    let response =
+      extensions::handle(&client, &native_url, &channel, &method, extension, &body).await;
+    return Ok(response.map(boxed));
+  }
+
+  let method = req.method().clone();
+  let delete_session = method == Method::DELETE && path == format!("/session/{session}");
+
+  let (parts, body) = req.into_parts();
+  let body = body.collect().await?.to_bytes();
+  let new_req = Request::from_parts(parts, Full::new(body));
 
-      let body = body.collect().await?.to_bytes().to_vec();
+  let response = client
+    .request(forward_to_native_driver(new_req, port)?)
+    .await;
 
-      Request::from_parts(parts, Full::new(body.into()))
+  // the native driver is not needed anymore once its session is deleted
+  if delete_session {
+    let response = match response {
+      Ok(response) => {
+        let (parts, body) = response.into_parts();
+        let body = body.collect().await;
+        pool.remove(session);
+        Response::from_parts(parts, full(body?.to_bytes()))
+      }
+      Err(e) => {
+        pool.remove(session);
+        return Err(e.into());
+      }
     };
+    return Ok(response);
+  }
+
+  let response = response?;
+  match &args.artifacts_dir {
+    Some(artifacts_dir) if !response.status().is_success() => {
+      let (parts, body) = response.into_parts();
+      let body = body.collect().await?.to_bytes();
+      let failure = artifacts::Failure {
+        method: method.to_string(),
+        path: path.clone(),
+        status: parts.status,
+        body: body.clone(),
+      };
+      if !failure.is_expected() {
+        if let Some((index, new)) = pool.next_failure(session, failure.signature()) {
+          // record it without delaying the response
+          tokio::spawn(artifacts::record_failure(
+            client,
+            args.artifacts.screenshots && new,
+            artifacts::session_dir(artifacts_dir, session),
+            native_url,
+            session.to_string(),
+            index,
+            failure,
+          ));
+        }
+      }
+      Ok(Response::from_parts(parts, full(body)))
+    }
+    _ => Ok(response.map(BodyExt::boxed)),
+  }
+}
+
+/// Starts a native driver for a new session and forwards the request to it.
+async fn new_session(
+  client: Client<HttpConnector, Full<Bytes>>,
+  pool: Pool,
+  req: Request<Incoming>,
+  args: Args,
+) -> Result<Response<Body>, Error> {
+  let (mut parts, body) = req.into_parts();
+
+  // get the body from the future stream and parse it as json
+  let body = body.collect().await?.to_bytes().to_vec();
+  let json: Value = serde_json::from_slice(&body)?;
+
+  // manipulate the json to convert from tauri option to native driver options
+  let (json, options) = match map_capabilities(json) {
+    Ok(mapped) => mapped,
+    Err(message) => {
+      let error = WebDriverError::invalid_argument(message);
+      return Ok(error.into_response().map(boxed));
+    }
+  };
+
+  // serialize json and update the content-length header to be accurate
+  let bytes = serde_json::to_vec(&json)?;
+  parts.headers.insert(CONTENT_LENGTH, bytes.len().into());
+
+  let new_req = Request::from_parts(parts, Full::new(bytes.into()));
+
+  let instance = match pool.start(&args, &client, options.as_ref()).await {
+    Ok(instance) => instance,
+    Err(error) => return Ok(error.into_response().map(boxed)),
+  };
+
+  let response = match forward_to_native_driver(new_req, instance.port) {
+    Ok(new_req) => client.request(new_req).await.map_err(Error::from),
+    Err(e) => Err(e),
+  };
+  let (parts, body) = match response {
+    Ok(response) => response.into_parts(),
+    Err(e) => {
+      pool.discard(instance);
+      return Err(e);
+    }
+  };
+  let body = match body.collect().await {
+    Ok(body) => body.to_bytes(),
+    Err(e) => {
+      pool.discard(instance);
+      return Err(e.into());
+    }
+  };
 
-  client
-    .request(forward_to_native_driver(new_req, args)?)
-    .err_into()
-    .await
+  // keep the native driver around for as long as the session it created
+  let session = serde_json::from_slice::<Value>(&body)
+    .ok()
+    .and_then(|json| {
+      json
+        .pointer("/value/sessionId")
+        .or_else(|| json.get("sessionId"))
+        .and_then(Value::as_str)
+        .map(ToString::to_string)
+    });
+  match session {
+    Some(session) if parts.status.is_success() => pool.insert(session, &args, instance),
+    _ => pool.discard(instance),
+  }
+
+  Ok(Response::from_parts(parts, full(body)))
+}
+
+/// The base URL of a native webdriver server, using the host the request was sent to.
+fn native_url(host: Option<&HeaderValue>, port: u16) -> Result<String, Error> {
+  let host: Authority = host.expect("hyper request has host").to_str()?.parse()?;
+  Ok(format!("http://{}:{port}", host.host()))
 }
 
-/// Transform the request to a request for the native webdriver server.
+/// Transform the request to a request for the native webdriver server listening on `port`.
 fn forward_to_native_driver(
   mut req: Request<Full<Bytes>>,
-  args: Args,
+  port: u16,
 ) -> Result<Request<Full<Bytes>>, Error> {
-  let host: Authority = {
-    let headers = req.headers_mut();
-    headers.remove("host").expect("hyper request has host")
-  }
-  .to_str()?
-  .parse()?;
+  let host = req.headers_mut().remove("host");
 
   let path = req
     .uri()
@@ -123,51 +279,44 @@ fn forward_to_native_driver(
     .expect("hyper request has uri")
     .clone();
 
-  let uri = format!(
-    "http://{}:{}{}",
-    host.host(),
-    args.native_port,
-    path.as_str()
-  );
+  let uri = format!("{}{}", native_url(host.as_ref(), port)?, path.as_str());
 
   let (mut parts, body) = req.into_parts();
   parts.uri = uri.parse()?;
   Ok(Request::from_parts(parts, body))
 }
 
-/// only happy path for now, no errors
-fn map_capabilities(mut json: Value) -> Value {
-  let mut native = None;
+/// Converts the tauri options to the native driver capabilities, returning the parsed options.
+fn map_capabilities(mut json: Value) -> Result<(Value, Option<TauriOptions>), String> {
+  let mut options = None;
   if let Some(capabilities) = json.get_mut("capabilities") {
     if let Some(always_match) = capabilities.get_mut("alwaysMatch") {
       if let Some(always_match) = always_match.as_object_mut() {
         if let Some(tauri_options) = always_match.remove(TAURI_OPTIONS) {
-          if let Ok(options) = serde_json::from_value::<TauriOptions>(tauri_options) {
-            native = Some(options.into_native_object());
-          }
-        }
-
-        if let Some(native) = native.clone() {
-          always_match.extend(native);
+          let parsed = TauriOptions::parse(tauri_options)?;
+          always_match.extend(native_capabilities(&parsed));
+          options = Some(parsed);
         }
       }
     }
   }
 
-  if let Some(native) = native {
+  if let Some(options) = &options {
     if let Some(desired) = json.get_mut("desiredCapabilities") {
       if let Some(desired) = desired.as_object_mut() {
         desired.remove(TAURI_OPTIONS);
-        desired.extend(native);
+        desired.extend(native_capabilities(options));
       }
     }
   }
 
-  json
+  Ok((json, options))
 }
 
 #[tokio::main(flavor = "current_thread")]
-pub async fn run(args: Args, mut _driver: Child) -> Result<(), Error> {
+pub async fn run(args: Args, native_binary: PathBuf) -> Result<(), Error> {
+  let pool = Pool::new(native_binary);
+
   #[cfg(unix)]
   let (signals_handle, signals_task) = {
     use futures_util::StreamExt;
@@ -175,15 +324,14 @@ pub async fn run(args: Args, mut _driver: Child) -> Result<(), Error> {
 
     let signals = signal_hook_tokio::Signals::new([SIGTERM, SIGINT, SIGQUIT])?;
     let signals_handle = signals.handle();
+    let pool = pool.clone();
     let signals_task = tokio::spawn(async move {
       let mut signals = signals.fuse();
       #[allow(clippy::never_loop)]
       while let Some(signal) = signals.next().await {
         match signal {
           SIGTERM | SIGINT | SIGQUIT => {
-            _driver
-              .kill()
-              .expect("unable to kill native webdriver server");
+            pool.kill_all();
             std::process::exit(0);
           }
           _ => unreachable!(),
@@ -193,6 +341,18 @@ pub async fn run(args: Args, mut _driver: Child) -> Result<(), Error> {
     (signals_handle, signals_task)
   };
 
+  // clean up the sessions whose native webdriver crashed
+  let reaper = {
+    let pool = pool.clone();
+    tokio::spawn(async move {
+      let mut interval = tokio::time::interval(Duration::from_secs(1));
+      loop {
+        interval.tick().await;
+        pool.reap();
+      }
+    })
+  };
+
   let address = std::net::SocketAddr::from(([127, 0, 0, 1], args.port));
 
   // the client we use to proxy requests to the native webdriver
@@ -207,6 +367,7 @@ pub async fn run(args: Args, mut _driver: Child) -> Result<(), Error> {
     if let Ok(listener) = TcpListener::bind(address).await {
       loop {
         let client = client.clone();
+        let pool = pool.clone();
         let args = args.clone();
         if let Ok((stream, _)) = listener.accept().await {
           let io = TokioIo::new(stream);
@@ -218,7 +379,7 @@ pub async fn run(args: Args, mut _driver: Child) -> Result<(), Error> {
               .preserve_header_case(true)
               .serve_connection(
                 io,
-                service_fn(|request| handle(client.clone(), request, args.clone())),
+                service_fn(|request| handle(client.clone(), pool.clone(), request, args.clone())),
               )
               .await
             {
@@ -235,6 +396,9 @@ pub async fn run(args: Args, mut _driver: Child) -> Result<(), Error> {
   };
   srv.await;
 
+  reaper.abort();
+  pool.kill_all();
+
   #[cfg(unix)]
   {
     signals_handle.close();
@@ -243,3 +407,52 @@ pub async fn run(args: Args, mut _driver: Child) -> Result<(), Error> {
 
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn session_id() {
+    assert_eq!(super::session_id("/session/1234"), Some("1234"));
+    assert_eq!(super::session_id("/session/1234/url"), Some("1234"));
+    assert_eq!(super::session_id("session/1234/tauri/invoke"), Some("1234"));
+    assert_eq!(super::session_id("/session"), None);
+    assert_eq!(super::session_id("/session/"), None);
+    assert_eq!(super::session_id("/status"), None);
+    assert_eq!(super::session_id("/sessions/1234"), None);
+  }
+
+  #[test]
+  fn map_capabilities_without_tauri_options() {
+    let json = json!({ "capabilities": { "alwaysMatch": { "browserName": "wry" } } });
+    let (mapped, options) = map_capabilities(json.clone()).unwrap();
+    assert_eq!(mapped, json);
+    assert!(options.is_none());
+  }
+
+  #[test]
+  fn map_capabilities_with_tauri_options() {
+    let tauri_options = json!({ "application": "/path/to/app", "args": ["--flag"] });
+    let json = json!({
+      "capabilities": { "alwaysMatch": { TAURI_OPTIONS: tauri_options } },
+      "desiredCapabilities": { TAURI_OPTIONS: tauri_options },
+    });
+    let (mapped, options) = map_capabilities(json).unwrap();
+
+    let options = options.unwrap();
+    assert_eq!(options.application, PathBuf::from("/path/to/app"));
+    assert_eq!(options.args, ["--flag"]);
+
+    let native = Value::Object(native_capabilities(&options));
+    assert_eq!(mapped["capabilities"]["alwaysMatch"], native);
+    assert_eq!(mapped["desiredCapabilities"], native);
+  }
+
+  #[test]
+  fn map_capabilities_with_invalid_tauri_options() {
+    let json = json!({ "capabilities": { "alwaysMatch": { TAURI_OPTIONS: { "args": [] } } } });
+    let error = map_capabilities(json).unwrap_err();
+    assert!(error.starts_with("invalid tauri:options"), "{error}");
+  }
+}
@@ -2,9 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use crate::cli::Args;
+use crate::{cli::Args, options::TauriOptions};
 use std::{
   env::current_dir,
+  path::{Path, PathBuf},
   process::{Command, Stdio},
 };
 
@@ -16,8 +17,8 @@ const DRIVER_BINARY: &str = "WebKitWebDriver";
 const DRIVER_BINARY: &str = "msedgedriver.exe";
 
 /// Find the native driver binary in the PATH, or exits the process with an error.
-pub fn native(args: &Args) -> Command {
-  let native_binary = match args.native_driver.as_deref() {
+pub fn native_binary(args: &Args) -> PathBuf {
+  match args.native_driver.as_deref() {
     Some(custom) => {
       if custom.exists() {
         custom.to_owned()
@@ -44,12 +45,24 @@ pub fn native(args: &Args) -> Command {
         std::process::exit(1);
       }
     },
-  };
+  }
+}
 
+/// The command running the native driver.
+///
+/// The driver listens on `port`, and the application it launches inherits its environment and
+/// working directory from the `options`, and stores its data in `data_dir` if set.
+pub fn native(
+  native_binary: &Path,
+  args: &Args,
+  port: u16,
+  options: Option<&TauriOptions>,
+  data_dir: Option<&Path>,
+) -> Command {
   let mut cmd = Command::new(native_binary);
   cmd.env("TAURI_AUTOMATION", "true"); // 1.x
   cmd.env("TAURI_WEBVIEW_AUTOMATION", "true"); // 2.x
-  cmd.arg(format!("--port={}", args.native_port));
+  cmd.arg(format!("--port={port}"));
   cmd.arg(format!("--host={}", args.native_host));
 
   // Don't inherit stdout from parent to prevent native WebDriver binary/HTTP protocol data
@@ -57,5 +70,33 @@ pub fn native(args: &Args) -> Command {
   // Keep stderr inherited so WebDriver logs/errors are still visible.
   cmd.stdout(Stdio::null());
 
+  if let Some(data_dir) = data_dir {
+    isolate_data_dir(&mut cmd, data_dir);
+  }
+
+  if let Some(options) = options {
+    cmd.envs(&options.env);
+    if let Some(cwd) = &options.cwd {
+      cmd.current_dir(cwd);
+    }
+  }
+
   cmd
 }
+
+/// Points the data directories of the launched application to `data_dir`.
+#[cfg(target_os = "linux")]
+fn isolate_data_dir(cmd: &mut Command, data_dir: &Path) {
+  cmd.env("XDG_CONFIG_HOME", data_dir.join("config"));
+  cmd.env("XDG_DATA_HOME", data_dir.join("data"));
+  cmd.env("XDG_CACHE_HOME", data_dir.join("cache"));
+  cmd.env("XDG_STATE_HOME", data_dir.join("state"));
+}
+
+/// Points the webview data of the launched application to `data_dir`.
+///
+/// The known folders used for the application data can not be overridden on Windows.
+#[cfg(target_os = "windows")]
+fn isolate_data_dir(cmd: &mut Command, data_dir: &Path) {
+  cmd.env("WEBVIEW2_USER_DATA_FOLDER", data_dir.join("EBWebView"));
+}
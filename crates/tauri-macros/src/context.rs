@@ -10,7 +10,7 @@ use syn::{
   punctuated::Punctuated,
   Expr, ExprLit, Lit, LitBool, LitStr, Meta, PathArguments, PathSegment, Token,
 };
-use tauri_codegen::{context_codegen, get_config, ContextData};
+use tauri_codegen::{context_codegen_with_snapshot, get_config, ContextData};
 use tauri_utils::{config::parse::does_supported_file_name_exist, platform::Target};
 
 pub(crate) struct ContextItems {
@@ -160,7 +160,10 @@ pub(crate) fn generate_context(context: ContextItems) -> TokenStream {
       assets: context.assets,
       test: context.test,
     })
-    .and_then(|data| context_codegen(data).map_err(|e| e.to_string()));
+    .and_then(|data| {
+      let snapshot = std::env::var_os("TAURI_CONTEXT_SNAPSHOT").map(PathBuf::from);
+      context_codegen_with_snapshot(data, snapshot.as_deref()).map_err(|e| e.to_string())
+    });
 
   match context {
     Ok(code) => code,
@@ -11,6 +11,11 @@ use tauri_utils::{
   write_if_changed,
 };
 
+// the `tauri:options` capability of tauri-driver, which is a binary crate
+#[allow(dead_code)]
+#[path = "../tauri-driver/src/options.rs"]
+mod tauri_driver_options;
+
 macro_rules! schema {
   ($name:literal, $path:ty) => {
     (concat!($name, ".schema.json"), schemars::schema_for!($path))
@@ -54,5 +59,13 @@ pub fn main() -> Result<(), Box<dyn Error>> {
     write_if_changed(out.join("../tauri-cli/config.schema.json"), config_schema)?;
   }
 
+  // write tauri-driver options schema file
+  {
+    let (filename, schema) = schema!("tauri-driver-options", tauri_driver_options::TauriOptions);
+    let schema = serde_json::to_string_pretty(&schema)?;
+    write_if_changed(schemas_dir.join(filename), &schema)?;
+    write_if_changed(out.join("../tauri-driver/options.schema.json"), schema)?;
+  }
+
   Ok(())
 }
@@ -66,6 +66,7 @@ pub mod manifest;
 pub mod resolved;
 #[cfg(feature = "schema")]
 pub mod schema;
+pub mod scope;
 pub mod value;
 
 /// Possible errors while processing ACL files.
@@ -331,6 +332,27 @@ impl PartialEq for RemoteUrlPattern {
 
 impl Eq for RemoteUrlPattern {}
 
+impl Serialize for RemoteUrlPattern {
+  fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    serializer.serialize_str(self.as_str())
+  }
+}
+
+impl<'de> Deserialize<'de> for RemoteUrlPattern {
+  fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+  where
+    D: serde::Deserializer<'de>,
+  {
+    let pattern = String::deserialize(deserializer)?;
+    pattern
+      .parse()
+      .map_err(|e| serde::de::Error::custom(format!("invalid URL pattern `{pattern}`: {e}")))
+  }
+}
+
 /// Execution context of an IPC call.
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
 pub enum ExecutionContext {
@@ -19,9 +19,34 @@ use crate::{platform::Target, write_if_changed};
 use super::{
   capability::CapabilityFile,
   manifest::{Manifest, PermissionFile},
-  Permission, PermissionSet, PERMISSION_SCHEMA_FILE_NAME,
+  Permission, PermissionSet, RemoteUrlPattern, PERMISSION_SCHEMA_FILE_NAME,
 };
 
+impl schemars::JsonSchema for RemoteUrlPattern {
+  fn schema_name() -> String {
+    "RemoteUrlPattern".to_string()
+  }
+
+  fn schema_id() -> std::borrow::Cow<'static, str> {
+    // Include the module, in case a type with the same name is in another module/crate
+    std::borrow::Cow::Borrowed(concat!(module_path!(), "::RemoteUrlPattern"))
+  }
+
+  fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> Schema {
+    Schema::Object(SchemaObject {
+      metadata: Some(Box::new(Metadata {
+        description: Some(
+          "A URL pattern, see <https://urlpattern.spec.whatwg.org/> for the syntax.".into(),
+        ),
+        examples: vec!["https://*.tauri.app/*".into()],
+        ..Default::default()
+      })),
+      instance_type: Some(InstanceType::String.into()),
+      ..Default::default()
+    })
+  }
+}
+
 /// Capability schema file name.
 pub const CAPABILITIES_SCHEMA_FILE_NAME: &str = "schema.json";
 /// Path of the folder where schemas are saved.
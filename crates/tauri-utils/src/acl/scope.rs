@@ -0,0 +1,160 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Reusable scope types for the ACL.
+//!
+//! Plugins usually need to restrict URLs, numeric arguments or string arguments
+//! (e.g. program names or environment variable keys). These types can be used directly
+//! as the scope object of `tauri::ipc::CommandScope` and `tauri::ipc::GlobalScope`,
+//! and their JSON schema can be registered with `tauri_plugin::Builder::global_scope_schema`
+//! so permission files get autocomplete.
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::RemoteUrlPattern;
+
+/// A scope entry that matches URLs against a [URL pattern](https://urlpattern.spec.whatwg.org/).
+///
+/// ```json
+/// { "url": "https://*.tauri.app/*" }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct UrlScope {
+  /// The URL pattern to match.
+  ///
+  /// An empty or `/` pathname, search and hash match any value.
+  pub url: RemoteUrlPattern,
+}
+
+impl UrlScope {
+  /// Test if the given URL matches this scope entry.
+  pub fn matches(&self, url: &Url) -> bool {
+    self.url.test(url)
+  }
+}
+
+/// A scope entry that matches a number within an inclusive range.
+///
+/// ```json
+/// { "min": 0, "max": 100 }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct RangeScope {
+  /// The inclusive lower bound. No lower bound if not set.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub min: Option<f64>,
+  /// The inclusive upper bound. No upper bound if not set.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub max: Option<f64>,
+}
+
+impl RangeScope {
+  /// Test if the given number is within this range.
+  pub fn matches(&self, value: f64) -> bool {
+    !value.is_nan()
+      && self.min.map(|min| value >= min).unwrap_or(true)
+      && self.max.map(|max| value <= max).unwrap_or(true)
+  }
+}
+
+/// A scope entry that matches strings, either against a list of values or a glob pattern.
+///
+/// ```json
+/// [
+///   { "values": ["stdout", "stderr"] },
+///   { "glob": "TAURI_*" }
+/// ]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub enum StringScope {
+  /// Matches if the string is equal to one of the values.
+  Values {
+    /// The list of accepted values.
+    values: Vec<String>,
+  },
+  /// Matches if the string matches the glob pattern.
+  Glob {
+    /// The glob pattern.
+    ///
+    /// See <https://docs.rs/glob/latest/glob/struct.Pattern.html> for the syntax.
+    #[serde(with = "glob_pattern")]
+    #[cfg_attr(feature = "schema", schemars(with = "String"))]
+    glob: glob::Pattern,
+  },
+}
+
+impl StringScope {
+  /// Test if the given string matches this scope entry.
+  pub fn matches(&self, value: &str) -> bool {
+    match self {
+      Self::Values { values } => values.iter().any(|v| v == value),
+      Self::Glob { glob } => glob.matches(value),
+    }
+  }
+}
+
+mod glob_pattern {
+  use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+  pub fn serialize<S: Serializer>(
+    pattern: &glob::Pattern,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(pattern.as_str())
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<glob::Pattern, D::Error> {
+    let pattern = String::deserialize(deserializer)?;
+    glob::Pattern::new(&pattern)
+      .map_err(|e| D::Error::custom(format!("invalid glob `{pattern}`: {e}")))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn url_scope() {
+    let scope: UrlScope = serde_json::from_str(r#"{ "url": "https://*.tauri.app" }"#).unwrap();
+    assert!(scope.matches(&"https://v2.tauri.app/start".parse().unwrap()));
+    assert!(!scope.matches(&"https://tauri.app".parse().unwrap()));
+    assert!(!scope.matches(&"http://v2.tauri.app".parse().unwrap()));
+  }
+
+  #[test]
+  fn range_scope() {
+    let scope: RangeScope = serde_json::from_str(r#"{ "min": 1, "max": 10 }"#).unwrap();
+    assert!(scope.matches(1.));
+    assert!(scope.matches(10.));
+    assert!(!scope.matches(0.5));
+    assert!(!scope.matches(11.));
+    assert!(!scope.matches(f64::NAN));
+
+    let scope: RangeScope = serde_json::from_str(r#"{ "max": 0 }"#).unwrap();
+    assert!(scope.matches(-100.));
+    assert!(!scope.matches(1.));
+  }
+
+  #[test]
+  fn string_scope() {
+    let scope: StringScope = serde_json::from_str(r#"{ "values": ["a", "b"] }"#).unwrap();
+    assert!(scope.matches("a"));
+    assert!(!scope.matches("c"));
+
+    let scope: StringScope = serde_json::from_str(r#"{ "glob": "TAURI_*" }"#).unwrap();
+    assert!(scope.matches("TAURI_ENV"));
+    assert!(!scope.matches("PATH"));
+
+    assert!(serde_json::from_str::<StringScope>(r#"{ "glob": "[" }"#).is_err());
+  }
+}
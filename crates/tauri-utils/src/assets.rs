@@ -5,18 +5,216 @@
 //! The Assets module allows you to read files that have been bundled by tauri
 //! during both compile time and runtime.
 
+pub mod archive;
+
 #[doc(hidden)]
 pub use phf;
 use std::{
   borrow::Cow,
+  collections::HashMap,
   path::{Component, Path},
 };
 
+use crate::config::CspDirectiveSources;
+
 /// The token used for script nonces.
 pub const SCRIPT_NONCE_TOKEN: &str = "__TAURI_SCRIPT_NONCE__";
 /// The token used for style nonces.
 pub const STYLE_NONCE_TOKEN: &str = "__TAURI_STYLE_NONCE__";
 
+// inspired by <https://github.com/rust-lang/rust/blob/1be5c8f90912c446ecbdc405cbc4a89f9acd20fd/library/alloc/src/str.rs#L260-L297>
+fn replace_with_callback<F: FnMut() -> String>(
+  original: &str,
+  pattern: &str,
+  mut replacement: F,
+) -> String {
+  let mut result = String::new();
+  let mut last_end = 0;
+  for (start, part) in original.match_indices(pattern) {
+    result.push_str(unsafe { original.get_unchecked(last_end..start) });
+    result.push_str(&replacement());
+    last_end = start + part.len();
+  }
+  result.push_str(unsafe { original.get_unchecked(last_end..original.len()) });
+  result
+}
+
+/// Replaces the nonce `token`s of an HTML asset with random nonces, adding them and the `hashes` to the CSP `directive`.
+pub fn replace_csp_nonce(
+  asset: &mut String,
+  token: &str,
+  csp: &mut HashMap<String, CspDirectiveSources>,
+  directive: &str,
+  hashes: Vec<String>,
+) {
+  let mut nonces = Vec::new();
+  *asset = replace_with_callback(asset, token, || {
+    let nonce = getrandom::u64().expect("failed to get random bytes");
+    nonces.push(nonce);
+    nonce.to_string()
+  });
+
+  if !(nonces.is_empty() && hashes.is_empty()) {
+    let nonce_sources = nonces
+      .into_iter()
+      .map(|n| format!("'nonce-{n}'"))
+      .collect::<Vec<String>>();
+    let sources = csp.entry(directive.into()).or_default();
+    let self_source = "'self'".to_string();
+    if !sources.contains(&self_source) {
+      sources.push(self_source);
+    }
+    sources.extend(nonce_sources);
+    sources.extend(hashes);
+  }
+}
+
+/// Size in bytes of the nonce prepended to encrypted assets.
+#[cfg(feature = "asset-encryption")]
+pub const ASSET_NONCE_SIZE: usize = 12;
+
+/// Encrypts an asset with AES-256-GCM, prepending the nonce to the ciphertext.
+#[cfg(feature = "asset-encryption")]
+pub fn encrypt_asset(
+  key: &[u8; 32],
+  nonce: &[u8; ASSET_NONCE_SIZE],
+  asset: &[u8],
+) -> Option<Vec<u8>> {
+  use aes_gcm::{aead::Aead, Aes256Gcm, Key, KeyInit, Nonce};
+
+  let ciphertext = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key))
+    .encrypt(Nonce::from_slice(nonce), asset)
+    .ok()?;
+  let mut encrypted = Vec::with_capacity(ASSET_NONCE_SIZE + ciphertext.len());
+  encrypted.extend_from_slice(nonce);
+  encrypted.extend(ciphertext);
+  Some(encrypted)
+}
+
+/// Decrypts an asset encrypted with [`encrypt_asset`].
+#[cfg(feature = "asset-encryption")]
+pub fn decrypt_asset(key: &[u8; 32], encrypted: &[u8]) -> Option<Vec<u8>> {
+  use aes_gcm::{aead::Aead, Aes256Gcm, Key, KeyInit, Nonce};
+
+  if encrypted.len() < ASSET_NONCE_SIZE {
+    return None;
+  }
+  let (nonce, ciphertext) = encrypted.split_at(ASSET_NONCE_SIZE);
+  Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key))
+    .decrypt(Nonce::from_slice(nonce), ciphertext)
+    .ok()
+}
+
+/// The encoding an embedded asset is stored in.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetEncoding {
+  /// The asset is not compressed.
+  Identity,
+  /// The asset is compressed with brotli.
+  Brotli,
+  /// The asset is compressed with zstd.
+  Zstd,
+}
+
+impl AssetEncoding {
+  /// The encoding of the assets that do not have an explicit encoding.
+  pub const DEFAULT: Self = if cfg!(feature = "compression") {
+    Self::Brotli
+  } else {
+    Self::Identity
+  };
+
+  /// The `Content-Encoding` header value for this encoding, `None` if the asset is not compressed.
+  pub fn content_encoding(self) -> Option<&'static str> {
+    match self {
+      Self::Identity => None,
+      Self::Brotli => Some("br"),
+      Self::Zstd => Some("zstd"),
+    }
+  }
+
+  /// Parses an `Accept-Encoding` header value, returning the accepted compressed encodings.
+  pub fn parse_accept_encoding(header: &str) -> Vec<Self> {
+    header
+      .split(',')
+      .filter_map(|encoding| {
+        let mut params = encoding.split(';').map(str::trim);
+        let encoding = match params.next()? {
+          "br" => Self::Brotli,
+          "zstd" => Self::Zstd,
+          _ => return None,
+        };
+        // `q=0` means the encoding is not acceptable
+        let rejected = params.any(|param| {
+          param
+            .strip_prefix("q=")
+            .and_then(|q| q.parse::<f32>().ok())
+            .is_some_and(|q| q == 0.0)
+        });
+        (!rejected).then_some(encoding)
+      })
+      .collect()
+  }
+
+  /// Decodes an asset stored in this encoding.
+  ///
+  /// Returns `None` if the asset is invalid or the encoding is not supported
+  /// without the `compression` (brotli) or `compression-zstd` (zstd) feature.
+  pub fn decode(self, asset: Cow<'_, [u8]>) -> Option<Cow<'static, [u8]>> {
+    match self {
+      Self::Identity => Some(Cow::Owned(asset.into_owned())),
+      #[cfg(feature = "compression")]
+      Self::Brotli => {
+        // with the exception of extremely small files, output should usually be
+        // at least as large as the compressed version.
+        let mut buf = Vec::with_capacity(asset.len());
+        brotli::BrotliDecompress(&mut &*asset, &mut buf)
+          .ok()
+          .map(|()| Cow::Owned(buf))
+      }
+      #[cfg(feature = "compression-zstd")]
+      Self::Zstd => zstd::stream::decode_all(&*asset).ok().map(Cow::Owned),
+      #[cfg(not(feature = "compression"))]
+      Self::Brotli => None,
+      #[cfg(not(feature = "compression-zstd"))]
+      Self::Zstd => None,
+    }
+  }
+
+  /// Decodes at most `len` bytes from the start of an asset stored in this encoding,
+  /// e.g. to infer its MIME type without decoding it entirely.
+  pub fn decode_prefix(self, asset: &[u8], len: usize) -> Option<Vec<u8>> {
+    #[allow(unused_imports)]
+    use std::io::Read;
+
+    let mut buf = Vec::with_capacity(len);
+    match self {
+      Self::Identity => buf.extend_from_slice(&asset[..len.min(asset.len())]),
+      #[cfg(feature = "compression")]
+      Self::Brotli => {
+        brotli::Decompressor::new(asset, 4096)
+          .take(len as u64)
+          .read_to_end(&mut buf)
+          .ok()?;
+      }
+      #[cfg(feature = "compression-zstd")]
+      Self::Zstd => {
+        zstd::stream::read::Decoder::new(asset)
+          .ok()?
+          .take(len as u64)
+          .read_to_end(&mut buf)
+          .ok()?;
+      }
+      #[cfg(not(feature = "compression"))]
+      Self::Brotli => return None,
+      #[cfg(not(feature = "compression-zstd"))]
+      Self::Zstd => return None,
+    }
+    Some(buf)
+  }
+}
+
 /// Assets iterator.
 pub type AssetsIter<'a> = dyn Iterator<Item = (Cow<'a, str>, Cow<'a, [u8]>)> + 'a;
 
@@ -119,6 +317,13 @@ pub struct EmbeddedAssets {
   global_hashes: &'static [CspHash<'static>],
   // Hashes that are associated to the CSP of the HTML file identified by the map key (the HTML asset key).
   html_hashes: phf::Map<&'static str, &'static [CspHash<'static>]>,
+  // SHA-256 hex digests of the uncompressed assets, used to verify them before serving.
+  integrity: Option<phf::Map<&'static str, &'static str>>,
+  // The encoding of each asset, assets not in the map use `AssetEncoding::DEFAULT`.
+  encodings: Option<phf::Map<&'static str, AssetEncoding>>,
+  // AES-256-GCM key used to decrypt the assets, if they were encrypted at build time.
+  #[cfg(feature = "asset-encryption")]
+  encryption_key: Option<&'static [u8; 32]>,
 }
 
 /// Temporary struct that overrides the Debug formatting for the `assets` field.
@@ -147,6 +352,8 @@ impl std::fmt::Debug for EmbeddedAssets {
       .field("assets", &DebugAssetMap(&self.assets))
       .field("global_hashes", &self.global_hashes)
       .field("html_hashes", &self.html_hashes)
+      .field("integrity", &self.integrity)
+      .field("encodings", &self.encodings)
       .finish()
   }
 }
@@ -162,33 +369,55 @@ impl EmbeddedAssets {
       assets: map,
       global_hashes,
       html_hashes,
+      integrity: None,
+      encodings: None,
+      #[cfg(feature = "asset-encryption")]
+      encryption_key: None,
     }
   }
 
-  /// Get an asset by key.
-  #[cfg(feature = "compression")]
-  pub fn get(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>> {
+  /// Sets the SHA-256 hex digests of the uncompressed assets, computed at build time.
+  #[must_use]
+  pub fn with_integrity(mut self, integrity: phf::Map<&'static str, &'static str>) -> Self {
+    self.integrity.replace(integrity);
     self
-      .assets
-      .get(key.as_ref())
-      .map(|&(mut asdf)| {
-        // with the exception of extremely small files, output should usually be
-        // at least as large as the compressed version.
-        let mut buf = Vec::with_capacity(asdf.len());
-        brotli::BrotliDecompress(&mut asdf, &mut buf).map(|()| buf)
-      })
-      .and_then(Result::ok)
-      .map(Cow::Owned)
+  }
+
+  /// Sets the encoding of each asset, assets not in the map use [`AssetEncoding::DEFAULT`].
+  #[must_use]
+  pub fn with_encodings(mut self, encodings: phf::Map<&'static str, AssetEncoding>) -> Self {
+    self.encodings.replace(encodings);
+    self
+  }
+
+  /// Sets the key used to decrypt the assets, which were encrypted at build time.
+  #[cfg(feature = "asset-encryption")]
+  #[must_use]
+  pub fn with_encryption_key(mut self, key: &'static [u8; 32]) -> Self {
+    self.encryption_key.replace(key);
+    self
+  }
+
+  /// Get the embedded (possibly compressed) bytes of an asset, decrypting them if needed.
+  fn get_raw(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>> {
+    let asset = *self.assets.get(key.as_ref())?;
+    decrypt(
+      asset,
+      #[cfg(feature = "asset-encryption")]
+      self.encryption_key,
+    )
   }
 
   /// Get an asset by key.
-  #[cfg(not(feature = "compression"))]
   pub fn get(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>> {
-    self
-      .assets
-      .get(key.as_ref())
-      .copied()
-      .map(|a| Cow::Owned(a.to_vec()))
+    let (asset, encoding) = self.get_encoded(key)?;
+    encoding.decode(asset)
+  }
+
+  /// Get an asset by key in the encoding it is stored in, without decoding it.
+  pub fn get_encoded(&self, key: &AssetKey) -> Option<(Cow<'_, [u8]>, AssetEncoding)> {
+    let asset = self.get_raw(key)?;
+    Some((asset, encoding(self.encodings.as_ref(), key)))
   }
 
   /// Iterate on the assets.
@@ -201,21 +430,374 @@ impl EmbeddedAssets {
     )
   }
 
+  /// The SHA-256 hex digest of the given asset, if integrity data was embedded.
+  pub fn integrity(&self, key: &AssetKey) -> Option<&str> {
+    self
+      .integrity
+      .as_ref()
+      .and_then(|integrity| integrity.get(key.as_ref()))
+      .copied()
+  }
+
   /// CSP hashes for the given asset.
   pub fn csp_hashes(&self, html_path: &AssetKey) -> Box<dyn Iterator<Item = CspHash<'_>> + '_> {
-    Box::new(
-      self
-        .global_hashes
-        .iter()
-        .chain(
-          self
-            .html_hashes
-            .get(html_path.as_ref())
-            .copied()
-            .into_iter()
-            .flatten(),
-        )
-        .copied(),
-    )
+    csp_hashes(self.global_hashes, &self.html_hashes, html_path)
+  }
+}
+
+/// [`Assets`] implementation that reads the assets from an [`archive`] shipped as a bundle resource.
+///
+/// The archive is memory mapped the first time an asset is requested, so the assets are not loaded at startup
+/// and do not take space in the application binary. The CSP hashes and integrity digests are computed at build time.
+///
+/// The archive is looked up in the resource directory, falling back to the directory of the current executable.
+#[cfg(feature = "resources")]
+pub struct ArchiveAssets {
+  file_name: &'static str,
+  resource_path: &'static str,
+  id: &'static str,
+  // Hashes that must be injected to the CSP of every HTML file.
+  global_hashes: &'static [CspHash<'static>],
+  // Hashes that are associated to the CSP of the HTML file identified by the map key (the HTML asset key).
+  html_hashes: phf::Map<&'static str, &'static [CspHash<'static>]>,
+  // SHA-256 hex digests of the uncompressed assets, used to verify them before serving.
+  integrity: Option<phf::Map<&'static str, &'static str>>,
+  // The encoding of each asset, assets not in the map use `AssetEncoding::DEFAULT`.
+  encodings: Option<phf::Map<&'static str, AssetEncoding>>,
+  // AES-256-GCM key used to decrypt the assets, if they were encrypted at build time.
+  #[cfg(feature = "asset-encryption")]
+  encryption_key: Option<&'static [u8; 32]>,
+  resource_dir: std::sync::OnceLock<std::path::PathBuf>,
+  archive: std::sync::OnceLock<Option<archive::Archive>>,
+}
+
+#[cfg(feature = "resources")]
+impl std::fmt::Debug for ArchiveAssets {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("ArchiveAssets")
+      .field("file_name", &self.file_name)
+      .field("resource_path", &self.resource_path)
+      .field("id", &self.id)
+      .field("global_hashes", &self.global_hashes)
+      .field("html_hashes", &self.html_hashes)
+      .field("integrity", &self.integrity)
+      .field("encodings", &self.encodings)
+      .field("resource_dir", &self.resource_dir)
+      .field("archive", &self.archive)
+      .finish()
+  }
+}
+
+#[cfg(feature = "resources")]
+impl ArchiveAssets {
+  /// Creates a new instance reading the archive with the given file name and identifier.
+  ///
+  /// `resource_path` is the path of the archive relative to the resource directory.
+  pub const fn new(
+    file_name: &'static str,
+    resource_path: &'static str,
+    id: &'static str,
+    global_hashes: &'static [CspHash<'static>],
+    html_hashes: phf::Map<&'static str, &'static [CspHash<'static>]>,
+  ) -> Self {
+    Self {
+      file_name,
+      resource_path,
+      id,
+      global_hashes,
+      html_hashes,
+      integrity: None,
+      encodings: None,
+      #[cfg(feature = "asset-encryption")]
+      encryption_key: None,
+      resource_dir: std::sync::OnceLock::new(),
+      archive: std::sync::OnceLock::new(),
+    }
+  }
+
+  /// Sets the SHA-256 hex digests of the uncompressed assets, computed at build time.
+  #[must_use]
+  pub fn with_integrity(mut self, integrity: phf::Map<&'static str, &'static str>) -> Self {
+    self.integrity.replace(integrity);
+    self
+  }
+
+  /// Sets the key used to decrypt the assets, which were encrypted at build time.
+  #[cfg(feature = "asset-encryption")]
+  #[must_use]
+  pub fn with_encryption_key(mut self, key: &'static [u8; 32]) -> Self {
+    self.encryption_key.replace(key);
+    self
+  }
+
+  /// Sets the encoding of each asset, assets not in the map use [`AssetEncoding::DEFAULT`].
+  #[must_use]
+  pub fn with_encodings(mut self, encodings: phf::Map<&'static str, AssetEncoding>) -> Self {
+    self.encodings.replace(encodings);
+    self
+  }
+
+  /// Sets the resource directory the archive is read from.
+  ///
+  /// Has no effect once the archive has been loaded.
+  pub fn set_resource_dir(&self, resource_dir: std::path::PathBuf) {
+    let _ = self.resource_dir.set(resource_dir);
+  }
+
+  /// The archive, opened on first use.
+  fn archive(&self) -> Option<&archive::Archive> {
+    self
+      .archive
+      .get_or_init(|| {
+        let candidates = self
+          .resource_dir
+          .get()
+          .map(|dir| dir.join(self.resource_path))
+          .into_iter()
+          .chain(
+            std::env::current_exe()
+              .ok()
+              .and_then(|exe| exe.parent().map(|dir| dir.join(self.file_name))),
+          );
+
+        for path in candidates {
+          match archive::Archive::open(&path) {
+            Ok(archive) if archive.id() == self.id => return Some(archive),
+            Ok(_) => log::warn!("ignoring stale asset archive {}", path.display()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => log::warn!("failed to open asset archive {}: {e}", path.display()),
+          }
+        }
+
+        log::error!("asset archive {} not found", self.file_name);
+        None
+      })
+      .as_ref()
+  }
+
+  /// Get an asset by key.
+  pub fn get(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>> {
+    let (asset, encoding) = self.get_encoded(key)?;
+    encoding.decode(asset)
+  }
+
+  /// Get an asset by key in the encoding it is stored in, without decoding it.
+  pub fn get_encoded(&self, key: &AssetKey) -> Option<(Cow<'_, [u8]>, AssetEncoding)> {
+    let asset = self.archive()?.get(key.as_ref())?;
+    let asset = decrypt(
+      asset,
+      #[cfg(feature = "asset-encryption")]
+      self.encryption_key,
+    )?;
+    Some((asset, encoding(self.encodings.as_ref(), key)))
+  }
+
+  /// Iterate on the assets.
+  pub fn iter(&self) -> Box<AssetsIter<'_>> {
+    match self.archive() {
+      Some(archive) => Box::new(
+        archive
+          .iter()
+          .map(|(k, b)| (Cow::Borrowed(k), Cow::Borrowed(b))),
+      ),
+      None => Box::new(std::iter::empty()),
+    }
+  }
+
+  /// The SHA-256 hex digest of the given asset, if integrity data was embedded.
+  pub fn integrity(&self, key: &AssetKey) -> Option<&str> {
+    self
+      .integrity
+      .as_ref()
+      .and_then(|integrity| integrity.get(key.as_ref()))
+      .copied()
+  }
+
+  /// CSP hashes for the given asset.
+  pub fn csp_hashes(&self, html_path: &AssetKey) -> Box<dyn Iterator<Item = CspHash<'_>> + '_> {
+    csp_hashes(self.global_hashes, &self.html_hashes, html_path)
+  }
+}
+
+/// Decrypts the stored bytes of an asset if an encryption key is set.
+fn decrypt<'a>(
+  asset: &'a [u8],
+  #[cfg(feature = "asset-encryption")] encryption_key: Option<&[u8; 32]>,
+) -> Option<Cow<'a, [u8]>> {
+  #[cfg(feature = "asset-encryption")]
+  if let Some(encryption_key) = encryption_key {
+    return decrypt_asset(encryption_key, asset).map(Cow::Owned);
+  }
+
+  Some(Cow::Borrowed(asset))
+}
+
+fn encoding(
+  encodings: Option<&phf::Map<&'static str, AssetEncoding>>,
+  key: &AssetKey,
+) -> AssetEncoding {
+  encodings
+    .and_then(|encodings| encodings.get(key.as_ref()))
+    .copied()
+    .unwrap_or(AssetEncoding::DEFAULT)
+}
+
+fn csp_hashes<'a>(
+  global_hashes: &'a [CspHash<'static>],
+  html_hashes: &'a phf::Map<&'static str, &'static [CspHash<'static>]>,
+  html_path: &AssetKey,
+) -> Box<dyn Iterator<Item = CspHash<'a>> + 'a> {
+  Box::new(
+    global_hashes
+      .iter()
+      .chain(
+        html_hashes
+          .get(html_path.as_ref())
+          .copied()
+          .into_iter()
+          .flatten(),
+      )
+      .copied(),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{replace_with_callback, AssetEncoding};
+
+  #[test]
+  fn string_replace_with_callback() {
+    let mut tauri_index = 0;
+    #[allow(clippy::single_element_loop)]
+    for (src, pattern, replacement, result) in [(
+      "tauri is awesome, tauri is amazing",
+      "tauri",
+      || {
+        tauri_index += 1;
+        tauri_index.to_string()
+      },
+      "1 is awesome, 2 is amazing",
+    )] {
+      assert_eq!(replace_with_callback(src, pattern, replacement), result);
+    }
+  }
+
+  #[test]
+  fn replace_csp_nonce() {
+    use super::{replace_csp_nonce, SCRIPT_NONCE_TOKEN, STYLE_NONCE_TOKEN};
+    use crate::config::{Csp, CspDirectiveSources};
+    use std::collections::HashMap;
+
+    let mut csp: HashMap<String, CspDirectiveSources> =
+      Csp::Policy("default-src 'self'; script-src https://example.com".into()).into();
+    let mut asset = format!(
+      "<script nonce=\"{SCRIPT_NONCE_TOKEN}\"></script><script nonce=\"{SCRIPT_NONCE_TOKEN}\"></script>"
+    );
+    replace_csp_nonce(
+      &mut asset,
+      SCRIPT_NONCE_TOKEN,
+      &mut csp,
+      "script-src",
+      vec!["'sha256-hash'".into()],
+    );
+
+    assert!(!asset.contains(SCRIPT_NONCE_TOKEN));
+    let nonces = asset
+      .split("nonce=\"")
+      .skip(1)
+      .map(|part| format!("'nonce-{}'", &part[..part.find('"').unwrap()]))
+      .collect::<Vec<_>>();
+    assert_eq!(nonces.len(), 2);
+    assert_ne!(nonces[0], nonces[1]);
+
+    let mut expected = vec!["https://example.com".to_string(), "'self'".into()];
+    expected.extend(nonces);
+    expected.push("'sha256-hash'".into());
+    assert_eq!(Vec::from(csp["script-src"].clone()), expected);
+
+    // directives are left untouched without nonces or hashes
+    replace_csp_nonce(
+      &mut asset,
+      STYLE_NONCE_TOKEN,
+      &mut csp,
+      "style-src",
+      Vec::new(),
+    );
+    assert!(!csp.contains_key("style-src"));
+  }
+
+  #[test]
+  #[cfg(feature = "asset-encryption")]
+  fn encrypt_asset_round_trip() {
+    use super::{decrypt_asset, encrypt_asset, ASSET_NONCE_SIZE};
+
+    let key = [7u8; 32];
+    let nonce = [3u8; ASSET_NONCE_SIZE];
+    let asset = b"<html><body>hello</body></html>";
+
+    let encrypted = encrypt_asset(&key, &nonce, asset).unwrap();
+    assert_eq!(&encrypted[..ASSET_NONCE_SIZE], &nonce);
+    assert_ne!(&encrypted[ASSET_NONCE_SIZE..], &asset[..]);
+    assert_eq!(decrypt_asset(&key, &encrypted).as_deref(), Some(&asset[..]));
+
+    // wrong key
+    assert!(decrypt_asset(&[8u8; 32], &encrypted).is_none());
+
+    // tampered ciphertext, tag and nonce
+    for index in [0, ASSET_NONCE_SIZE, encrypted.len() - 1] {
+      let mut tampered = encrypted.clone();
+      tampered[index] ^= 1;
+      assert!(decrypt_asset(&key, &tampered).is_none());
+    }
+
+    // truncated
+    assert!(decrypt_asset(&key, &encrypted[..ASSET_NONCE_SIZE - 1]).is_none());
+    assert!(decrypt_asset(&key, &encrypted[..encrypted.len() - 1]).is_none());
+  }
+
+  #[test]
+  fn parse_accept_encoding() {
+    assert_eq!(
+      AssetEncoding::parse_accept_encoding("gzip, deflate, br, zstd"),
+      vec![AssetEncoding::Brotli, AssetEncoding::Zstd]
+    );
+    assert_eq!(
+      AssetEncoding::parse_accept_encoding("br;q=0, zstd;q=0.5"),
+      vec![AssetEncoding::Zstd]
+    );
+    assert!(AssetEncoding::parse_accept_encoding("gzip").is_empty());
+  }
+
+  #[test]
+  #[cfg(feature = "compression")]
+  fn decode() {
+    let contents = b"<html><body>hello</body></html>".repeat(64);
+
+    let mut brotli = Vec::new();
+    brotli::BrotliCompress(&mut &contents[..], &mut brotli, &Default::default()).unwrap();
+
+    #[allow(unused_mut)]
+    let mut encoded_assets = vec![
+      (AssetEncoding::Identity, contents.clone()),
+      (AssetEncoding::Brotli, brotli),
+    ];
+    #[cfg(feature = "compression-zstd")]
+    encoded_assets.push((
+      AssetEncoding::Zstd,
+      zstd::stream::encode_all(&contents[..], 3).unwrap(),
+    ));
+    #[cfg(not(feature = "compression-zstd"))]
+    assert!(AssetEncoding::Zstd.decode(contents[..].into()).is_none());
+
+    for (encoding, encoded) in encoded_assets {
+      assert_eq!(
+        encoding.decode(encoded.as_slice().into()).as_deref(),
+        Some(&contents[..])
+      );
+      assert_eq!(
+        encoding.decode_prefix(&encoded, 6).as_deref(),
+        Some(&b"<html>"[..])
+      );
+    }
   }
 }
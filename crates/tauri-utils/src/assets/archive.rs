@@ -0,0 +1,355 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! An indexed archive of assets, shipped next to the application instead of being embedded in its binary.
+//!
+//! The archive starts with [`MAGIC`], followed by the little-endian `u64` length of a JSON index
+//! mapping each asset key to the offset and length of its contents in the data section that follows the index.
+
+use std::{
+  collections::BTreeMap,
+  fs::File,
+  io::{BufWriter, Write},
+  path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// The bytes every asset archive starts with.
+pub const MAGIC: &[u8; 8] = b"TAURIAR\x01";
+
+/// The extension of asset archive files.
+pub const EXTENSION: &str = "tauri-assets";
+
+/// The name of the asset archive file of the given package.
+pub fn file_name(package_name: &str) -> String {
+  format!("{package_name}.{EXTENSION}")
+}
+
+/// The path of the archive in the resource directory when it is bundled as a resource listed relative to `base`.
+///
+/// Both the build script and the CLI use this so the application looks up the archive where the bundler puts it.
+#[cfg(feature = "resources")]
+pub fn resource_path(archive: &Path, base: &Path) -> PathBuf {
+  crate::resources::resource_relpath(&relative_path(archive, base))
+}
+
+/// The path of `path` relative to the `base` directory, or `path` itself if they do not share a root.
+pub fn relative_path(path: &Path, base: &Path) -> PathBuf {
+  use std::path::Component;
+
+  // the file might not exist yet, so only its directory is resolved
+  let path = match (path.parent(), path.file_name()) {
+    (Some(parent), Some(file_name)) => dunce::canonicalize(parent)
+      .map(|parent| parent.join(file_name))
+      .unwrap_or_else(|_| path.to_path_buf()),
+    _ => path.to_path_buf(),
+  };
+  let base = dunce::canonicalize(base).unwrap_or_else(|_| base.to_path_buf());
+
+  let mut path_components = path.components().peekable();
+  let mut base_components = base.components().peekable();
+  if path_components.peek() != base_components.peek()
+    || !matches!(
+      path_components.peek(),
+      Some(Component::Prefix(_) | Component::RootDir)
+    )
+  {
+    return path;
+  }
+  while path_components.peek().is_some() && path_components.peek() == base_components.peek() {
+    path_components.next();
+    base_components.next();
+  }
+
+  base_components
+    .map(|_| Component::ParentDir)
+    .chain(path_components)
+    .collect()
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Index {
+  /// Identifies the build that wrote the archive, so a stale archive is not used by a newer binary.
+  id: String,
+  /// Maps an asset key to the offset and length of its contents in the data section.
+  entries: BTreeMap<String, (u64, u64)>,
+}
+
+/// Writes an asset archive.
+///
+/// The asset files are streamed to the archive, which is only written if the `id` changed.
+pub fn write(
+  path: &Path,
+  id: &str,
+  assets: impl IntoIterator<Item = (String, PathBuf)>,
+) -> std::io::Result<()> {
+  // the data section follows the key order
+  let files = assets.into_iter().collect::<BTreeMap<_, _>>();
+  let mut index = Index {
+    id: id.into(),
+    entries: Default::default(),
+  };
+  let mut offset = 0;
+  for (key, file) in &files {
+    let len = std::fs::metadata(file)?.len();
+    index.entries.insert(key.clone(), (offset, len));
+    offset += len;
+  }
+
+  // the id changes whenever an asset changes
+  if let Ok(existing) = read_index(path) {
+    if existing.id == index.id {
+      return Ok(());
+    }
+  }
+
+  let index = serde_json::to_vec(&index)?;
+
+  // write to a temporary file in the same directory and rename it so readers,
+  // including apps that mapped the previous archive, never see a partially written file
+  let file_name = path
+    .file_name()
+    .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid archive path"))?;
+  let mut tmp_file_name = std::ffi::OsString::from(".");
+  tmp_file_name.push(file_name);
+  tmp_file_name.push(format!(".{}.tmp", std::process::id()));
+  let tmp_path = path.with_file_name(tmp_file_name);
+
+  let result = (|| {
+    let mut out = BufWriter::new(File::create(&tmp_path)?);
+    out.write_all(MAGIC)?;
+    out.write_all(&(index.len() as u64).to_le_bytes())?;
+    out.write_all(&index)?;
+    for file in files.values() {
+      std::io::copy(&mut File::open(file)?, &mut out)?;
+    }
+    out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+    std::fs::rename(&tmp_path, path)
+  })();
+  if result.is_err() {
+    let _ = std::fs::remove_file(&tmp_path);
+  }
+  result
+}
+
+/// A memory mapped asset archive.
+#[cfg(feature = "resources")]
+pub struct Archive {
+  data: memmap2::Mmap,
+  data_offset: usize,
+  index: Index,
+}
+
+#[cfg(feature = "resources")]
+impl std::fmt::Debug for Archive {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("Archive")
+      .field("id", &self.index.id)
+      .field("entries", &self.index.entries.len())
+      .finish()
+  }
+}
+
+#[cfg(feature = "resources")]
+impl Archive {
+  /// Opens the archive at the given path, mapping it to memory.
+  ///
+  /// The contents are only read when an asset is requested.
+  pub fn open(path: &Path) -> std::io::Result<Self> {
+    let file = File::open(path)?;
+    // SAFETY: mapping a file is only sound if it is not modified while mapped, which we cannot guarantee.
+    // The archive is a bundle resource that the build replaces with a rename instead of writing it in place (see `write`),
+    // so a running app keeps the previous contents mapped; modifying the installed file in place
+    // may still change the mapped contents or crash the app, like modifying its executable would.
+    // Entry bounds are validated below so lookups stay within the mapping.
+    let data = unsafe { memmap2::Mmap::map(&file)? };
+    let (index, data_offset) = parse_index(&data)?;
+
+    let data_len = (data.len() - data_offset) as u64;
+    if index
+      .entries
+      .values()
+      .any(|(offset, len)| offset.checked_add(*len).map_or(true, |end| end > data_len))
+    {
+      return Err(invalid_data("asset archive entry out of bounds"));
+    }
+
+    Ok(Self {
+      data,
+      data_offset,
+      index,
+    })
+  }
+
+  /// The identifier of the build that wrote the archive.
+  pub fn id(&self) -> &str {
+    &self.index.id
+  }
+
+  /// Gets the contents of an asset.
+  pub fn get(&self, key: &str) -> Option<&[u8]> {
+    let (offset, len) = self.index.entries.get(key)?;
+    let start = self.data_offset + *offset as usize;
+    self.data.get(start..start + *len as usize)
+  }
+
+  /// Iterates on the assets, ordered by key.
+  pub fn iter(&self) -> impl Iterator<Item = (&str, &[u8])> + '_ {
+    self
+      .index
+      .entries
+      .keys()
+      .filter_map(|key| self.get(key).map(|contents| (key.as_str(), contents)))
+  }
+}
+
+/// Parses the archive index, returning it with the offset of the data section.
+fn parse_index(data: &[u8]) -> std::io::Result<(Index, usize)> {
+  let header_len = MAGIC.len() + 8;
+  if data.len() < header_len || &data[..MAGIC.len()] != MAGIC {
+    return Err(invalid_data("not an asset archive"));
+  }
+  let mut index_len = [0; 8];
+  index_len.copy_from_slice(&data[MAGIC.len()..header_len]);
+  let index_end = usize::try_from(u64::from_le_bytes(index_len))
+    .ok()
+    .and_then(|len| header_len.checked_add(len))
+    .filter(|end| *end <= data.len())
+    .ok_or_else(|| invalid_data("asset archive index out of bounds"))?;
+  let index = serde_json::from_slice(&data[header_len..index_end])?;
+  Ok((index, index_end))
+}
+
+/// Reads the index of an existing archive without mapping it.
+fn read_index(path: &Path) -> std::io::Result<Index> {
+  use std::io::Read;
+
+  let mut file = File::open(path)?;
+  let mut header = [0; MAGIC.len() + 8];
+  file.read_exact(&mut header)?;
+  let mut index_len = [0; 8];
+  index_len.copy_from_slice(&header[MAGIC.len()..]);
+  let mut data = header.to_vec();
+  file
+    .take(u64::from_le_bytes(index_len))
+    .read_to_end(&mut data)?;
+  parse_index(&data).map(|(index, _)| index)
+}
+
+fn invalid_data(message: &str) -> std::io::Error {
+  std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(all(test, feature = "resources"))]
+mod tests {
+  use std::path::{Path, PathBuf};
+
+  use super::{relative_path, write, Archive};
+
+  #[test]
+  fn write_and_read() {
+    let dir = std::env::temp_dir().join(format!("tauri-asset-archive-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let index = dir.join("index.html");
+    let script = dir.join("main.js");
+    std::fs::write(&index, "<html></html>").unwrap();
+    std::fs::write(&script, "console.log(1)").unwrap();
+
+    let path = dir.join("app.tauri-assets");
+    write(
+      &path,
+      "first",
+      [
+        ("/main.js".to_string(), script.clone()),
+        ("/index.html".to_string(), index.clone()),
+      ],
+    )
+    .unwrap();
+
+    let archive = Archive::open(&path).unwrap();
+    assert_eq!(archive.id(), "first");
+    assert_eq!(archive.get("/index.html"), Some(&b"<html></html>"[..]));
+    assert_eq!(archive.get("/main.js"), Some(&b"console.log(1)"[..]));
+    assert_eq!(archive.get("/missing.js"), None);
+    assert_eq!(
+      archive.iter().map(|(key, _)| key).collect::<Vec<_>>(),
+      vec!["/index.html", "/main.js"]
+    );
+    drop(archive);
+
+    // the archive is rewritten when the id changes
+    std::fs::write(&script, "console.log(2)").unwrap();
+    write(&path, "second", [("/main.js".to_string(), script)]).unwrap();
+    let archive = Archive::open(&path).unwrap();
+    assert_eq!(archive.id(), "second");
+    assert_eq!(archive.get("/main.js"), Some(&b"console.log(2)"[..]));
+    assert_eq!(archive.get("/index.html"), None);
+    drop(archive);
+
+    std::fs::write(&path, b"not an archive").unwrap();
+    assert!(Archive::open(&path).is_err());
+
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn write_is_atomic() {
+    let dir =
+      std::env::temp_dir().join(format!("tauri-asset-archive-atomic-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("main.js");
+    std::fs::write(&script, "console.log(1)").unwrap();
+
+    let path = dir.join("app.tauri-assets");
+    write(&path, "first", [("/main.js".to_string(), script.clone())]).unwrap();
+
+    // a failed write leaves the previous archive untouched
+    assert!(write(
+      &path,
+      "second",
+      [("/missing.js".to_string(), dir.join("missing.js"))]
+    )
+    .is_err());
+    assert_eq!(Archive::open(&path).unwrap().id(), "first");
+
+    // an archive that is already mapped keeps its contents when it is replaced
+    #[cfg(unix)]
+    {
+      let mapped = Archive::open(&path).unwrap();
+      std::fs::write(&script, "console.log(2)").unwrap();
+      write(&path, "second", [("/main.js".to_string(), script)]).unwrap();
+      assert_eq!(mapped.get("/main.js"), Some(&b"console.log(1)"[..]));
+      assert_eq!(Archive::open(&path).unwrap().id(), "second");
+    }
+
+    // no temporary file is left behind
+    let files = std::fs::read_dir(&dir)
+      .unwrap()
+      .map(|entry| entry.unwrap().file_name())
+      .collect::<Vec<_>>();
+    assert_eq!(files.len(), 2);
+
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn relative_paths() {
+    assert_eq!(
+      relative_path(
+        Path::new("/project/target/release/app.tauri-assets"),
+        Path::new("/project/src-tauri")
+      ),
+      PathBuf::from("../target/release/app.tauri-assets")
+    );
+    assert_eq!(
+      relative_path(
+        Path::new("/project/src-tauri/target/app.tauri-assets"),
+        Path::new("/project/src-tauri")
+      ),
+      PathBuf::from("target/app.tauri-assets")
+    );
+  }
+}
@@ -61,6 +61,9 @@ fn add_description(schema: Schema, description: impl Into<String>) -> Schema {
 /// Items to help with parsing content into a [`Config`].
 pub mod parse;
 
+/// The channel used by the CLI to apply configuration changes to an application running in development.
+pub mod dev;
+
 use crate::{acl::capability::Capability, TitleBarStyle, WindowEffect, WindowEffectState};
 
 pub use self::parse::parse;
@@ -2595,6 +2598,21 @@ pub struct SecurityConfig {
   /// Freeze the `Object.prototype` when using the custom protocol.
   #[serde(default, alias = "freeze-prototype")]
   pub freeze_prototype: bool,
+  /// Adds [`integrity`](https://developer.mozilla.org/en-US/docs/Web/Security/Subresource_Integrity) attributes
+  /// to the `<script>` and `<link>` tags of the embedded HTML files that load other embedded assets.
+  #[serde(default, alias = "subresource-integrity")]
+  pub subresource_integrity: bool,
+  /// Verifies the hash of each embedded asset, computed at build time, before serving it.
+  ///
+  /// Assets that fail the check are not served to the webview.
+  #[serde(default, alias = "verify-asset-integrity")]
+  pub verify_asset_integrity: bool,
+  /// Encrypts the embedded assets with AES-256-GCM using a key generated at build time.
+  ///
+  /// Requires the `asset-encryption` Cargo feature of `tauri`. The key is embedded in the binary,
+  /// so this prevents trivially extracting the assets but does not protect them from a determined attacker.
+  #[serde(default, alias = "encrypt-assets")]
+  pub encrypt_assets: bool,
   /// Disables the Tauri-injected CSP sources.
   ///
   /// At compile time, Tauri parses all the frontend assets and changes the Content-Security-Policy
@@ -3065,6 +3083,37 @@ impl From<String> for RunnerConfig {
   }
 }
 
+/// The algorithm used to compress an embedded asset.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum AssetCompressionAlgorithm {
+  /// Brotli compression, supported by all webviews.
+  Brotli,
+  /// Zstandard compression, faster to decompress than brotli.
+  Zstd,
+  /// Store the asset uncompressed, recommended for formats that are already compressed like images or videos.
+  None,
+}
+
+/// Compression settings for the embedded assets matching a glob pattern.
+///
+/// Requires the `compression` Cargo feature of `tauri`, which is enabled by default.
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AssetCompressionRule {
+  /// Glob pattern matched against the asset path relative to the frontend directory, e.g. `**/*.wasm`.
+  pub glob: String,
+  /// The compression algorithm.
+  pub algorithm: AssetCompressionAlgorithm,
+  /// The compression level, from 0 to 11 for brotli and from 1 to 22 for zstd.
+  ///
+  /// Defaults to a fast level for debug builds and a high level for release builds.
+  #[serde(default)]
+  pub level: Option<i32>,
+}
+
 /// The Build configuration object.
 ///
 /// See more: <https://v2.tauri.app/reference/config/#buildconfig>
@@ -3128,6 +3177,21 @@ pub struct BuildConfig {
   /// Additional paths to watch for changes when running `tauri dev`.
   #[serde(alias = "additional-watch-directories", default)]
   pub additional_watch_folders: Vec<PathBuf>,
+  /// Packs the [`frontendDist`](BuildConfig::frontend_dist) files into an indexed archive
+  /// shipped as a bundle resource instead of embedding them in the application binary.
+  ///
+  /// The archive is read lazily at runtime. The asset hashes and CSP hashes are still computed at build time.
+  /// Recommended for large frontends, which make the build slow and the executable huge.
+  ///
+  /// Only supported on desktop, the assets are always embedded on mobile. The archive is added to the bundle resources by the Tauri CLI.
+  #[serde(alias = "asset-archive", default)]
+  pub asset_archive: bool,
+  /// How the embedded frontend assets are compressed.
+  ///
+  /// The first rule matching an asset applies, assets not matching any rule are compressed with brotli.
+  /// Compressed assets are served as is to webviews that accept their encoding.
+  #[serde(alias = "asset-compression", default)]
+  pub asset_compression: Vec<AssetCompressionRule>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -3798,6 +3862,8 @@ mod build {
       let features = quote!(None);
       let remove_unused_commands = quote!(false);
       let additional_watch_folders = quote!(Vec::new());
+      let asset_archive = self.asset_archive;
+      let asset_compression = quote!(Vec::new());
 
       literal_struct!(
         tokens,
@@ -3810,7 +3876,9 @@ mod build {
         before_bundle_command,
         features,
         remove_unused_commands,
-        additional_watch_folders
+        additional_watch_folders,
+        asset_archive,
+        asset_compression
       );
     }
   }
@@ -3949,6 +4017,9 @@ mod build {
       let csp = opt_lit(self.csp.as_ref());
       let dev_csp = opt_lit(self.dev_csp.as_ref());
       let freeze_prototype = self.freeze_prototype;
+      let subresource_integrity = self.subresource_integrity;
+      let verify_asset_integrity = self.verify_asset_integrity;
+      let encrypt_assets = self.encrypt_assets;
       let dangerous_disable_asset_csp_modification = &self.dangerous_disable_asset_csp_modification;
       let asset_protocol = &self.asset_protocol;
       let pattern = &self.pattern;
@@ -3961,6 +4032,9 @@ mod build {
         csp,
         dev_csp,
         freeze_prototype,
+        subresource_integrity,
+        verify_asset_integrity,
+        encrypt_assets,
         dangerous_disable_asset_csp_modification,
         asset_protocol,
         pattern,
@@ -4111,6 +4185,9 @@ mod test {
         csp: None,
         dev_csp: None,
         freeze_prototype: false,
+        subresource_integrity: false,
+        verify_asset_integrity: false,
+        encrypt_assets: false,
         dangerous_disable_asset_csp_modification: DisabledCspModificationKind::Flag(false),
         asset_protocol: AssetProtocolConfig::default(),
         pattern: Default::default(),
@@ -4134,6 +4211,8 @@ mod test {
       features: None,
       remove_unused_commands: false,
       additional_watch_folders: Vec::new(),
+      asset_archive: false,
+      asset_compression: Vec::new(),
     };
 
     // create a bundle config
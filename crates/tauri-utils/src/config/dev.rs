@@ -0,0 +1,111 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! When running `tauri dev`, the CLI listens on a local TCP socket and sets [`CHANNEL_ENV_VAR`] to its address.
+//! The application connects to it and receives a [`ConfigUpdate`] per line, serialized as JSON,
+//! whenever a configuration change can be applied without rebuilding the application.
+//! It answers each update with a [`ConfigUpdateReply`] line once it is applied, and the CLI
+//! rebuilds the application if it failed or no reply came.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{Config, WindowConfig};
+use crate::acl::capability::Capability;
+
+/// The environment variable holding the address of the configuration channel, `{host}:{port}`.
+pub const CHANNEL_ENV_VAR: &str = "TAURI_DEV_CONFIG_CHANNEL";
+
+/// The configuration fields that are applied at runtime, as JSON pointers.
+const RUNTIME_FIELDS: &[&str] = &["/app/windows", "/app/security/capabilities"];
+
+/// A configuration change applied to the running application.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigUpdate {
+  /// The new window configurations.
+  ///
+  /// Windows whose configuration changed are recreated, new windows are created and removed windows are closed.
+  pub windows: Vec<WindowConfig>,
+  /// The new capabilities of the application, replacing the ones it is currently using.
+  pub capabilities: BTreeMap<String, Capability>,
+}
+
+/// The reply of the application to a [`ConfigUpdate`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum ConfigUpdateReply {
+  /// The update was applied.
+  Applied,
+  /// The update could not be applied, the application must be rebuilt.
+  Failed {
+    /// Why the update failed.
+    error: String,
+  },
+}
+
+/// Whether the only differences between two configurations can be applied at runtime with a [`ConfigUpdate`].
+///
+/// Any other change is used by the codegen or the Cargo manifest, so the application must be rebuilt.
+pub fn is_runtime_change(old: &Config, new: &Config) -> bool {
+  fn without_runtime_fields(config: &Config) -> Option<Value> {
+    let mut value = serde_json::to_value(config).ok()?;
+    for field in RUNTIME_FIELDS {
+      if let Some(field) = value.pointer_mut(field) {
+        field.take();
+      }
+    }
+    Some(value)
+  }
+
+  match (without_runtime_fields(old), without_runtime_fields(new)) {
+    (Some(old), Some(new)) => old == new,
+    _ => false,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::config::Csp;
+
+  #[test]
+  fn runtime_change() {
+    let old = Config::default();
+
+    let mut windows = old.clone();
+    windows.app.windows.push(WindowConfig {
+      width: 1024.,
+      ..Default::default()
+    });
+    assert!(is_runtime_change(&old, &windows));
+
+    let mut csp = old.clone();
+    csp.app.security.csp = Some(Csp::Policy("default-src 'self'".into()));
+    assert!(!is_runtime_change(&old, &csp));
+
+    let mut identifier = old.clone();
+    identifier.identifier = "com.tauri.other".into();
+    assert!(!is_runtime_change(&old, &identifier));
+  }
+
+  #[test]
+  fn reply_format() {
+    assert_eq!(
+      serde_json::to_string(&ConfigUpdateReply::Applied).unwrap(),
+      r#"{"status":"applied"}"#
+    );
+    let failed = ConfigUpdateReply::Failed {
+      error: "unknown permission".into(),
+    };
+    let line = serde_json::to_string(&failed).unwrap();
+    assert_eq!(line, r#"{"status":"failed","error":"unknown permission"}"#);
+    assert_eq!(
+      serde_json::from_str::<ConfigUpdateReply>(&line).unwrap(),
+      failed
+    );
+  }
+}
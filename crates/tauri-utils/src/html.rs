@@ -157,6 +157,46 @@ pub fn inject_nonce_token(
   }
 }
 
+/// Injects [`integrity`](https://developer.mozilla.org/en-US/docs/Web/Security/Subresource_Integrity) attributes
+/// to the scripts and stylesheets loaded by the HTML.
+///
+/// The `integrity` closure receives the `src` or `href` attribute value and returns the integrity metadata
+/// (e.g. `sha384-<base64 digest>`) of the referenced file, or `None` if it is not a local asset.
+/// Elements that already have an `integrity` attribute are left untouched.
+pub fn inject_subresource_integrity<F: Fn(&str) -> Option<String>>(
+  document: &NodeRef,
+  integrity: F,
+) {
+  let Ok(elements) = document.select("script[src], link[href]") else {
+    return;
+  };
+
+  for target in elements {
+    let element = target.as_node().as_element().unwrap();
+    let mut attrs = element.attributes.borrow_mut();
+    if attrs.get("integrity").is_some() {
+      continue;
+    }
+
+    let url = if &*element.name.local == "link" {
+      let rel = attrs.get("rel").unwrap_or_default().to_ascii_lowercase();
+      if !rel
+        .split_ascii_whitespace()
+        .any(|rel| matches!(rel, "stylesheet" | "modulepreload" | "preload"))
+      {
+        continue;
+      }
+      attrs.get("href")
+    } else {
+      attrs.get("src")
+    };
+
+    if let Some(value) = url.and_then(&integrity) {
+      attrs.insert("integrity", value);
+    }
+  }
+}
+
 /// Injects a content security policy to the HTML.
 pub fn inject_csp(document: &NodeRef, csp: &str) {
   with_head(document, |head| {
@@ -313,6 +353,26 @@ pub fn normalize_script_for_csp(input: &[u8]) -> Vec<u8> {
   output
 }
 
+/// The CSP sources allowing the inline scripts of the document, as `'sha256-<digest>'` hashes.
+pub fn inline_script_hashes(document: &NodeRef) -> Vec<String> {
+  use base64::Engine;
+  use sha2::{Digest, Sha256};
+
+  let Ok(inline_script_elements) = document.select("script:not(:empty)") else {
+    return Vec::new();
+  };
+  inline_script_elements
+    .map(|inline_script_el| {
+      let script = inline_script_el.as_node().text_contents();
+      let hash = Sha256::digest(normalize_script_for_csp(script.as_bytes()));
+      format!(
+        "'sha256-{}'",
+        base64::engine::general_purpose::STANDARD.encode(hash)
+      )
+    })
+    .collect()
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -335,6 +395,36 @@ mod tests {
     }
   }
 
+  #[test]
+  fn subresource_integrity() {
+    let document = super::parse(
+      r#"<html><head><script type="module" src="/main.js"></script><link rel="stylesheet" href="style.css"><link rel="icon" href="/icon.png"><script src="https://example.com/a.js"></script></head></html>"#
+        .into(),
+    );
+    super::inject_subresource_integrity(&document, |url| {
+      (!url.starts_with("https:")).then(|| format!("sha384-{url}"))
+    });
+    let integrity = |selector: &str| {
+      document
+        .select_first(selector)
+        .unwrap()
+        .attributes
+        .borrow()
+        .get("integrity")
+        .map(ToString::to_string)
+    };
+    assert_eq!(
+      integrity("script[type]").as_deref(),
+      Some("sha384-/main.js")
+    );
+    assert_eq!(
+      integrity("link[rel=stylesheet]").as_deref(),
+      Some("sha384-style.css")
+    );
+    assert_eq!(integrity("link[rel=icon]"), None);
+    assert_eq!(integrity("script:not([type])"), None);
+  }
+
   #[test]
   fn normalize_script_for_csp() {
     let js = "// Copyright 2019-2024 Tauri Programme within The Commons Conservancy\r// SPDX-License-Identifier: Apache-2.0\n// SPDX-License-Identifier: MIT\r\n\r\nwindow.__TAURI_ISOLATION_HOOK__ = (payload, options) => {\r\n  return payload\r\n}\r\n";
@@ -344,4 +434,18 @@ mod tests {
       expected.as_bytes()
     )
   }
+
+  #[test]
+  fn inline_script_hashes() {
+    let document = super::parse(
+      "<html><head><script>console.log('a')\r\n</script><script src=\"/main.js\"></script></head><body><script>console.log('b')</script></body></html>".into(),
+    );
+    assert_eq!(
+      super::inline_script_hashes(&document),
+      [
+        "'sha256-Q8z/CIwpqYG2Wh0qOtcWxBm2iKsGoJV1LqjyPO422kE='",
+        "'sha256-SKtp6/lc4n452m32L3ESYsz0EwNbtOtF2R6q7OhgPi4='",
+      ]
+    );
+  }
 }
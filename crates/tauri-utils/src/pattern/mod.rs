@@ -5,3 +5,35 @@
 /// Handling the Tauri "Isolation" Pattern.
 #[cfg(feature = "isolation")]
 pub mod isolation;
+
+/// The domain the isolation iframe is loaded from.
+pub const ISOLATION_IFRAME_SRC_DOMAIN: &str = "localhost";
+
+/// The name of the file the isolation schema of a development build of `package_name` is written to.
+///
+/// The build script writes it to the target directory so the dev server can allow the isolation iframe in its CSP.
+pub fn dev_isolation_schema_file_name(package_name: &str) -> String {
+  format!("{package_name}.isolation-schema")
+}
+
+/// The URL of the isolation iframe served on the `schema` custom protocol.
+///
+/// `http_custom_protocol` is set on platforms serving custom protocols as `http(s)://<scheme>.localhost` (Windows and Android),
+/// where `https` selects the scheme.
+pub fn isolation_iframe_src(schema: &str, http_custom_protocol: bool, https: bool) -> String {
+  if http_custom_protocol {
+    let scheme = if https { "https" } else { "http" };
+    format!("{scheme}://{schema}.{ISOLATION_IFRAME_SRC_DOMAIN}/")
+  } else {
+    format!("{schema}://{ISOLATION_IFRAME_SRC_DOMAIN}/")
+  }
+}
+
+/// The URLs of the isolation iframe served on the `schema` custom protocol on every platform.
+pub fn isolation_iframe_sources(schema: &str) -> [String; 3] {
+  [
+    isolation_iframe_src(schema, false, false),
+    isolation_iframe_src(schema, true, false),
+    isolation_iframe_src(schema, true, true),
+  ]
+}
@@ -178,7 +178,16 @@ const PLUGINS: &[(&str, &[(&str, bool)])] = &[
       ("size", true),
     ],
   ),
-  ("core:resources", &[("close", true)]),
+  ("core:resources", &[("close", true), ("list", false)]),
+  (
+    "core:state",
+    &[
+      ("get", true),
+      ("subscribe", true),
+      ("unsubscribe", true),
+      ("update", false),
+    ],
+  ),
   (
     "core:menu",
     &[
@@ -10,14 +10,14 @@ use crate::{
   },
   manager::{webview::UriSchemeProtocol, AppManager, Asset},
   plugin::{Plugin, PluginStore},
-  resources::ResourceTable,
+  resources::{ResourceOwner, ResourceTable, ResourceUsage},
   runtime::{
     window::{WebviewEvent as RuntimeWebviewEvent, WindowEvent as RuntimeWindowEvent},
     ExitRequestedEventAction, RunEvent as RuntimeRunEvent,
   },
   sealed::{ManagerBase, RuntimeOrDispatch},
   utils::{config::Config, Env},
-  webview::PageLoadPayload,
+  webview::{CspViolationReport, PageLoadPayload},
   Context, DeviceEventFilter, Emitter, EventLoopMessage, EventName, Listener, Manager, Monitor,
   Runtime, Scopes, StateManager, Theme, Webview, WebviewWindowBuilder, Window,
 };
@@ -67,6 +67,8 @@ pub type SetupHook<R> =
   Box<dyn FnOnce(&mut App<R>) -> std::result::Result<(), Box<dyn std::error::Error>> + Send>;
 /// A closure that is run every time a page starts or finishes loading.
 pub type OnPageLoad<R> = dyn Fn(&Webview<R>, &PageLoadPayload<'_>) + Send + Sync + 'static;
+/// A closure that is run when the webview reports a Content Security Policy violation.
+pub type OnCspViolation<R> = dyn Fn(&Webview<R>, &CspViolationReport) + Send + Sync + 'static;
 pub type ChannelInterceptor<R> =
   Box<dyn Fn(&Webview<R>, CallbackFn, usize, &InvokeResponseBody) -> bool + Send + Sync + 'static>;
 
@@ -322,6 +324,7 @@ impl<R: Runtime> AssetResolver<R> {
             bytes,
             mime_type,
             csp_header: None,
+            content_encoding: None,
           }
         });
       }
@@ -1021,6 +1024,33 @@ macro_rules! shared_app_impl {
         Ok(())
       }
 
+      /// Lists the live resources of the application, its windows and webviews.
+      ///
+      /// Resources created by the frontend are stored in the webview resources table and closed along with the webview,
+      /// so a growing number of live resources in a long lived webview usually means the frontend never closes them.
+      ///
+      /// The same information is available from the frontend with the `plugin:resources|list` command,
+      /// which requires the `core:resources:allow-list` permission.
+      pub fn resource_usage(&self) -> Vec<ResourceUsage> {
+        let mut usage = vec![ResourceUsage::new(
+          ResourceOwner::App,
+          &self.manager.resources_table(),
+        )];
+        for (label, window) in self.manager.windows() {
+          usage.push(ResourceUsage::new(
+            ResourceOwner::Window(label),
+            &window.resources_table(),
+          ));
+        }
+        for (label, webview) in self.manager.webviews() {
+          usage.push(ResourceUsage::new(
+            ResourceOwner::Webview(label),
+            &webview.resources_table(),
+          ));
+        }
+        usage
+      }
+
       /// Runs necessary cleanup tasks before exiting the process.
       /// **You should always exit the tauri app immediately after this function returns and not use any tauri-related APIs.**
       pub fn cleanup_before_exit(&self) {
@@ -1127,6 +1157,7 @@ impl<R: Runtime> App<R> {
     self.handle.plugin(crate::app::plugin::init())?;
     self.handle.plugin(crate::resources::plugin::init())?;
     self.handle.plugin(crate::image::plugin::init())?;
+    self.handle.plugin(crate::state::plugin::init())?;
     #[cfg(desktop)]
     self.handle.plugin(crate::menu::plugin::init())?;
     #[cfg(all(desktop, feature = "tray-icon"))]
@@ -1390,6 +1421,9 @@ pub struct Builder<R: Runtime> {
   /// Page load hook.
   on_page_load: Option<Arc<OnPageLoad<R>>>,
 
+  /// CSP violation hook.
+  on_csp_violation: Option<Arc<OnCspViolation<R>>>,
+
   /// All passed plugins
   plugins: PluginStore<R>,
 
@@ -1476,6 +1510,7 @@ impl<R: Runtime> Builder<R> {
       .into_string(),
       channel_interceptor: None,
       on_page_load: None,
+      on_csp_violation: None,
       plugins: PluginStore::default(),
       uri_scheme_protocols: Default::default(),
       state: StateManager::new(),
@@ -1656,6 +1691,34 @@ tauri::Builder::default()
     self
   }
 
+  /// Defines the Content Security Policy violation hook.
+  ///
+  /// When set, a `report-uri` directive pointing to a built-in endpoint on the `ipc` protocol
+  /// is added to the CSP of the app assets (unless `report-uri` or `report-to` is already configured)
+  /// and each violation reported by the webview is parsed and forwarded to this hook.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// tauri::Builder::default()
+  ///   .on_csp_violation(|webview, report| {
+  ///     log::warn!(
+  ///       "[{}] CSP blocked {:?} ({:?})",
+  ///       webview.label(),
+  ///       report.blocked_url,
+  ///       report.effective_directive
+  ///     );
+  ///   });
+  /// ```
+  #[must_use]
+  pub fn on_csp_violation<F>(mut self, on_csp_violation: F) -> Self
+  where
+    F: Fn(&Webview<R>, &CspViolationReport) + Send + Sync + 'static,
+  {
+    self.on_csp_violation.replace(Arc::new(on_csp_violation));
+    self
+  }
+
   /// Adds a Tauri application plugin.
   ///
   /// A plugin is created using the [`crate::plugin::Builder`] struct.Check its documentation for more information.
@@ -2104,6 +2167,7 @@ tauri::Builder::default()
       self.plugins,
       self.invoke_handler,
       self.on_page_load,
+      self.on_csp_violation,
       self.uri_scheme_protocols,
       self.state,
       #[cfg(desktop)]
@@ -2376,6 +2440,9 @@ fn setup<R: Runtime>(app: &mut App<R>) -> crate::Result<()> {
 
   app.manager.assets.setup(app);
 
+  #[cfg(all(dev, desktop, any(feature = "dynamic-acl", debug_assertions)))]
+  crate::dev_config::listen(app.handle());
+
   if let Some(setup) = app.setup.take() {
     (setup)(app).map_err(|e| crate::Error::Setup(e.into()))?;
   }
@@ -0,0 +1,142 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Receives the configuration changes `tauri dev` applies without rebuilding the app.
+//!
+//! See [`tauri_utils::config::dev`] for the protocol.
+
+use std::{
+  io::{BufRead, BufReader, Write},
+  net::TcpStream,
+  time::{Duration, Instant},
+};
+
+use tauri_utils::config::{
+  dev::{ConfigUpdate, ConfigUpdateReply, CHANNEL_ENV_VAR},
+  WindowConfig,
+};
+
+use crate::{AppHandle, Manager, Runtime, WebviewWindowBuilder};
+
+/// How long to wait for a window to be destroyed before recreating it.
+const DESTROY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Connects to the configuration channel of the CLI, if the app was started by `tauri dev`.
+pub(crate) fn listen<R: Runtime>(app: &AppHandle<R>) {
+  let Ok(address) = std::env::var(CHANNEL_ENV_VAR) else {
+    return;
+  };
+  let app = app.clone();
+
+  std::thread::spawn(move || {
+    let stream = match TcpStream::connect(&address) {
+      Ok(stream) => stream,
+      Err(e) => {
+        log::warn!("failed to connect to the tauri dev configuration channel at {address}: {e}");
+        return;
+      }
+    };
+
+    let mut writer = match stream.try_clone() {
+      Ok(writer) => writer,
+      Err(e) => {
+        log::warn!("failed to use the tauri dev configuration channel: {e}");
+        return;
+      }
+    };
+
+    let mut windows = app
+      .config()
+      .app
+      .windows
+      .iter()
+      .filter(|w| w.create)
+      .cloned()
+      .collect::<Vec<_>>();
+
+    for line in BufReader::new(stream).lines() {
+      let Ok(line) = line else {
+        break;
+      };
+      let reply = match serde_json::from_str::<ConfigUpdate>(&line) {
+        Ok(update) => match apply(&app, &windows, update) {
+          Ok(applied) => {
+            windows = applied;
+            ConfigUpdateReply::Applied
+          }
+          Err(error) => ConfigUpdateReply::Failed { error },
+        },
+        Err(e) => ConfigUpdateReply::Failed {
+          error: format!("invalid configuration update: {e}"),
+        },
+      };
+      if let ConfigUpdateReply::Failed { error } = &reply {
+        log::error!("failed to apply the tauri dev configuration update: {error}");
+      }
+
+      let mut line = serde_json::to_vec(&reply).expect("failed to serialize config update reply");
+      line.push(b'\n');
+      if writer
+        .write_all(&line)
+        .and_then(|()| writer.flush())
+        .is_err()
+      {
+        break;
+      }
+    }
+  });
+}
+
+/// Applies an update, returning the window configurations now in use.
+///
+/// Nothing is changed if the capabilities can not be reloaded, e.g. when they reference a permission
+/// that only exists after a rebuild.
+fn apply<R: Runtime>(
+  app: &AppHandle<R>,
+  current: &[WindowConfig],
+  update: ConfigUpdate,
+) -> Result<Vec<WindowConfig>, String> {
+  app
+    .manager
+    .runtime_authority
+    .lock()
+    .unwrap()
+    .reload_capabilities(update.capabilities)
+    .map_err(|e| format!("failed to reload capabilities: {e}"))?;
+
+  let windows = update
+    .windows
+    .into_iter()
+    .filter(|w| w.create)
+    .collect::<Vec<_>>();
+
+  // windows that were removed or changed are destroyed, changed windows are recreated below
+  let destroyed = current
+    .iter()
+    .filter(|old| !windows.contains(old))
+    .filter_map(|old| app.get_window(&old.label))
+    .filter(|window| window.destroy().is_ok())
+    .map(|window| window.label().to_string())
+    .collect::<Vec<_>>();
+
+  let deadline = Instant::now() + DESTROY_TIMEOUT;
+  while destroyed
+    .iter()
+    .any(|label| app.get_window(label).is_some())
+  {
+    if Instant::now() > deadline {
+      log::warn!("timed out waiting for windows to be destroyed");
+      break;
+    }
+    std::thread::sleep(Duration::from_millis(10));
+  }
+
+  for config in windows.iter().filter(|new| !current.contains(new)) {
+    WebviewWindowBuilder::from_config(app, config)
+      .and_then(|b| b.build())
+      .map_err(|e| format!("failed to create window `{}`: {e}", config.label))?;
+  }
+
+  Ok(windows)
+}
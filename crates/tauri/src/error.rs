@@ -46,6 +46,9 @@ pub enum Error {
   /// Embedded asset not found.
   #[error("asset not found: {0}")]
   AssetNotFound(String),
+  /// Embedded asset failed the integrity check.
+  #[error("asset `{0}` failed the integrity check")]
+  AssetIntegrity(String),
   /// Failed to serialize/deserialize.
   #[error("JSON error: {0}")]
   Json(#[from] serde_json::Error),
@@ -160,9 +163,36 @@ pub enum Error {
   /// Bad `__TAURI_INVOKE_KEY__` value received in ipc message.
   #[error("bad __TAURI_INVOKE_KEY__ value received in ipc message")]
   InvokeKey,
+  /// Illegal persisted scope identifier.
+  #[error("only alphanumeric, '-', '_' permitted for scope identifiers: {0:?}")]
+  IllegalScopeIdentifier(String),
   /// Illegal event name.
   #[error("only alphanumeric, '-', '/', ':', '_' permitted for event names: {0:?}")]
   IllegalEventName(String),
+  /// A synced state with the given name is already managed.
+  #[error("a synced state named `{0}` is already managed")]
+  SyncedStateAlreadyManaged(String),
+  /// Synced state not found or not managed with the requested type.
+  #[error("synced state `{0}` not found")]
+  SyncedStateNotFound(String),
+  /// The webview is not allowed to access the synced state.
+  #[error("synced state `{0}` not allowed on this webview, check your capabilities")]
+  SyncedStateNotAllowed(String),
+  /// The synced state was modified since the version the update is based on.
+  #[error(
+    "synced state `{name}` is at version {current} but the update expected version {expected}"
+  )]
+  SyncedStateVersionMismatch {
+    /// The synced state name.
+    name: String,
+    /// The version the update is based on.
+    expected: u64,
+    /// The current version.
+    current: u64,
+  },
+  /// Failed to apply a JSON Patch to a synced state.
+  #[error("invalid patch for synced state `{0}`: {1}")]
+  InvalidSyncedStatePatch(String, String),
   /// tokio oneshot channel failed to receive message
   #[error(transparent)]
   TokioOneshotRecv(#[from] tokio::sync::oneshot::error::RecvError),
@@ -139,6 +139,16 @@ impl EmitArgs {
       payload,
     })
   }
+
+  #[cfg(any(test, feature = "test"))]
+  pub(crate) fn event_name(&self) -> &str {
+    self.event.as_str()
+  }
+
+  #[cfg(any(test, feature = "test"))]
+  pub(crate) fn payload(&self) -> &str {
+    &self.payload
+  }
 }
 
 /// An event that was emitted.
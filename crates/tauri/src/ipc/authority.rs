@@ -14,6 +14,7 @@ use tauri_utils::acl::capability::CapabilityFile;
 use tauri_utils::acl::manifest::Manifest;
 use tauri_utils::acl::{
   resolved::{Resolved, ResolvedCommand, ResolvedScope, ScopeKey},
+  scope::{RangeScope, StringScope, UrlScope},
   ExecutionContext, Value, APP_ACL_KEY,
 };
 
@@ -225,6 +226,30 @@ impl RuntimeAuthority {
     Ok(())
   }
 
+  /// Replaces the resolved capabilities with the given ones, used to apply capability changes in `tauri dev`.
+  ///
+  /// Capabilities added with [`Self::add_capability`] are dropped.
+  #[cfg(all(dev, desktop, any(feature = "dynamic-acl", debug_assertions)))]
+  pub(crate) fn reload_capabilities(
+    &mut self,
+    capabilities: BTreeMap<String, tauri_utils::acl::capability::Capability>,
+  ) -> Result<(), tauri_utils::acl::Error> {
+    let resolved = Resolved::resolve(
+      &self.acl,
+      capabilities,
+      tauri_utils::platform::Target::current(),
+    )?;
+    let acl = std::mem::take(&mut self.acl);
+    *self = Self::new(
+      acl,
+      Resolved {
+        has_app_acl: self.has_app_acl,
+        ..resolved
+      },
+    );
+    Ok(())
+  }
+
   #[cfg(debug_assertions)]
   pub(crate) fn resolve_access_message(
     &self,
@@ -735,6 +760,30 @@ pub trait ScopeObjectMatch: ScopeObject {
   fn matches(&self, input: &Self::Input) -> bool;
 }
 
+impl ScopeObjectMatch for UrlScope {
+  type Input = Url;
+
+  fn matches(&self, input: &Url) -> bool {
+    UrlScope::matches(self, input)
+  }
+}
+
+impl ScopeObjectMatch for RangeScope {
+  type Input = f64;
+
+  fn matches(&self, input: &f64) -> bool {
+    RangeScope::matches(self, *input)
+  }
+}
+
+impl ScopeObjectMatch for StringScope {
+  type Input = str;
+
+  fn matches(&self, input: &str) -> bool {
+    StringScope::matches(self, input)
+  }
+}
+
 impl ScopeManager {
   pub(crate) fn get_global_scope_typed<R: Runtime, T: ScopeObject>(
     &self,
@@ -816,6 +865,8 @@ impl ScopeManager {
 
 #[cfg(test)]
 mod tests {
+  use std::sync::Arc;
+
   use glob::Pattern;
   use tauri_utils::acl::{
     resolved::{Resolved, ResolvedCommand},
@@ -824,7 +875,31 @@ mod tests {
 
   use crate::ipc::Origin;
 
-  use super::RuntimeAuthority;
+  use super::{CommandScope, RuntimeAuthority};
+
+  #[test]
+  fn core_scope_types_match() {
+    let scope = CommandScope::<tauri_utils::acl::scope::StringScope> {
+      allow: vec![Arc::new(
+        serde_json::from_str(r#"{ "glob": "TAURI_*" }"#).unwrap(),
+      )],
+      deny: vec![Arc::new(
+        serde_json::from_str(r#"{ "values": ["TAURI_SECRET"] }"#).unwrap(),
+      )],
+    };
+    assert!(scope.matches("TAURI_ENV"));
+    assert!(!scope.matches("TAURI_SECRET"));
+    assert!(!scope.matches("PATH"));
+
+    let scope = CommandScope::<tauri_utils::acl::scope::RangeScope> {
+      allow: vec![Arc::new(
+        serde_json::from_str(r#"{ "min": 0, "max": 10 }"#).unwrap(),
+      )],
+      deny: Vec::new(),
+    };
+    assert!(scope.matches(&5.));
+    assert!(!scope.matches(&11.));
+  }
 
   #[test]
   fn window_glob_pattern_matches() {
@@ -1190,4 +1265,70 @@ mod tests {
       "myplugin.my-command-webview-window not allowed on window \"main-*\", webview \"webview-*\", URL: http://localhost:123/\n\nallowed on: [windows: \"main-*\", webviews: \"webview-*\", URL: local], [windows: \"main-*\", webviews: \"webview-*\", URL: http://localhost:8080]\n\nreferenced by: capability: maincap, permission: allow-command || capability: maincap, permission: allow-command"
     );
   }
+
+  #[cfg(all(dev, desktop, any(feature = "dynamic-acl", debug_assertions)))]
+  #[test]
+  fn reload_capabilities() {
+    use std::{collections::BTreeMap, str::FromStr};
+    use tauri_utils::acl::{
+      capability::{Capability, CapabilityFile},
+      manifest::{Manifest, PermissionFile},
+      APP_ACL_KEY,
+    };
+
+    fn capabilities(capability: &str) -> BTreeMap<String, Capability> {
+      match CapabilityFile::from_str(capability).unwrap() {
+        CapabilityFile::Capability(capability) => {
+          [(capability.identifier.clone(), capability)].into()
+        }
+        _ => unreachable!(),
+      }
+    }
+
+    let permissions: PermissionFile = serde_json::from_str(
+      r#"{ "permission": [{ "identifier": "allow-ping", "commands": { "allow": ["ping"] } }] }"#,
+    )
+    .unwrap();
+    let acl: BTreeMap<_, _> = [(
+      APP_ACL_KEY.to_string(),
+      Manifest::new(vec![permissions], None),
+    )]
+    .into();
+    let resolved = Resolved::resolve(
+      &acl,
+      capabilities(
+        r#"{ "identifier": "main", "windows": ["main"], "permissions": ["allow-ping"] }"#,
+      ),
+      tauri_utils::platform::Target::current(),
+    )
+    .unwrap();
+    let mut authority = RuntimeAuthority::new(acl, resolved);
+    assert!(authority.has_app_manifest());
+    assert!(authority
+      .resolve_access("ping", "main", "main", &Origin::Local)
+      .is_some());
+
+    authority
+      .reload_capabilities(capabilities(
+        r#"{ "identifier": "other", "windows": ["other"], "permissions": ["allow-ping"] }"#,
+      ))
+      .unwrap();
+    assert!(authority.has_app_manifest());
+    assert!(authority
+      .resolve_access("ping", "main", "main", &Origin::Local)
+      .is_none());
+    assert!(authority
+      .resolve_access("ping", "other", "other", &Origin::Local)
+      .is_some());
+
+    // the capabilities in use are kept when the new ones can not be resolved
+    assert!(authority
+      .reload_capabilities(capabilities(
+        r#"{ "identifier": "main", "windows": ["main"], "permissions": ["allow-missing"] }"#,
+      ))
+      .is_err());
+    assert!(authority
+      .resolve_access("ping", "other", "other", &Origin::Local)
+      .is_some());
+  }
 }
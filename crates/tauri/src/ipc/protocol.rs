@@ -7,7 +7,10 @@ use std::{borrow::Cow, sync::Arc};
 use crate::{
   ipc::InvokeResponseBody,
   manager::AppManager,
-  webview::{InvokeRequest, UriSchemeProtocolHandler},
+  webview::{
+    csp::{CspViolationReport, CSP_REPORT_PATH},
+    InvokeRequest, UriSchemeProtocolHandler,
+  },
   Runtime,
 };
 use http::{
@@ -57,6 +60,13 @@ pub fn get<R: Runtime>(manager: Arc<AppManager<R>>) -> UriSchemeProtocolHandler
     };
 
     match *request.method() {
+      Method::POST if is_csp_report_request(&request) => {
+        handle_csp_report(&manager, label, request.body());
+        let mut r = http::Response::new(Vec::new().into());
+        *r.status_mut() = StatusCode::NO_CONTENT;
+        respond(r);
+      }
+
       Method::POST => {
         if let Some(webview) = manager.get_webview(label) {
           match parse_invoke_request(&manager, request) {
@@ -182,6 +192,32 @@ pub fn get<R: Runtime>(manager: Arc<AppManager<R>>) -> UriSchemeProtocolHandler
   })
 }
 
+fn is_csp_report_request(request: &http::Request<Vec<u8>>) -> bool {
+  request
+    .uri()
+    .path()
+    .strip_prefix('/')
+    .is_some_and(|path| path == CSP_REPORT_PATH)
+}
+
+fn handle_csp_report<R: Runtime>(manager: &AppManager<R>, label: &str, body: &[u8]) {
+  let Some(on_csp_violation) = &manager.webview.on_csp_violation else {
+    return;
+  };
+  let Some(webview) = manager.get_webview(label) else {
+    return;
+  };
+
+  match CspViolationReport::parse(body) {
+    Ok(reports) => {
+      for report in reports {
+        on_csp_violation(&webview, &report);
+      }
+    }
+    Err(e) => log::error!("failed to parse CSP violation report: {e}"),
+  }
+}
+
 fn handle_ipc_message<R: Runtime>(request: Request<String>, manager: &AppManager<R>, label: &str) {
   if let Some(webview) = manager.get_webview(label) {
     #[cfg(feature = "tracing")]
@@ -571,6 +607,7 @@ mod tests {
       PluginStore::default(),
       Box::new(|_| false),
       None,
+      None,
       Default::default(),
       StateManager::new(),
       Default::default(),
@@ -687,6 +724,7 @@ mod tests {
       PluginStore::default(),
       Box::new(|_| false),
       None,
+      None,
       Default::default(),
       StateManager::new(),
       Default::default(),
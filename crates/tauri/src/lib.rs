@@ -30,6 +30,8 @@
 //! - **macos-private-api**: Enables features only available in **macOS**'s private APIs, currently the `transparent` window functionality and the `fullScreenEnabled` preference setting to `true`. Enabled by default if the `tauri > macosPrivateApi` config flag is set to `true` on the `tauri.conf.json` file.
 //! - **webview-data-url**: Enables usage of data URLs on the webview.
 //! - **compression** *(enabled by default): Enables asset compression. You should only disable this if you want faster compile times in release builds - it produces larger binaries.
+//! - **compression-zstd**: Enables the `zstd` algorithm of [`build > assetCompression`](https://v2.tauri.app/reference/config/#assetcompression).
+//! - **asset-encryption**: Encrypts the embedded assets with AES-256-GCM using a key generated at build time, so they cannot be trivially extracted from the binary. The assets are decrypted when they are requested, at a small runtime cost.
 //! - **config-json5**: Adds support to JSON5 format for `tauri.conf.json`.
 //! - **config-toml**: Adds support to TOML format for the configuration `Tauri.toml`.
 //! - **image-ico**: Adds support to parse `.ico` image, see [`Image`].
@@ -69,7 +71,10 @@ pub use error::{Error, Result};
 use ipc::RuntimeAuthority;
 #[cfg(feature = "dynamic-acl")]
 use ipc::RuntimeCapability;
-pub use resources::{Resource, ResourceId, ResourceTable};
+pub use resources::{
+  Resource, ResourceId, ResourceInfo, ResourceOwner, ResourceTable, ResourceTableStats,
+  ResourceUsage,
+};
 #[cfg(target_os = "ios")]
 #[doc(hidden)]
 pub use swift_rs;
@@ -83,6 +88,8 @@ pub use url::Url;
 
 pub(crate) mod app;
 pub mod async_runtime;
+#[cfg(all(dev, desktop, any(feature = "dynamic-acl", debug_assertions)))]
+mod dev_config;
 mod error;
 mod event;
 pub mod ipc;
@@ -199,7 +206,7 @@ use std::{
   fmt::{self, Debug},
   sync::MutexGuard,
 };
-use utils::assets::{AssetKey, CspHash, EmbeddedAssets};
+use utils::assets::{ArchiveAssets, AssetEncoding, AssetKey, CspHash, EmbeddedAssets};
 
 #[cfg(feature = "wry")]
 #[cfg_attr(docsrs, doc(cfg(feature = "wry")))]
@@ -228,7 +235,7 @@ pub use {
     window::{CursorIcon, DragDropEvent, WindowSizeConstraints},
     DeviceEventFilter, UserAttentionType,
   },
-  self::state::{State, StateManager},
+  self::state::{ScopedState, State, StateManager, SyncedState, SyncedStateChange, WebviewState},
   self::utils::{
     config::{Config, WebviewUrl},
     Env, PackageInfo, Theme,
@@ -325,11 +332,27 @@ pub trait Assets<R: Runtime>: Send + Sync + 'static {
   /// Get the content of the passed [`AssetKey`].
   fn get(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>>;
 
+  /// Get the content of the passed [`AssetKey`] in the encoding it is stored in.
+  ///
+  /// Compressed assets are served as is to webviews that accept their encoding.
+  /// The default implementation returns the decoded content of [`Self::get`].
+  fn get_encoded(&self, key: &AssetKey) -> Option<(Cow<'_, [u8]>, AssetEncoding)> {
+    self.get(key).map(|asset| (asset, AssetEncoding::Identity))
+  }
+
   /// Iterator for the assets.
   fn iter(&self) -> Box<tauri_utils::assets::AssetsIter<'_>>;
 
   /// Gets the hashes for the CSP tag of the HTML on the given path.
   fn csp_hashes(&self, html_path: &AssetKey) -> Box<dyn Iterator<Item = CspHash<'_>> + '_>;
+
+  /// Gets the expected SHA-256 hex digest of the passed [`AssetKey`].
+  ///
+  /// Used to verify assets before serving them when `app > security > verifyAssetIntegrity` is enabled.
+  fn integrity(&self, key: &AssetKey) -> Option<&str> {
+    let _ = key;
+    None
+  }
 }
 
 impl<R: Runtime> Assets<R> for EmbeddedAssets {
@@ -337,6 +360,10 @@ impl<R: Runtime> Assets<R> for EmbeddedAssets {
     EmbeddedAssets::get(self, key)
   }
 
+  fn get_encoded(&self, key: &AssetKey) -> Option<(Cow<'_, [u8]>, AssetEncoding)> {
+    EmbeddedAssets::get_encoded(self, key)
+  }
+
   fn iter(&self) -> Box<AssetsIter<'_>> {
     EmbeddedAssets::iter(self)
   }
@@ -344,6 +371,38 @@ impl<R: Runtime> Assets<R> for EmbeddedAssets {
   fn csp_hashes(&self, html_path: &AssetKey) -> Box<dyn Iterator<Item = CspHash<'_>> + '_> {
     EmbeddedAssets::csp_hashes(self, html_path)
   }
+
+  fn integrity(&self, key: &AssetKey) -> Option<&str> {
+    EmbeddedAssets::integrity(self, key)
+  }
+}
+
+impl<R: Runtime> Assets<R> for ArchiveAssets {
+  fn setup(&self, app: &App<R>) {
+    if let Ok(resource_dir) = app.path().resource_dir() {
+      self.set_resource_dir(resource_dir);
+    }
+  }
+
+  fn get(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>> {
+    ArchiveAssets::get(self, key)
+  }
+
+  fn get_encoded(&self, key: &AssetKey) -> Option<(Cow<'_, [u8]>, AssetEncoding)> {
+    ArchiveAssets::get_encoded(self, key)
+  }
+
+  fn iter(&self) -> Box<AssetsIter<'_>> {
+    ArchiveAssets::iter(self)
+  }
+
+  fn csp_hashes(&self, html_path: &AssetKey) -> Box<dyn Iterator<Item = CspHash<'_>> + '_> {
+    ArchiveAssets::csp_hashes(self, html_path)
+  }
+
+  fn integrity(&self, key: &AssetKey) -> Option<&str> {
+    ArchiveAssets::integrity(self, key)
+  }
 }
 
 /// User supplied data required inside of a Tauri application.
@@ -754,6 +813,56 @@ pub trait Manager<R: Runtime>: sealed::ManagerBase<R> {
     self.manager().state.try_get()
   }
 
+  /// Manages a [`SyncedState`] with the given name, which is synchronized to the webviews that subscribe to it.
+  ///
+  /// Returns an error if a synced state with the same name is already managed.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use serde::{Deserialize, Serialize};
+  /// use tauri::Manager;
+  ///
+  /// #[derive(Serialize, Deserialize)]
+  /// struct Settings {
+  ///   theme: String,
+  /// }
+  ///
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     let settings = app.manage_synced("settings", Settings { theme: "dark".into() })?;
+  ///     // the subscribed webviews receive a patch replacing `/theme`
+  ///     settings.update(|settings| settings.theme = "light".into())?;
+  ///     Ok(())
+  ///   });
+  /// ```
+  ///
+  /// On the frontend:
+  ///
+  /// ```javascript
+  /// import { SyncedState } from '@tauri-apps/api/state'
+  /// const settings = await SyncedState.subscribe('settings', (value) => console.log(value.theme))
+  /// ```
+  fn manage_synced<T>(&self, name: impl Into<String>, value: T) -> Result<SyncedState<T>>
+  where
+    T: serde::Serialize + serde::de::DeserializeOwned + Send + Sync + 'static,
+  {
+    let state = SyncedState::new(name.into(), value)?;
+    self.manage(state::SyncedStates::default());
+    self.state::<state::SyncedStates>().insert(state.clone())?;
+    Ok(state)
+  }
+
+  /// Retrieves the [`SyncedState`] with the given name.
+  ///
+  /// Returns `None` if it has not been [managed](Self::manage_synced) or if its value is not of type `T`.
+  fn synced_state<T>(&self, name: &str) -> Option<SyncedState<T>>
+  where
+    T: Send + Sync + 'static,
+  {
+    self.try_state::<state::SyncedStates>()?.get_typed(name)
+  }
+
   /// Get a reference to the resources table of this manager.
   fn resources_table(&self) -> MutexGuard<'_, ResourceTable>;
 
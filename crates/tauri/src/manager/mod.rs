@@ -14,19 +14,22 @@ use url::Url;
 
 use tauri_macros::default_runtime;
 use tauri_utils::{
-  assets::{AssetKey, CspHash, SCRIPT_NONCE_TOKEN, STYLE_NONCE_TOKEN},
+  assets::{
+    replace_csp_nonce, AssetEncoding, AssetKey, CspHash, SCRIPT_NONCE_TOKEN, STYLE_NONCE_TOKEN,
+  },
   config::{Csp, CspDirectiveSources},
 };
 
 use crate::{
   app::{
     AppHandle, ChannelInterceptor, GlobalWebviewEventListener, GlobalWindowEventListener,
-    OnPageLoad,
+    OnCspViolation, OnPageLoad,
   },
   event::{EmitArgs, Event, EventId, EventTarget, Listeners},
   ipc::{Invoke, InvokeHandler, RuntimeAuthority},
   plugin::PluginStore,
   resources::ResourceTable,
+  state::SyncedStates,
   utils::{config::Config, PackageInfo},
   Assets, Context, DebugAppIcon, EventName, Pattern, Runtime, StateManager, Webview, Window,
 };
@@ -103,52 +106,47 @@ pub(crate) fn set_csp<R: Runtime>(
   csp
 }
 
-// inspired by <https://github.com/rust-lang/rust/blob/1be5c8f90912c446ecbdc405cbc4a89f9acd20fd/library/alloc/src/str.rs#L260-L297>
-fn replace_with_callback<F: FnMut() -> String>(
-  original: &str,
-  pattern: &str,
-  mut replacement: F,
-) -> String {
-  let mut result = String::new();
-  let mut last_end = 0;
-  for (start, part) in original.match_indices(pattern) {
-    result.push_str(unsafe { original.get_unchecked(last_end..start) });
-    result.push_str(&replacement());
-    last_end = start + part.len();
+/// Checks the asset against the SHA-256 digest computed at build time.
+///
+/// Fails closed: assets without a known digest are rejected too.
+fn verify_asset_integrity<R: Runtime>(
+  assets: &dyn Assets<R>,
+  asset_path: &AssetKey,
+  asset: &[u8],
+) -> crate::Result<()> {
+  use sha2::{Digest, Sha256};
+
+  let digest = Sha256::digest(asset)
+    .iter()
+    .map(|b| format!("{b:02x}"))
+    .collect::<String>();
+  let matches = assets
+    .integrity(asset_path)
+    .is_some_and(|expected| expected.eq_ignore_ascii_case(&digest));
+
+  if matches {
+    Ok(())
+  } else {
+    log::error!("asset `{}` failed the integrity check", asset_path.as_ref());
+    Err(crate::Error::AssetIntegrity(asset_path.as_ref().into()))
   }
-  result.push_str(unsafe { original.get_unchecked(last_end..original.len()) });
-  result
 }
 
-fn replace_csp_nonce(
-  asset: &mut String,
-  token: &str,
-  csp: &mut HashMap<String, CspDirectiveSources>,
-  directive: &str,
-  hashes: Vec<String>,
-) {
-  let mut nonces = Vec::new();
-  *asset = replace_with_callback(asset, token, || {
-    let nonce = getrandom::u64().expect("failed to get random bytes");
-    nonces.push(nonce);
-    nonce.to_string()
-  });
-
-  if !(nonces.is_empty() && hashes.is_empty()) {
-    let nonce_sources = nonces
-      .into_iter()
-      .map(|n| format!("'nonce-{n}'"))
-      .collect::<Vec<String>>();
-    let sources = csp.entry(directive.into()).or_default();
-    let self_source = "'self'".to_string();
-    if !sources.contains(&self_source) {
-      sources.push(self_source);
-    }
-    sources.extend(nonce_sources);
-    sources.extend(hashes);
+/// Closes all resources of the given table, calling [`Resource::close`](crate::Resource::close) outside of the table lock.
+fn close_resources(table: &Mutex<ResourceTable>) {
+  let resources = table
+    .lock()
+    .expect("poisoned resources table")
+    .drain()
+    .collect::<Vec<_>>();
+  for resource in resources {
+    resource.close();
   }
 }
 
+/// How many decoded bytes are used to infer the MIME type of a compressed asset.
+const MIME_TYPE_SNIFF_LEN: usize = 8192;
+
 /// A resolved asset.
 #[non_exhaustive]
 pub struct Asset {
@@ -158,6 +156,8 @@ pub struct Asset {
   pub mime_type: String,
   /// The `Content-Security-Policy` header value.
   pub csp_header: Option<String>,
+  /// The `Content-Encoding` header value, if the bytes are compressed.
+  pub content_encoding: Option<&'static str>,
 }
 
 impl Asset {
@@ -175,6 +175,11 @@ impl Asset {
   pub fn csp_header(&self) -> Option<&str> {
     self.csp_header.as_deref()
   }
+
+  /// The `Content-Encoding` header value, if the bytes are compressed.
+  pub fn content_encoding(&self) -> Option<&str> {
+    self.content_encoding
+  }
 }
 
 #[default_runtime(crate::Wry, wry)]
@@ -251,6 +256,7 @@ impl<R: Runtime> AppManager<R> {
     plugins: PluginStore<R>,
     invoke_handler: Box<InvokeHandler<R>>,
     on_page_load: Option<Arc<OnPageLoad<R>>>,
+    on_csp_violation: Option<Arc<OnCspViolation<R>>>,
     uri_scheme_protocols: HashMap<String, Arc<webview::UriSchemeProtocol<R>>>,
     state: StateManager,
     #[cfg(desktop)] menu_event_listener: Vec<crate::app::GlobalMenuEventListener<AppHandle<R>>>,
@@ -284,6 +290,7 @@ impl<R: Runtime> AppManager<R> {
         webviews: Mutex::default(),
         invoke_handler,
         on_page_load,
+        on_csp_violation,
         uri_scheme_protocols: Mutex::new(uri_scheme_protocols),
         event_listeners: Arc::new(webview_event_listeners),
         invoke_initialization_script,
@@ -373,9 +380,22 @@ impl<R: Runtime> AppManager<R> {
   }
 
   pub fn get_asset(
+    &self,
+    path: String,
+    use_https_schema: bool,
+  ) -> Result<Asset, Box<dyn std::error::Error>> {
+    self.get_asset_with_encodings(path, use_https_schema, &[])
+  }
+
+  /// Same as [`Self::get_asset`], but assets stored in one of the `accepted_encodings` are not decoded,
+  /// setting [`Asset::content_encoding`] instead.
+  ///
+  /// HTML files are always decoded since the CSP is injected into them.
+  pub fn get_asset_with_encodings(
     &self,
     mut path: String,
     _use_https_schema: bool,
+    accepted_encodings: &[AssetEncoding],
   ) -> Result<Asset, Box<dyn std::error::Error>> {
     let assets = &self.assets;
     if path.ends_with('/') {
@@ -392,43 +412,61 @@ impl<R: Runtime> AppManager<R> {
       path.strip_prefix('/').unwrap_or(path.as_str()).to_string()
     };
 
+    let verify_integrity = self.config.app.security.verify_asset_integrity;
+    let get = |key: &AssetKey| {
+      // the integrity is verified on the decoded contents
+      if accepted_encodings.is_empty() || verify_integrity || key.as_ref().ends_with(".html") {
+        return assets.get(key).map(|asset| (asset, None));
+      }
+      let (asset, encoding) = assets.get_encoded(key)?;
+      if encoding.content_encoding().is_some() && accepted_encodings.contains(&encoding) {
+        Some((asset, Some(encoding)))
+      } else {
+        encoding.decode(asset).map(|asset| (asset, None))
+      }
+    };
+
     let mut asset_path = AssetKey::from(path.as_str());
 
-    let asset_response = assets
-      .get(&asset_path)
+    let asset_response = get(&asset_path)
       .or_else(|| {
         log::debug!("Asset `{path}` not found; fallback to {path}.html");
         let fallback = format!("{path}.html").into();
-        let asset = assets.get(&fallback);
+        let asset = get(&fallback);
         asset_path = fallback;
         asset
       })
       .or_else(|| {
         log::debug!("Asset `{path}` not found; fallback to {path}/index.html",);
         let fallback = format!("{path}/index.html").into();
-        let asset = assets.get(&fallback);
+        let asset = get(&fallback);
         asset_path = fallback;
         asset
       })
       .or_else(|| {
         log::debug!("Asset `{path}` not found; fallback to index.html");
         let fallback = AssetKey::from("index.html");
-        let asset = assets.get(&fallback);
+        let asset = get(&fallback);
         asset_path = fallback;
         asset
       })
       .ok_or_else(|| crate::Error::AssetNotFound(path.clone()))
-      .map(Cow::into_owned);
+      .map(|(asset, encoding)| (asset.into_owned(), encoding))
+      .and_then(|(asset, encoding)| {
+        if verify_integrity {
+          verify_asset_integrity(&**assets, &asset_path, &asset)?;
+        }
+        Ok((asset, encoding))
+      });
 
     let mut csp_header = None;
     let is_html = asset_path.as_ref().ends_with(".html");
 
     match asset_response {
-      Ok(asset) => {
+      Ok((asset, encoding)) => {
         let final_data = if is_html {
           let mut asset = String::from_utf8_lossy(&asset).into_owned();
           if let Some(csp) = self.csp() {
-            #[allow(unused_mut)]
             let mut csp_map = set_csp(&mut asset, &self.assets, &asset_path, self, csp);
             #[cfg(feature = "isolation")]
             if let Pattern::Isolation { schema, .. } = &*self.pattern {
@@ -441,6 +479,16 @@ impl<R: Runtime> AppManager<R> {
               ));
             }
 
+            if self.webview.on_csp_violation.is_some()
+              && !csp_map.contains_key("report-uri")
+              && !csp_map.contains_key("report-to")
+            {
+              csp_map.insert(
+                "report-uri".into(),
+                CspDirectiveSources::List(vec![crate::webview::csp::report_uri(_use_https_schema)]),
+              );
+            }
+
             csp_header.replace(Csp::DirectiveMap(csp_map).to_string());
           }
 
@@ -448,11 +496,21 @@ impl<R: Runtime> AppManager<R> {
         } else {
           asset
         };
-        let mime_type = tauri_utils::mime_type::MimeType::parse(&final_data, &path);
+        let mime_type = match encoding {
+          // the MIME type is inferred from the first bytes of the asset
+          Some(encoding) => tauri_utils::mime_type::MimeType::parse(
+            &encoding
+              .decode_prefix(&final_data, MIME_TYPE_SNIFF_LEN)
+              .unwrap_or_default(),
+            &path,
+          ),
+          None => tauri_utils::mime_type::MimeType::parse(&final_data, &path),
+        };
         Ok(Asset {
           bytes: final_data,
           mime_type,
           csp_header,
+          content_encoding: encoding.and_then(AssetEncoding::content_encoding),
         })
       }
       Err(e) => {
@@ -541,6 +599,9 @@ impl<R: Runtime> AppManager<R> {
       EmitPayload::Str(payload) => EmitArgs::new_str(event, payload)?,
     };
 
+    #[cfg(any(test, feature = "test"))]
+    self.record_emit(&emit_args, || vec![EventTarget::Any]);
+
     let listeners = self.listeners();
 
     listeners.emit_js(self.webview.webviews_lock().values(), &emit_args)?;
@@ -549,6 +610,43 @@ impl<R: Runtime> AppManager<R> {
     Ok(())
   }
 
+  /// Records an emitted event on the timeline of the [`test`](crate::test) harness, if it is running.
+  #[cfg(any(test, feature = "test"))]
+  fn record_emit(&self, emit_args: &EmitArgs, targets: impl FnOnce() -> Vec<EventTarget>) {
+    if let Some(timeline) = self.state.try_get::<crate::test::Timeline>() {
+      for target in targets() {
+        timeline.record_event(emit_args.event_name(), emit_args.payload(), target);
+      }
+    }
+  }
+
+  /// Lists the existing targets accepted by an emit filter.
+  #[cfg(any(test, feature = "test"))]
+  fn emit_targets<F: Fn(&EventTarget) -> bool>(&self, filter: &F) -> Vec<EventTarget> {
+    let mut targets = vec![EventTarget::App];
+    targets.extend(
+      self
+        .window
+        .windows_lock()
+        .keys()
+        .map(|label| EventTarget::Window {
+          label: label.clone(),
+        }),
+    );
+    for (label, webview) in self.webview.webviews_lock().iter() {
+      targets.push(EventTarget::Webview {
+        label: label.clone(),
+      });
+      if webview.window_label() == *label {
+        targets.push(EventTarget::WebviewWindow {
+          label: label.clone(),
+        });
+      }
+    }
+    targets.retain(|target| filter(target));
+    targets
+  }
+
   #[cfg_attr(
     feature = "tracing",
     tracing::instrument("app::emit::filter", skip(self, payload, filter))
@@ -570,6 +668,9 @@ impl<R: Runtime> AppManager<R> {
       EmitPayload::Str(payload) => EmitArgs::new_str(event, payload)?,
     };
 
+    #[cfg(any(test, feature = "test"))]
+    self.record_emit(&emit_args, || self.emit_targets(&filter));
+
     let listeners = self.listeners();
 
     listeners.emit_js_filter(
@@ -653,13 +754,29 @@ impl<R: Runtime> AppManager<R> {
     if let Some(window) = window {
       for webview in window.webviews() {
         self.webview.webviews_lock().remove(webview.label());
+        self.on_webview_removed(&webview);
       }
+      close_resources(&window.resources_table);
+      window.scoped_state.clear();
     }
   }
 
   #[cfg(desktop)]
   pub(crate) fn on_webview_close(&self, label: &str) {
-    self.webview.webviews_lock().remove(label);
+    let webview = self.webview.webviews_lock().remove(label);
+    if let Some(webview) = webview {
+      self.on_webview_removed(&webview);
+    }
+  }
+
+  fn on_webview_removed(&self, webview: &Webview<R>) {
+    if let Some(synced_states) = self.state.try_get::<SyncedStates>() {
+      synced_states.unsubscribe_webview(webview.label());
+    }
+    // resources created by the frontend are owned by the webview
+    // and would leak if it never closed them
+    close_resources(&webview.resources_table);
+    webview.scoped_state.clear();
   }
 
   pub fn windows(&self) -> HashMap<String, Window<R>> {
@@ -702,28 +819,6 @@ impl<R: Runtime> AppManager<R> {
   }
 }
 
-#[cfg(test)]
-mod tests {
-  use super::replace_with_callback;
-
-  #[test]
-  fn string_replace_with_callback() {
-    let mut tauri_index = 0;
-    #[allow(clippy::single_element_loop)]
-    for (src, pattern, replacement, result) in [(
-      "tauri is awesome, tauri is amazing",
-      "tauri",
-      || {
-        tauri_index += 1;
-        tauri_index.to_string()
-      },
-      "1 is awesome, 2 is amazing",
-    )] {
-      assert_eq!(replace_with_callback(src, pattern, replacement), result);
-    }
-  }
-}
-
 #[cfg(test)]
 mod test {
   use std::{
@@ -731,11 +826,16 @@ mod test {
     time::Duration,
   };
 
+  use std::borrow::Cow;
+
+  use tauri_utils::assets::{AssetEncoding, AssetKey, CspHash};
+
   use crate::{
     event::EventTarget,
     generate_context,
     plugin::PluginStore,
-    test::{mock_app, MockRuntime},
+    sealed::ManagerBase,
+    test::{mock_app, mock_builder, mock_context, MockRuntime},
     webview::WebviewBuilder,
     window::WindowBuilder,
     App, Emitter, Listener, Manager, StateManager, Webview, WebviewWindow, WebviewWindowBuilder,
@@ -762,6 +862,7 @@ mod test {
       PluginStore::default(),
       Box::new(|_| false),
       None,
+      None,
       Default::default(),
       StateManager::new(),
       Default::default(),
@@ -802,6 +903,153 @@ mod test {
     );
   }
 
+  struct EncodedAssets;
+
+  impl<R: crate::Runtime> crate::Assets<R> for EncodedAssets {
+    fn get(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>> {
+      (key.as_ref() == "/index.html").then_some(Cow::Borrowed(&b"<html></html>"[..]))
+    }
+
+    fn get_encoded(&self, key: &AssetKey) -> Option<(Cow<'_, [u8]>, AssetEncoding)> {
+      match key.as_ref() {
+        "/main.js" => Some((Cow::Borrowed(&b"compressed"[..]), AssetEncoding::Zstd)),
+        _ => crate::Assets::<R>::get(self, key).map(|asset| (asset, AssetEncoding::Identity)),
+      }
+    }
+
+    fn iter(&self) -> Box<tauri_utils::assets::AssetsIter<'_>> {
+      Box::new(std::iter::empty())
+    }
+
+    fn csp_hashes(&self, _html_path: &AssetKey) -> Box<dyn Iterator<Item = CspHash<'_>> + '_> {
+      Box::new(std::iter::empty())
+    }
+  }
+
+  #[test]
+  fn get_encoded_asset() {
+    let app = mock_builder().build(mock_context(EncodedAssets)).unwrap();
+
+    let asset = app
+      .manager()
+      .get_asset_with_encodings("/main.js".into(), false, &[AssetEncoding::Zstd])
+      .unwrap();
+    assert_eq!(asset.bytes(), b"compressed");
+    assert_eq!(asset.content_encoding(), Some("zstd"));
+    assert_eq!(asset.mime_type(), "text/javascript");
+
+    // HTML files are always decoded to inject the CSP
+    let asset = app
+      .manager()
+      .get_asset_with_encodings("/index.html".into(), false, &[AssetEncoding::Zstd])
+      .unwrap();
+    assert_eq!(asset.content_encoding(), None);
+  }
+
+  struct IntegrityAssets;
+
+  impl<R: crate::Runtime> crate::Assets<R> for IntegrityAssets {
+    fn get(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>> {
+      match key.as_ref() {
+        "/index.html" => Some(Cow::Borrowed(&b"<html></html>"[..])),
+        "/main.js" => Some(Cow::Borrowed(&b"tampered"[..])),
+        _ => None,
+      }
+    }
+
+    fn iter(&self) -> Box<tauri_utils::assets::AssetsIter<'_>> {
+      Box::new(std::iter::empty())
+    }
+
+    fn csp_hashes(&self, _html_path: &AssetKey) -> Box<dyn Iterator<Item = CspHash<'_>> + '_> {
+      Box::new(std::iter::empty())
+    }
+
+    fn integrity(&self, key: &AssetKey) -> Option<&str> {
+      match key.as_ref() {
+        // SHA-256 of `<html></html>`
+        "/index.html" => Some("b633a587c652d02386c4f16f8c6f6aab7352d97f16367c3c40576214372dd628"),
+        // uppercase SHA-256 of `original`
+        "/main.js" => Some("0682C5F2076F099C34CFDD15A9E063849ED437A49677E6FCC5B4198C76575BE5"),
+        _ => None,
+      }
+    }
+  }
+
+  #[test]
+  fn verify_asset_integrity() {
+    let assets: &dyn crate::Assets<MockRuntime> = &IntegrityAssets;
+    let html = assets.get(&"/index.html".into()).unwrap();
+    assert!(super::verify_asset_integrity(assets, &"/index.html".into(), &html).is_ok());
+
+    // the content does not match the digest computed at build time
+    let js = assets.get(&"/main.js".into()).unwrap();
+    assert!(matches!(
+      super::verify_asset_integrity(assets, &"/main.js".into(), &js),
+      Err(crate::Error::AssetIntegrity(_))
+    ));
+    // assets without a digest are rejected
+    assert!(super::verify_asset_integrity(assets, &"/other.js".into(), b"").is_err());
+    // the digest comparison is case insensitive
+    assert!(super::verify_asset_integrity(assets, &"/main.js".into(), b"original").is_ok());
+  }
+
+  #[test]
+  fn get_asset_verifies_integrity() {
+    let mut context = mock_context(IntegrityAssets);
+    context.config_mut().app.security.verify_asset_integrity = true;
+    let app = mock_builder().build(context).unwrap();
+
+    assert!(app.manager().get_asset("/index.html".into(), false).is_ok());
+    assert!(app.manager().get_asset("/main.js".into(), false).is_err());
+  }
+
+  fn csp_context(csp: &str) -> crate::Context<MockRuntime> {
+    let mut context = mock_context(EncodedAssets);
+    context.config_mut().app.security.csp = Some(tauri_utils::config::Csp::Policy(csp.into()));
+    context
+  }
+
+  fn index_csp(app: &App<MockRuntime>) -> String {
+    app
+      .manager()
+      .get_asset("/index.html".into(), false)
+      .unwrap()
+      .csp_header()
+      .unwrap()
+      .to_string()
+  }
+
+  #[test]
+  fn csp_report_uri() {
+    let report_uri = crate::webview::csp::report_uri(false);
+
+    let app = mock_builder()
+      .build(csp_context("default-src 'self'"))
+      .unwrap();
+    assert!(!index_csp(&app).contains("report-uri"));
+
+    let app = mock_builder()
+      .on_csp_violation(|_, _| {})
+      .build(csp_context("default-src 'self'"))
+      .unwrap();
+    assert!(index_csp(&app).contains(&format!("report-uri {report_uri}")));
+
+    // the report directives of the app are not overridden
+    for directive in [
+      "report-uri https://tauri.app/csp-report",
+      "report-to csp-endpoint",
+    ] {
+      let app = mock_builder()
+        .on_csp_violation(|_, _| {})
+        .build(csp_context(&format!("default-src 'self'; {directive}")))
+        .unwrap();
+      let csp = index_csp(&app);
+      assert!(csp.contains(directive));
+      assert!(!csp.contains(&report_uri));
+    }
+  }
+
   struct EventSetup {
     app: App<MockRuntime>,
     window: Window<MockRuntime>,
@@ -20,7 +20,7 @@ use tauri_utils::config::WebviewUrl;
 use url::Url;
 
 use crate::{
-  app::{GlobalWebviewEventListener, OnPageLoad, UriSchemeResponder, WebviewEvent},
+  app::{GlobalWebviewEventListener, OnCspViolation, OnPageLoad, UriSchemeResponder, WebviewEvent},
   ipc::InvokeHandler,
   pattern::PatternJavascript,
   sealed::ManagerBase,
@@ -70,6 +70,8 @@ pub struct WebviewManager<R: Runtime> {
   pub invoke_handler: Box<InvokeHandler<R>>,
   /// The page load hook, invoked when the webview performs a navigation.
   pub on_page_load: Option<Arc<OnPageLoad<R>>>,
+  /// The CSP violation hook, invoked when the webview reports a Content Security Policy violation.
+  pub on_csp_violation: Option<Arc<OnCspViolation<R>>>,
   /// The webview protocols available to all webviews.
   pub uri_scheme_protocols: Mutex<HashMap<String, Arc<UriSchemeProtocol<R>>>>,
   /// Webview event listeners to all webviews.
@@ -10,7 +10,7 @@ use serialize_to_javascript::{default_template, Template};
 
 /// The domain of the isolation iframe source.
 #[cfg(feature = "isolation")]
-pub const ISOLATION_IFRAME_SRC_DOMAIN: &str = "localhost";
+pub const ISOLATION_IFRAME_SRC_DOMAIN: &str = tauri_utils::pattern::ISOLATION_IFRAME_SRC_DOMAIN;
 
 /// An application pattern.
 #[derive(Debug)]
@@ -83,10 +83,9 @@ pub(crate) struct PatternJavascript {
 
 #[cfg(feature = "isolation")]
 pub(crate) fn format_real_schema(schema: &str, https: bool) -> String {
-  if cfg!(windows) || cfg!(target_os = "android") {
-    let scheme = if https { "https" } else { "http" };
-    format!("{scheme}://{schema}.{ISOLATION_IFRAME_SRC_DOMAIN}/")
-  } else {
-    format!("{schema}://{ISOLATION_IFRAME_SRC_DOMAIN}/")
-  }
+  tauri_utils::pattern::isolation_iframe_src(
+    schema,
+    cfg!(windows) || cfg!(target_os = "android"),
+    https,
+  )
 }
@@ -5,6 +5,8 @@
 use std::{borrow::Cow, sync::Arc};
 
 use http::{header::CONTENT_TYPE, Request, Response as HttpResponse, StatusCode};
+#[cfg(not(all(dev, mobile)))]
+use tauri_utils::assets::AssetEncoding;
 use tauri_utils::config::HeaderAddition;
 
 use crate::{
@@ -68,6 +70,15 @@ pub fn get<R: Runtime>(
   })
 }
 
+/// Whether the webview decodes custom protocol responses according to their `Content-Encoding` header.
+///
+/// WebView2 runs the responses through the Chromium network stack, which decodes them,
+/// while WKURLSchemeHandler, WebKitGTK URI scheme requests and Android's `shouldInterceptRequest`
+/// hand the body to the page as is even if the request advertised `Accept-Encoding`,
+/// so compressed assets are decoded in Rust on those platforms.
+#[cfg(not(all(dev, mobile)))]
+const WEBVIEW_DECODES_CONTENT_ENCODING: bool = cfg!(windows);
+
 fn get_response<R: Runtime>(
   #[allow(unused_mut)] mut request: Request<Vec<u8>>,
   #[allow(unused_variables)] manager: &AppManager<R>,
@@ -212,11 +223,26 @@ fn get_response<R: Runtime>(
   #[cfg(not(all(dev, mobile)))]
   let mut response = {
     let use_https_scheme = request.uri().scheme() == Some(&http::uri::Scheme::HTTPS);
-    let asset = manager.get_asset(path, use_https_scheme)?;
+    // compressed assets are served as is when the webview can decode them,
+    // unless a handler might need to read the response body
+    let accepted_encodings = match (
+      request.headers().get(http::header::ACCEPT_ENCODING),
+      web_resource_request_handler,
+    ) {
+      (Some(accept_encoding), None) if WEBVIEW_DECODES_CONTENT_ENCODING => accept_encoding
+        .to_str()
+        .map(AssetEncoding::parse_accept_encoding)
+        .unwrap_or_default(),
+      _ => Vec::new(),
+    };
+    let asset = manager.get_asset_with_encodings(path, use_https_scheme, &accepted_encodings)?;
     builder = builder.header(CONTENT_TYPE, &asset.mime_type);
     if let Some(csp) = &asset.csp_header {
       builder = builder.header("Content-Security-Policy", csp);
     }
+    if let Some(content_encoding) = asset.content_encoding {
+      builder = builder.header(http::header::CONTENT_ENCODING, content_encoding);
+    }
     builder.body(asset.bytes.into())?
   };
   if let Some(handler) = &web_resource_request_handler {
@@ -12,8 +12,11 @@ use std::{
   borrow::Cow,
   collections::BTreeMap,
   sync::Arc,
+  time::{SystemTime, UNIX_EPOCH},
 };
 
+use serde::{Serialize, Serializer};
+
 /// Resources are Rust objects that are stored in [ResourceTable] and managed by tauri.
 ///
 /// They are identified in JS by a numeric ID (the resource ID, or rid).
@@ -72,9 +75,103 @@ pub type ResourceId = u32;
 ///
 /// Each resource is identified through a _resource ID (rid)_, which acts as
 /// the key in the map.
+///
+/// The table keeps track of when each resource was added and how many resources
+/// were allocated and released over its lifetime, see [`Self::resources`] and [`Self::stats`].
 #[derive(Default)]
 pub struct ResourceTable {
-  index: BTreeMap<ResourceId, Arc<dyn Resource>>,
+  index: BTreeMap<ResourceId, Entry>,
+  allocated: u64,
+  released: u64,
+}
+
+struct Entry {
+  resource: Arc<dyn Resource>,
+  created_at: SystemTime,
+}
+
+impl Entry {
+  fn new(resource: Arc<dyn Resource>) -> Self {
+    Self {
+      resource,
+      created_at: SystemTime::now(),
+    }
+  }
+}
+
+/// Information about a resource stored in a [`ResourceTable`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ResourceInfo {
+  /// The resource ID.
+  pub rid: ResourceId,
+  /// The resource name, see [`Resource::name`].
+  pub name: String,
+  /// When the resource was added to the table. Serialized as milliseconds since the UNIX epoch.
+  #[serde(serialize_with = "serialize_timestamp")]
+  pub created_at: SystemTime,
+  /// The number of references to the resource, including the one held by the table.
+  ///
+  /// A count greater than one means the resource is still used somewhere else
+  /// and will not be dropped when it is closed.
+  pub references: usize,
+}
+
+fn serialize_timestamp<S: Serializer>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error> {
+  let millis = time
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_millis() as u64)
+    .unwrap_or_default();
+  serializer.serialize_u64(millis)
+}
+
+/// Counters of a [`ResourceTable`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ResourceTableStats {
+  /// The number of resources currently stored.
+  pub live: usize,
+  /// The number of resources added over the table lifetime.
+  pub allocated: u64,
+  /// The number of resources removed over the table lifetime, either closed or taken.
+  pub released: u64,
+}
+
+/// The owner of a [`ResourceTable`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "label", rename_all = "camelCase")]
+pub enum ResourceOwner {
+  /// The application resources table, see [`AppHandle::resources_table`](crate::Manager::resources_table).
+  App,
+  /// The resources table of the window with the given label.
+  Window(String),
+  /// The resources table of the webview with the given label.
+  Webview(String),
+}
+
+/// The live resources of a [`ResourceOwner`], see [`AppHandle::resource_usage`](crate::AppHandle::resource_usage).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ResourceUsage {
+  /// The owner of the resources.
+  pub owner: ResourceOwner,
+  /// The counters of the owner resources table.
+  pub stats: ResourceTableStats,
+  /// The live resources.
+  pub resources: Vec<ResourceInfo>,
+}
+
+impl ResourceUsage {
+  pub(crate) fn new(owner: ResourceOwner, table: &ResourceTable) -> Self {
+    Self {
+      owner,
+      stats: table.stats(),
+      resources: table.resources().collect(),
+    }
+  }
 }
 
 impl ResourceTable {
@@ -117,8 +214,9 @@ impl ResourceTable {
       rid = Self::new_random_rid();
     }
 
-    let removed_resource = self.index.insert(rid, resource);
+    let removed_resource = self.index.insert(rid, Entry::new(resource));
     assert!(removed_resource.is_none());
+    self.allocated += 1;
     rid
   }
 
@@ -134,7 +232,7 @@ impl ResourceTable {
     self
       .index
       .get(&rid)
-      .and_then(|rc| rc.downcast_arc::<T>())
+      .and_then(|entry| entry.resource.downcast_arc::<T>())
       .cloned()
       .ok_or_else(|| crate::Error::BadResourceId(rid))
   }
@@ -145,8 +243,8 @@ impl ResourceTable {
     self
       .index
       .get(&rid)
+      .map(|entry| entry.resource.clone())
       .ok_or_else(|| crate::Error::BadResourceId(rid))
-      .cloned()
   }
 
   /// Replaces a resource with a new resource.
@@ -155,8 +253,10 @@ impl ResourceTable {
   pub fn replace<T: Resource>(&mut self, rid: ResourceId, resource: T) {
     let result = self
       .index
-      .insert(rid, Arc::new(resource) as Arc<dyn Resource>);
+      .insert(rid, Entry::new(Arc::new(resource) as Arc<dyn Resource>));
     assert!(result.is_some());
+    self.allocated += 1;
+    self.released += 1;
   }
 
   /// Removes a resource of type `T` from the resource table and returns it.
@@ -172,6 +272,7 @@ impl ResourceTable {
   pub fn take<T: Resource>(&mut self, rid: ResourceId) -> crate::Result<Arc<T>> {
     let resource = self.get::<T>(rid)?;
     self.index.remove(&rid);
+    self.released += 1;
     Ok(resource)
   }
 
@@ -184,10 +285,12 @@ impl ResourceTable {
   /// on success. In particular, be really careful when you want to extract the
   /// inner value of type `T` from `Arc<T>`.
   pub fn take_any(&mut self, rid: ResourceId) -> crate::Result<Arc<dyn Resource>> {
-    self
+    let entry = self
       .index
       .remove(&rid)
-      .ok_or_else(|| crate::Error::BadResourceId(rid))
+      .ok_or_else(|| crate::Error::BadResourceId(rid))?;
+    self.released += 1;
+    Ok(entry.resource)
   }
 
   /// Returns an iterator that yields a `(id, name)` pair for every resource
@@ -198,7 +301,28 @@ impl ResourceTable {
     self
       .index
       .iter()
-      .map(|(&id, resource)| (id, resource.name()))
+      .map(|(&id, entry)| (id, entry.resource.name()))
+  }
+
+  /// Returns an iterator that yields information about every resource
+  /// that's currently in the resource table, ordered by resource ID.
+  /// This can be used to find leaked resources.
+  pub fn resources(&self) -> impl Iterator<Item = ResourceInfo> + '_ {
+    self.index.iter().map(|(&rid, entry)| ResourceInfo {
+      rid,
+      name: entry.resource.name().into_owned(),
+      created_at: entry.created_at,
+      references: Arc::strong_count(&entry.resource),
+    })
+  }
+
+  /// Returns the counters of this resource table.
+  pub fn stats(&self) -> ResourceTableStats {
+    ResourceTableStats {
+      live: self.index.len(),
+      allocated: self.allocated,
+      released: self.released,
+    }
   }
 
   /// Removes the resource with the given `rid` from the resource table. If the
@@ -208,16 +332,90 @@ impl ResourceTable {
   /// may implement the `close()` method to perform clean-ups such as canceling
   /// ops.
   pub fn close(&mut self, rid: ResourceId) -> crate::Result<()> {
-    self
-      .index
-      .remove(&rid)
-      .ok_or_else(|| crate::Error::BadResourceId(rid))
-      .map(|resource| resource.close())
+    self.take_any(rid).map(|resource| resource.close())
+  }
+
+  /// Removes all resources stored and returns them so they can be closed
+  /// after the table lock is released.
+  pub(crate) fn drain(&mut self) -> impl Iterator<Item = Arc<dyn Resource>> {
+    let index = std::mem::take(&mut self.index);
+    self.released += index.len() as u64;
+    index.into_values().map(|entry| entry.resource)
   }
 
   /// Removes and frees all resources stored. Note that the
   /// resource's `close()` method is *not* called.
   pub(crate) fn clear(&mut self) {
+    self.released += self.index.len() as u64;
     self.index.clear()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use std::sync::atomic::{AtomicUsize, Ordering};
+
+  use super::*;
+
+  struct Handle(Arc<AtomicUsize>);
+
+  impl Resource for Handle {
+    fn close(self: Arc<Self>) {
+      self.0.fetch_add(1, Ordering::SeqCst);
+    }
+  }
+
+  #[test]
+  fn stats_and_drain() {
+    let closed = Arc::new(AtomicUsize::new(0));
+    let mut table = ResourceTable::default();
+
+    let first = table.add(Handle(closed.clone()));
+    let second = table.add(Handle(closed.clone()));
+    table.add(Handle(closed.clone()));
+    table.close(first).unwrap();
+    let kept = table.take::<Handle>(second).unwrap();
+
+    assert_eq!(
+      table.stats(),
+      ResourceTableStats {
+        live: 1,
+        allocated: 3,
+        released: 2,
+      }
+    );
+    assert_eq!(closed.load(Ordering::SeqCst), 1);
+    assert_eq!(table.resources().next().unwrap().references, 1);
+
+    let second = table.add_arc(kept.clone());
+    let info = table.resources().find(|r| r.rid == second).unwrap();
+    assert_eq!(info.references, 2);
+    assert!(info.name.ends_with("Handle"));
+
+    for resource in table.drain() {
+      resource.close();
+    }
+    assert_eq!(closed.load(Ordering::SeqCst), 3);
+    assert_eq!(table.stats().live, 0);
+    assert_eq!(table.stats().released, 4);
+  }
+
+  #[test]
+  fn clear_counts_released() {
+    let closed = Arc::new(AtomicUsize::new(0));
+    let mut table = ResourceTable::default();
+    table.add(Handle(closed.clone()));
+    table.add(Handle(closed.clone()));
+
+    table.clear();
+    assert_eq!(
+      table.stats(),
+      ResourceTableStats {
+        live: 0,
+        allocated: 2,
+        released: 2,
+      }
+    );
+    assert_eq!(closed.load(Ordering::SeqCst), 0);
+  }
+}
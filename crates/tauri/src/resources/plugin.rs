@@ -8,7 +8,7 @@ use crate::{
   Manager, Runtime, Webview,
 };
 
-use super::ResourceId;
+use super::{ResourceId, ResourceUsage};
 
 #[command(root = "crate")]
 fn close<R: Runtime>(webview: Webview<R>, rid: ResourceId) -> crate::Result<()> {
@@ -22,11 +22,17 @@ fn close<R: Runtime>(webview: Webview<R>, rid: ResourceId) -> crate::Result<()>
   result
 }
 
+#[command(root = "crate")]
+fn list<R: Runtime>(webview: Webview<R>) -> Vec<ResourceUsage> {
+  webview.app_handle().resource_usage()
+}
+
 pub(crate) fn init<R: Runtime>() -> TauriPlugin<R> {
   Builder::new("resources")
     .invoke_handler(crate::generate_handler![
       #![plugin(resources)]
-      close
+      close,
+      list
     ])
     .build()
 }
@@ -12,12 +12,22 @@ use std::{
   },
 };
 
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
 use tauri_utils::config::FsScope;
 
 use crate::ScopeEventId;
 
 pub use glob::Pattern;
 
+/// Name of the directory inside the app data directory where persisted scopes are stored.
+const PERSISTED_SCOPES_DIR: &str = "scopes";
+/// Name of the file inside the app local data directory holding the secret that authenticates persisted scopes.
+const PERSISTED_SCOPES_SECRET: &str = "scopes.key";
+/// Length in bytes of the persisted scopes secret.
+const SECRET_LEN: usize = 32;
+
 /// Scope change event.
 #[derive(Debug, Clone)]
 pub enum Event {
@@ -29,6 +39,229 @@ pub enum Event {
 
 type EventListener = Box<dyn Fn(&Event) + Send>;
 
+/// A runtime change made to a [`Scope`], stored on disk when persistence is enabled with [`Scope::persist`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum PersistedGrant {
+  /// A directory allowed with [`Scope::allow_directory`].
+  AllowDirectory {
+    /// The directory path.
+    path: PathBuf,
+    /// Whether subdirectories are allowed too.
+    recursive: bool,
+  },
+  /// A file allowed with [`Scope::allow_file`].
+  AllowFile {
+    /// The file path.
+    path: PathBuf,
+  },
+  /// A directory forbidden with [`Scope::forbid_directory`].
+  ForbidDirectory {
+    /// The directory path.
+    path: PathBuf,
+    /// Whether subdirectories are forbidden too.
+    recursive: bool,
+  },
+  /// A file forbidden with [`Scope::forbid_file`].
+  ForbidFile {
+    /// The file path.
+    path: PathBuf,
+  },
+}
+
+impl PersistedGrant {
+  fn is_allow(&self) -> bool {
+    matches!(self, Self::AllowDirectory { .. } | Self::AllowFile { .. })
+  }
+
+  fn patterns(&self) -> crate::Result<HashSet<Pattern>> {
+    let mut list = HashSet::new();
+    match self {
+      Self::AllowDirectory { path, recursive } | Self::ForbidDirectory { path, recursive } => {
+        // the directory itself
+        push_pattern(&mut list, path, escaped_pattern)?;
+        // its files and subdirectories
+        push_pattern(&mut list, path, |p| {
+          escaped_pattern_with(p, if *recursive { "**" } else { "*" })
+        })?;
+      }
+      Self::AllowFile { path } | Self::ForbidFile { path } => {
+        push_pattern(&mut list, path, escaped_pattern)?;
+      }
+    }
+    Ok(list)
+  }
+
+  fn event(&self) -> Event {
+    match self {
+      Self::AllowDirectory { path, .. } | Self::AllowFile { path } => {
+        Event::PathAllowed(path.clone())
+      }
+      Self::ForbidDirectory { path, .. } | Self::ForbidFile { path } => {
+        Event::PathForbidden(path.clone())
+      }
+    }
+  }
+
+  /// The event emitted when this grant is revoked.
+  fn revoke_event(&self) -> Event {
+    match self {
+      Self::AllowDirectory { path, .. } | Self::AllowFile { path } => {
+        Event::PathForbidden(path.clone())
+      }
+      Self::ForbidDirectory { path, .. } | Self::ForbidFile { path } => {
+        Event::PathAllowed(path.clone())
+      }
+    }
+  }
+}
+
+/// On-disk format of a persisted scope.
+#[derive(Serialize, Deserialize)]
+struct PersistedScope {
+  grants: Vec<PersistedGrant>,
+  hmac: String,
+}
+
+struct Persistence {
+  path: PathBuf,
+  /// Bound to the HMAC so a file copied from another app or another scope is ignored.
+  key: String,
+  /// Per-install secret keying the HMAC, stored apart from the grants file.
+  secret: [u8; SECRET_LEN],
+  grants: Vec<PersistedGrant>,
+}
+
+impl Persistence {
+  fn mac(
+    secret: &[u8; SECRET_LEN],
+    key: &str,
+    grants: &[PersistedGrant],
+  ) -> crate::Result<Hmac<Sha256>> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(key.as_bytes());
+    mac.update(b"\n");
+    mac.update(&serde_json::to_vec(grants)?);
+    Ok(mac)
+  }
+
+  /// Reads the grants stored at `path`, discarding them if the HMAC does not match.
+  fn load(path: &Path, key: &str, secret: &[u8; SECRET_LEN]) -> crate::Result<Vec<PersistedGrant>> {
+    let contents = match std::fs::read(path) {
+      Ok(contents) => contents,
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+      Err(e) => return Err(e.into()),
+    };
+
+    let persisted = match serde_json::from_slice::<PersistedScope>(&contents) {
+      Ok(persisted) => persisted,
+      Err(e) => {
+        log::warn!("ignoring malformed persisted scope {}: {e}", path.display());
+        return Ok(Vec::new());
+      }
+    };
+
+    let verified = decode_hex(&persisted.hmac).is_some_and(|tag| {
+      Self::mac(secret, key, &persisted.grants).is_ok_and(|mac| mac.verify_slice(&tag).is_ok())
+    });
+    if !verified {
+      log::warn!("ignoring persisted scope {}: HMAC mismatch", path.display());
+      return Ok(Vec::new());
+    }
+
+    Ok(persisted.grants)
+  }
+
+  fn save(&self) -> crate::Result<()> {
+    if self.grants.is_empty() {
+      return match std::fs::remove_file(&self.path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+      };
+    }
+
+    let persisted = PersistedScope {
+      hmac: Self::mac(&self.secret, &self.key, &self.grants)?
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect(),
+      grants: self.grants.clone(),
+    };
+    if let Some(parent) = self.path.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+    // write to a temporary file first so a crash never leaves a truncated grants file behind
+    let tmp = temp_path(&self.path);
+    let written = std::fs::write(&tmp, serde_json::to_vec(&persisted)?)
+      .and_then(|_| std::fs::rename(&tmp, &self.path));
+    if written.is_err() {
+      let _ = std::fs::remove_file(&tmp);
+    }
+    written.map_err(Into::into)
+  }
+}
+
+/// A unique sibling of `path` used to write it atomically.
+fn temp_path(path: &Path) -> PathBuf {
+  let mut name = path.file_name().unwrap_or_default().to_os_string();
+  name.push(format!(
+    ".{:x}.tmp",
+    getrandom::u64().expect("failed to get random bytes")
+  ));
+  path.with_file_name(name)
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+  (0..hex.len())
+    .step_by(2)
+    .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+    .collect()
+}
+
+/// Reads the per-install secret stored at `path`, creating it if it does not exist.
+fn load_or_create_secret(path: &Path) -> crate::Result<[u8; SECRET_LEN]> {
+  let invalid = || {
+    std::io::Error::new(
+      std::io::ErrorKind::InvalidData,
+      format!("invalid persisted scopes secret {}", path.display()),
+    )
+  };
+
+  match std::fs::read(path) {
+    Ok(secret) => return secret.try_into().map_err(|_| invalid().into()),
+    Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+    Err(_) => (),
+  }
+
+  let mut secret = [0; SECRET_LEN];
+  getrandom::fill(&mut secret).expect("failed to get random bytes");
+
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+  let tmp = temp_path(path);
+  let mut options = std::fs::OpenOptions::new();
+  options.write(true).create_new(true);
+  #[cfg(unix)]
+  std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+  let written = options
+    .open(&tmp)
+    .and_then(|mut file| std::io::Write::write_all(&mut file, &secret))
+    // linking fails if the secret already exists, so a secret created concurrently is never replaced
+    .and_then(|_| std::fs::hard_link(&tmp, path));
+  let _ = std::fs::remove_file(&tmp);
+
+  match written {
+    Ok(()) => Ok(secret),
+    Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => std::fs::read(path)?
+      .try_into()
+      .map_err(|_| invalid().into()),
+    Err(e) => Err(e.into()),
+  }
+}
+
 /// Scope for filesystem access.
 #[derive(Clone)]
 pub struct Scope {
@@ -37,6 +270,17 @@ pub struct Scope {
   event_listeners: Arc<Mutex<HashMap<ScopeEventId, EventListener>>>,
   match_options: glob::MatchOptions,
   next_event_id: Arc<AtomicU32>,
+  persistence: Arc<Mutex<Option<Persistence>>>,
+  /// Patterns of the [`FsScope`] configuration, which can not be revoked.
+  config_patterns: Arc<ConfigPatterns>,
+  /// Runtime grants made while persistence is disabled, which can not be revoked.
+  grants: Arc<Mutex<Vec<PersistedGrant>>>,
+}
+
+#[derive(Default)]
+struct ConfigPatterns {
+  allowed: HashSet<Pattern>,
+  forbidden: HashSet<Pattern>,
 }
 
 impl Scope {
@@ -208,10 +452,16 @@ impl Scope {
     };
 
     Ok(Self {
+      config_patterns: Arc::new(ConfigPatterns {
+        allowed: allowed_patterns.clone(),
+        forbidden: forbidden_patterns.clone(),
+      }),
       allowed_patterns: Arc::new(Mutex::new(allowed_patterns)),
       forbidden_patterns: Arc::new(Mutex::new(forbidden_patterns)),
       event_listeners: Default::default(),
       next_event_id: Default::default(),
+      persistence: Default::default(),
+      grants: Default::default(),
       match_options: glob::MatchOptions {
         // this is needed so `/dir/*` doesn't match files within subdirectories such as `/dir/subdir/file.txt`
         // see: <https://github.com/tauri-apps/tauri/security/advisories/GHSA-6mv3-wm7j-h4w5>
@@ -276,65 +526,206 @@ impl Scope {
   /// After this function has been called, the frontend will be able to use the Tauri API to read
   /// the directory and all of its files. If `recursive` is `true`, subdirectories will be accessible too.
   pub fn allow_directory<P: AsRef<Path>>(&self, path: P, recursive: bool) -> crate::Result<()> {
-    let path = path.as_ref();
-    {
-      let mut list = self.allowed_patterns.lock().unwrap();
-
-      // allow the directory to be read
-      push_pattern(&mut list, path, escaped_pattern)?;
-      // allow its files and subdirectories to be read
-      push_pattern(&mut list, path, |p| {
-        escaped_pattern_with(p, if recursive { "**" } else { "*" })
-      })?;
-    }
-    self.emit(Event::PathAllowed(path.to_path_buf()));
-    Ok(())
+    self.grant(PersistedGrant::AllowDirectory {
+      path: path.as_ref().to_path_buf(),
+      recursive,
+    })
   }
 
   /// Extend the allowed patterns with the given file path.
   ///
   /// After this function has been called, the frontend will be able to use the Tauri API to read the contents of this file.
   pub fn allow_file<P: AsRef<Path>>(&self, path: P) -> crate::Result<()> {
-    let path = path.as_ref();
-    push_pattern(
-      &mut self.allowed_patterns.lock().unwrap(),
-      path,
-      escaped_pattern,
-    )?;
-    self.emit(Event::PathAllowed(path.to_path_buf()));
-    Ok(())
+    self.grant(PersistedGrant::AllowFile {
+      path: path.as_ref().to_path_buf(),
+    })
   }
 
   /// Set the given directory path to be forbidden by this scope.
   ///
   /// **Note:** this takes precedence over allowed paths, so its access gets denied **always**.
   pub fn forbid_directory<P: AsRef<Path>>(&self, path: P, recursive: bool) -> crate::Result<()> {
-    let path = path.as_ref();
-    {
-      let mut list = self.forbidden_patterns.lock().unwrap();
-
-      // allow the directory to be read
-      push_pattern(&mut list, path, escaped_pattern)?;
-      // allow its files and subdirectories to be read
-      push_pattern(&mut list, path, |p| {
-        escaped_pattern_with(p, if recursive { "**" } else { "*" })
-      })?;
-    }
-    self.emit(Event::PathForbidden(path.to_path_buf()));
-    Ok(())
+    self.grant(PersistedGrant::ForbidDirectory {
+      path: path.as_ref().to_path_buf(),
+      recursive,
+    })
   }
 
   /// Set the given file path to be forbidden by this scope.
   ///
   /// **Note:** this takes precedence over allowed paths, so its access gets denied **always**.
   pub fn forbid_file<P: AsRef<Path>>(&self, path: P) -> crate::Result<()> {
-    let path = path.as_ref();
-    push_pattern(
-      &mut self.forbidden_patterns.lock().unwrap(),
-      path,
-      escaped_pattern,
+    self.grant(PersistedGrant::ForbidFile {
+      path: path.as_ref().to_path_buf(),
+    })
+  }
+
+  fn patterns_for(&self, grant: &PersistedGrant) -> &Arc<Mutex<HashSet<Pattern>>> {
+    if grant.is_allow() {
+      &self.allowed_patterns
+    } else {
+      &self.forbidden_patterns
+    }
+  }
+
+  fn grant(&self, grant: PersistedGrant) -> crate::Result<()> {
+    let patterns = grant.patterns()?;
+    self.patterns_for(&grant).lock().unwrap().extend(patterns);
+
+    let persisted = match &mut *self.persistence.lock().unwrap() {
+      Some(persistence) if !persistence.grants.contains(&grant) => {
+        persistence.grants.push(grant.clone());
+        persistence.save()
+      }
+      Some(_) => Ok(()),
+      None => {
+        let mut grants = self.grants.lock().unwrap();
+        if !grants.contains(&grant) {
+          grants.push(grant.clone());
+        }
+        Ok(())
+      }
+    };
+
+    self.emit(grant.event());
+    persisted
+  }
+
+  /// Persists the runtime changes made to this scope so they survive app restarts.
+  ///
+  /// Grants previously stored for the given `identifier` are loaded from the app data directory and applied,
+  /// emitting [`Event::PathAllowed`] and [`Event::PathForbidden`] events,
+  /// and any later call to [`Self::allow_directory`], [`Self::allow_file`],
+  /// [`Self::forbid_directory`] or [`Self::forbid_file`] is stored.
+  ///
+  /// The file is authenticated with an HMAC bound to the app and scope identifiers and keyed with a secret
+  /// generated on first use and stored in the app local data directory; if the file is corrupted, was modified
+  /// or was copied from another app, scope or install, the stored grants are discarded.
+  ///
+  /// The identifier may only contain alphanumeric characters, `-` and `_`.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use tauri::{scope::fs::Scope, App};
+  ///
+  /// fn restore_workspace_access(app: &App, scope: &Scope) -> tauri::Result<()> {
+  ///   // restores the folders picked by the user on previous runs
+  ///   scope.persist(app, "workspace")
+  /// }
+  /// ```
+  pub fn persist<R: crate::Runtime, M: crate::Manager<R>>(
+    &self,
+    manager: &M,
+    identifier: &str,
+  ) -> crate::Result<()> {
+    if identifier.is_empty()
+      || !identifier
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+      return Err(crate::Error::IllegalScopeIdentifier(identifier.into()));
+    }
+
+    let path = manager
+      .path()
+      .app_data_dir()?
+      .join(PERSISTED_SCOPES_DIR)
+      .join(format!("{identifier}.json"));
+    let key = format!("{}:{identifier}", manager.config().identifier);
+    let secret = load_or_create_secret(
+      &manager
+        .path()
+        .app_local_data_dir()?
+        .join(PERSISTED_SCOPES_SECRET),
     )?;
-    self.emit(Event::PathForbidden(path.to_path_buf()));
+    let grants = Persistence::load(&path, &key, &secret)?;
+
+    for grant in &grants {
+      let patterns = grant.patterns()?;
+      self.patterns_for(grant).lock().unwrap().extend(patterns);
+    }
+
+    self.persistence.lock().unwrap().replace(Persistence {
+      path,
+      key,
+      secret,
+      grants: grants.clone(),
+    });
+
+    for grant in grants {
+      self.emit(grant.event());
+    }
+
+    Ok(())
+  }
+
+  /// The runtime changes stored on disk for this scope.
+  ///
+  /// Always empty if [`Self::persist`] has not been called.
+  pub fn persisted_grants(&self) -> Vec<PersistedGrant> {
+    self
+      .persistence
+      .lock()
+      .unwrap()
+      .as_ref()
+      .map(|p| p.grants.clone())
+      .unwrap_or_default()
+  }
+
+  /// Revokes a persisted grant, removing it from this scope and from the disk.
+  ///
+  /// Paths also granted by the [`FsScope`] configuration or by other grants, persisted or not, remain accessible.
+  /// Emits [`Event::PathForbidden`] when revoking an allow grant and [`Event::PathAllowed`] when revoking a forbid grant.
+  ///
+  /// Returns `false` if the grant was not found in [`Self::persisted_grants`].
+  pub fn revoke(&self, grant: &PersistedGrant) -> crate::Result<bool> {
+    {
+      let mut persistence = self.persistence.lock().unwrap();
+      let Some(persistence) = persistence.as_mut() else {
+        return Ok(false);
+      };
+      let Some(index) = persistence.grants.iter().position(|g| g == grant) else {
+        return Ok(false);
+      };
+      persistence.grants.remove(index);
+
+      // keep patterns still required by the configuration or the remaining grants of the same kind
+      let mut retained = if grant.is_allow() {
+        self.config_patterns.allowed.clone()
+      } else {
+        self.config_patterns.forbidden.clone()
+      };
+      for g in self
+        .grants
+        .lock()
+        .unwrap()
+        .iter()
+        .chain(&persistence.grants)
+      {
+        if g.is_allow() == grant.is_allow() {
+          retained.extend(g.patterns()?);
+        }
+      }
+      let mut patterns = self.patterns_for(grant).lock().unwrap();
+      for pattern in grant.patterns()? {
+        if !retained.contains(&pattern) {
+          patterns.remove(&pattern);
+        }
+      }
+
+      persistence.save()?;
+    }
+
+    self.emit(grant.revoke_event());
+    Ok(true)
+  }
+
+  /// Revokes all persisted grants, see [`Self::revoke`].
+  pub fn revoke_all(&self) -> crate::Result<()> {
+    for grant in self.persisted_grants() {
+      self.revoke(&grant)?;
+    }
     Ok(())
   }
 
@@ -428,7 +819,7 @@ mod tests {
 
   use glob::Pattern;
 
-  use super::{push_pattern, Scope};
+  use super::{push_pattern, PersistedGrant, Persistence, Scope};
 
   fn new_scope() -> Scope {
     Scope {
@@ -436,6 +827,9 @@ mod tests {
       forbidden_patterns: Default::default(),
       event_listeners: Default::default(),
       next_event_id: Default::default(),
+      persistence: Default::default(),
+      config_patterns: Default::default(),
+      grants: Default::default(),
       match_options: glob::MatchOptions {
         // this is needed so `/dir/*` doesn't match files within subdirectories such as `/dir/subdir/file.txt`
         // see: <https://github.com/tauri-apps/tauri/security/advisories/GHSA-6mv3-wm7j-h4w5>
@@ -450,6 +844,148 @@ mod tests {
     }
   }
 
+  #[test]
+  fn persisted_grants() {
+    let path = std::env::temp_dir()
+      .join("tauri-persisted-scope-test")
+      .join("workspace.json");
+    let _ = std::fs::remove_file(&path);
+    let secret = [1; super::SECRET_LEN];
+
+    let scope = new_scope();
+    scope.persistence.lock().unwrap().replace(Persistence {
+      path: path.clone(),
+      key: "app.tauri.test:workspace".into(),
+      secret,
+      grants: Vec::new(),
+    });
+
+    #[cfg(unix)]
+    let (dir, file) = ("/home/tauri/workspace", "/home/tauri/workspace/file");
+    #[cfg(windows)]
+    let (dir, file) = (
+      "C:\\home\\tauri\\workspace",
+      "C:\\home\\tauri\\workspace\\file",
+    );
+
+    scope.allow_directory(dir, true).unwrap();
+    // already stored
+    scope.allow_directory(dir, true).unwrap();
+    assert!(scope.is_allowed(file));
+
+    let grants = Persistence::load(&path, "app.tauri.test:workspace", &secret).unwrap();
+    assert_eq!(
+      grants,
+      vec![PersistedGrant::AllowDirectory {
+        path: dir.into(),
+        recursive: true
+      }]
+    );
+    // bound to the app and scope identifiers and to the install secret
+    assert!(
+      Persistence::load(&path, "app.tauri.other:workspace", &secret)
+        .unwrap()
+        .is_empty()
+    );
+    assert!(
+      Persistence::load(&path, "app.tauri.test:workspace", &[2; super::SECRET_LEN])
+        .unwrap()
+        .is_empty()
+    );
+
+    assert!(scope.revoke(&grants[0]).unwrap());
+    assert!(!scope.is_allowed(file));
+    assert!(scope.persisted_grants().is_empty());
+    assert!(!path.exists());
+  }
+
+  #[test]
+  fn secret_is_created_once() {
+    let path = std::env::temp_dir()
+      .join("tauri-persisted-scope-secret-test")
+      .join("scopes.key");
+    let _ = std::fs::remove_file(&path);
+
+    let secret = super::load_or_create_secret(&path).unwrap();
+    assert_eq!(super::load_or_create_secret(&path).unwrap(), secret);
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::PermissionsExt;
+      let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+      assert_eq!(mode & 0o777, 0o600);
+    }
+    // no temporary file is left behind
+    assert_eq!(
+      std::fs::read_dir(path.parent().unwrap()).unwrap().count(),
+      1
+    );
+  }
+
+  #[test]
+  fn revoke_keeps_other_grants() {
+    let path = std::env::temp_dir()
+      .join("tauri-persisted-scope-revoke-test")
+      .join("workspace.json");
+    let _ = std::fs::remove_file(&path);
+
+    #[cfg(unix)]
+    let (config_dir, dir, file) = (
+      "/home/tauri/config",
+      "/home/tauri/workspace",
+      "/home/tauri/workspace/file",
+    );
+    #[cfg(windows)]
+    let (config_dir, dir, file) = (
+      "C:\\home\\tauri\\config",
+      "C:\\home\\tauri\\workspace",
+      "C:\\home\\tauri\\workspace\\file",
+    );
+
+    let mut scope = new_scope();
+    let grant = PersistedGrant::AllowDirectory {
+      path: config_dir.into(),
+      recursive: true,
+    };
+    let config_patterns = super::ConfigPatterns {
+      allowed: grant.patterns().unwrap(),
+      forbidden: Default::default(),
+    };
+    scope
+      .allowed_patterns
+      .lock()
+      .unwrap()
+      .extend(config_patterns.allowed.clone());
+    scope.config_patterns = std::sync::Arc::new(config_patterns);
+
+    let revoked = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let revoked_ = revoked.clone();
+    scope.listen(move |event| {
+      if let super::Event::PathForbidden(path) = event {
+        revoked_.lock().unwrap().push(path.clone());
+      }
+    });
+
+    // granted before persistence is enabled, so it is not stored
+    scope.allow_directory(dir, true).unwrap();
+    scope.persistence.lock().unwrap().replace(Persistence {
+      path: path.clone(),
+      key: "app.tauri.test:workspace".into(),
+      secret: [1; super::SECRET_LEN],
+      grants: Vec::new(),
+    });
+    scope.allow_directory(dir, true).unwrap();
+    scope.allow_directory(config_dir, true).unwrap();
+
+    for grant in scope.persisted_grants() {
+      assert!(scope.revoke(&grant).unwrap());
+    }
+    assert_eq!(revoked.lock().unwrap().len(), 2);
+
+    // still granted by the configuration and by the non-persisted grant
+    assert!(scope.is_allowed(format!("{config_dir}{}file", std::path::MAIN_SEPARATOR)));
+    assert!(scope.is_allowed(file));
+  }
+
   #[test]
   fn path_is_escaped() {
     let scope = new_scope();
@@ -7,6 +7,8 @@ pub mod fs;
 
 use std::path::Path;
 
+pub use tauri_utils::acl::scope::{RangeScope, StringScope, UrlScope};
+
 /// Unique id of a scope event.
 pub type ScopeEventId = u32;
 
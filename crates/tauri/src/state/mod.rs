@@ -7,7 +7,7 @@ use std::{
   collections::HashMap,
   hash::BuildHasherDefault,
   pin::Pin,
-  sync::Mutex,
+  sync::{Arc, Mutex},
 };
 
 use crate::{
@@ -15,6 +15,12 @@ use crate::{
   Runtime,
 };
 
+pub(crate) mod plugin;
+mod synced;
+
+pub(crate) use synced::SyncedStates;
+pub use synced::{SyncedState, SyncedStateChange};
+
 /// A guard for a state value.
 ///
 /// See [`Manager::manage`](`crate::Manager::manage`) for usage examples.
@@ -69,6 +75,153 @@ impl<'r, 'de: 'r, T: Send + Sync + 'static, R: Runtime> CommandArg<'de, R> for S
   }
 }
 
+/// A guard for a state value scoped to a window or webview.
+///
+/// The guard keeps the value alive even if the window or webview is destroyed while it is held.
+///
+/// See [`Webview::manage_scoped`](`crate::webview::Webview::manage_scoped`) for usage examples.
+pub struct ScopedState<T: Send + Sync + 'static>(Arc<T>);
+
+impl<T: Send + Sync + 'static> ScopedState<T> {
+  /// Retrieve a borrow to the underlying value.
+  /// Using this method is typically unnecessary as `ScopedState` implements
+  /// [`std::ops::Deref`] with a [`std::ops::Deref::Target`] of `T`.
+  #[inline(always)]
+  pub fn inner(&self) -> &T {
+    &self.0
+  }
+}
+
+impl<T: Send + Sync + 'static> std::ops::Deref for ScopedState<T> {
+  type Target = T;
+
+  #[inline(always)]
+  fn deref(&self) -> &T {
+    &self.0
+  }
+}
+
+impl<T: Send + Sync + 'static> Clone for ScopedState<T> {
+  fn clone(&self) -> Self {
+    ScopedState(self.0.clone())
+  }
+}
+
+impl<T: Send + Sync + std::fmt::Debug> std::fmt::Debug for ScopedState<T> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_tuple("ScopedState").field(&self.0).finish()
+  }
+}
+
+/// A guard for a state value scoped to the webview that invoked the command.
+///
+/// See [`Webview::manage_scoped`](`crate::webview::Webview::manage_scoped`) for usage examples.
+pub struct WebviewState<T: Send + Sync + 'static>(ScopedState<T>);
+
+impl<T: Send + Sync + 'static> WebviewState<T> {
+  /// Retrieve a borrow to the underlying value.
+  /// Using this method is typically unnecessary as `WebviewState` implements
+  /// [`std::ops::Deref`] with a [`std::ops::Deref::Target`] of `T`.
+  #[inline(always)]
+  pub fn inner(&self) -> &T {
+    self.0.inner()
+  }
+}
+
+impl<T: Send + Sync + 'static> std::ops::Deref for WebviewState<T> {
+  type Target = T;
+
+  #[inline(always)]
+  fn deref(&self) -> &T {
+    self.0.inner()
+  }
+}
+
+impl<T: Send + Sync + 'static> Clone for WebviewState<T> {
+  fn clone(&self) -> Self {
+    WebviewState(self.0.clone())
+  }
+}
+
+impl<T: Send + Sync + std::fmt::Debug> std::fmt::Debug for WebviewState<T> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_tuple("WebviewState").field(&self.0 .0).finish()
+  }
+}
+
+impl<'de, T: Send + Sync + 'static, R: Runtime> CommandArg<'de, R> for WebviewState<T> {
+  /// Grabs the [`WebviewState`] of the webview that invoked the command.
+  fn from_command(command: CommandItem<'de, R>) -> Result<Self, InvokeError> {
+    command
+      .message
+      .webview_ref()
+      .try_scoped_state()
+      .map(WebviewState)
+      .ok_or_else(|| {
+        InvokeError::from_anyhow(anyhow::anyhow!(
+          "webview state not managed for field `{}` on command `{}`. You must call `.manage_scoped()` on the webview before using this command",
+          command.key, command.name
+        ))
+      })
+  }
+}
+
+type ScopedStateMap = HashMap<TypeId, Arc<dyn Any + Send + Sync>, BuildHasherDefault<IdentHash>>;
+
+/// The state scoped to a window or webview.
+///
+/// Unlike [`StateManager`], values are reference counted so they can be dropped
+/// when the window or webview is destroyed without invalidating the guards handed out.
+#[derive(Debug)]
+pub(crate) struct ScopedStateManager {
+  /// `None` once the window or webview was destroyed.
+  map: Mutex<Option<ScopedStateMap>>,
+}
+
+impl ScopedStateManager {
+  pub(crate) fn new() -> Self {
+    Self {
+      map: Mutex::new(Some(Default::default())),
+    }
+  }
+
+  /// Returns `false` if the state was already set or the owner was destroyed.
+  pub(crate) fn set<T: Send + Sync + 'static>(&self, state: T) -> bool {
+    let mut map = self.map.lock().unwrap();
+    let Some(map) = map.as_mut() else {
+      return false;
+    };
+    let type_id = TypeId::of::<T>();
+    if map.contains_key(&type_id) {
+      return false;
+    }
+    map.insert(type_id, Arc::new(state));
+    true
+  }
+
+  pub(crate) fn get<T: Send + Sync + 'static>(&self) -> ScopedState<T> {
+    self.try_get().unwrap_or_else(|| {
+      panic!(
+        "scoped state not found for type {}",
+        std::any::type_name::<T>()
+      )
+    })
+  }
+
+  pub(crate) fn try_get<T: Send + Sync + 'static>(&self) -> Option<ScopedState<T>> {
+    let map = self.map.lock().unwrap();
+    let value = map.as_ref()?.get(&TypeId::of::<T>())?.clone();
+    value.downcast::<T>().ok().map(ScopedState)
+  }
+
+  /// Drops the state, called when the window or webview is destroyed.
+  pub(crate) fn clear(&self) {
+    // the values are dropped outside the lock as their `Drop` implementation may access the state
+    let map = self.map.lock().unwrap().take();
+    drop(map);
+  }
+}
+
 // Taken from: https://github.com/SergioBenitez/state/blob/556c1b94db8ce8427a0e72de7983ab5a9af4cc41/src/ident_hash.rs
 // This is a _super_ stupid hash. It just uses its input as the hash value. This
 // hash is meant to be used _only_ for "prehashed" values. In particular, we use
@@ -0,0 +1,163 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::sync::Arc;
+
+use crate::{
+  command,
+  ipc::{Channel, CommandScope},
+  plugin::{Builder, TauriPlugin},
+  scope::StringScope,
+  Error, Manager, Result, Runtime, Webview,
+};
+
+use super::synced::{ErasedSyncedState, SyncedStateChange, SyncedStateSnapshot, SyncedStates};
+
+fn resolve<R: Runtime>(
+  webview: &Webview<R>,
+  scope: &CommandScope<StringScope>,
+  name: &str,
+) -> Result<Arc<dyn ErasedSyncedState>> {
+  // states must be explicitly allowed, an unscoped permission does not grant access to any of them
+  if scope.allows().is_empty() || !scope.matches(name) {
+    return Err(Error::SyncedStateNotAllowed(name.into()));
+  }
+
+  webview
+    .try_state::<SyncedStates>()
+    .and_then(|states| states.get(name))
+    .ok_or_else(|| Error::SyncedStateNotFound(name.into()))
+}
+
+#[command(root = "crate")]
+fn get<R: Runtime>(
+  webview: Webview<R>,
+  scope: CommandScope<StringScope>,
+  name: String,
+) -> Result<SyncedStateSnapshot> {
+  Ok(resolve(&webview, &scope, &name)?.snapshot())
+}
+
+#[command(root = "crate")]
+fn subscribe<R: Runtime>(
+  webview: Webview<R>,
+  scope: CommandScope<StringScope>,
+  name: String,
+  on_change: Channel<SyncedStateChange>,
+) -> Result<SyncedStateSnapshot> {
+  let state = resolve(&webview, &scope, &name)?;
+  Ok(state.subscribe(webview.label().to_string(), on_change))
+}
+
+#[command(root = "crate")]
+fn unsubscribe<R: Runtime>(
+  webview: Webview<R>,
+  scope: CommandScope<StringScope>,
+  name: String,
+  id: u32,
+) -> Result<()> {
+  resolve(&webview, &scope, &name)?.unsubscribe(webview.label(), id);
+  Ok(())
+}
+
+#[command(root = "crate")]
+fn update<R: Runtime>(
+  webview: Webview<R>,
+  scope: CommandScope<StringScope>,
+  name: String,
+  patch: json_patch::Patch,
+  version: Option<u64>,
+) -> Result<u64> {
+  resolve(&webview, &scope, &name)?.apply(patch, version)
+}
+
+pub(crate) fn init<R: Runtime>() -> TauriPlugin<R> {
+  Builder::new("state")
+    .invoke_handler(crate::generate_handler![
+      #![plugin(state)]
+      get,
+      subscribe,
+      unsubscribe,
+      update
+    ])
+    .build()
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use crate::{
+    ipc::{CallbackFn, InvokeBody},
+    test::{
+      get_ipc_response, mock_builder, mock_context_with_acl, noop_assets, MockAcl, INVOKE_KEY,
+    },
+    webview::InvokeRequest,
+    Manager, WebviewWindowBuilder,
+  };
+
+  fn get(name: &str) -> InvokeRequest {
+    InvokeRequest {
+      cmd: "plugin:state|get".into(),
+      callback: CallbackFn(0),
+      error: CallbackFn(1),
+      url: "http://tauri.localhost".parse().unwrap(),
+      body: InvokeBody::Json(json!({ "name": name })),
+      headers: Default::default(),
+      invoke_key: INVOKE_KEY.to_string(),
+    }
+  }
+
+  fn acl(permission: &str) -> MockAcl {
+    MockAcl::new()
+      .plugin_permissions(
+        "core:state",
+        r#"{ "permission": [{ "identifier": "allow-get", "commands": { "allow": ["get"] } }] }"#,
+      )
+      .capability(&format!(
+        r#"{{ "identifier": "main", "windows": ["main"], "permissions": [{permission}] }}"#
+      ))
+  }
+
+  #[test]
+  fn unscoped_permission_is_denied() {
+    let app = mock_builder()
+      .build(mock_context_with_acl(
+        noop_assets(),
+        acl(r#""core:state:allow-get""#),
+      ))
+      .unwrap();
+    app.manage_synced("counter", 0u32).unwrap();
+    let main = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    assert_eq!(
+      get_ipc_response(&main, get("counter")).unwrap_err(),
+      json!(crate::Error::SyncedStateNotAllowed("counter".into()).to_string())
+    );
+  }
+
+  #[test]
+  fn scoped_permission() {
+    let app = mock_builder()
+      .build(mock_context_with_acl(
+        noop_assets(),
+        acl(r#"{ "identifier": "core:state:allow-get", "allow": [{ "values": ["counter"] }] }"#),
+      ))
+      .unwrap();
+    app.manage_synced("counter", 1u32).unwrap();
+    app.manage_synced("secret", 2u32).unwrap();
+    let main = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    let snapshot = get_ipc_response(&main, get("counter"))
+      .unwrap()
+      .deserialize::<serde_json::Value>()
+      .unwrap();
+    assert_eq!(snapshot["value"], 1);
+    assert!(get_ipc_response(&main, get("secret")).is_err());
+  }
+}
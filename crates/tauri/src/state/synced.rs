@@ -0,0 +1,431 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! State synchronized to the frontend.
+
+use std::{
+  any::Any,
+  collections::HashMap,
+  sync::{Arc, Mutex},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::ipc::Channel;
+
+/// A change of a [`SyncedState`], sent to its subscribers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncedStateChange {
+  /// The version of the state after the change was applied.
+  pub version: u64,
+  /// The [JSON Patch](https://datatracker.ietf.org/doc/html/rfc6902) to apply to the previous version.
+  pub patch: json_patch::Patch,
+}
+
+/// A snapshot of a [`SyncedState`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SyncedStateSnapshot {
+  pub(crate) version: u64,
+  pub(crate) value: JsonValue,
+}
+
+struct Subscriber {
+  webview: String,
+  channel: Channel<SyncedStateChange>,
+}
+
+struct Inner<T> {
+  value: T,
+  snapshot: JsonValue,
+  version: u64,
+  subscribers: HashMap<u32, Subscriber>,
+}
+
+/// A managed state that is synchronized to the webviews that subscribe to it.
+///
+/// Every change made on the Rust side is serialized and diffed against the previous value,
+/// and the resulting [JSON Patch](https://datatracker.ietf.org/doc/html/rfc6902) is delivered to the subscribers.
+/// The frontend reads and subscribes to the state with the `SyncedState` class of the `@tauri-apps/api/state` module.
+///
+/// Frontend access is controlled by the `core:state` permissions: the `allow-get`, `allow-subscribe` and `allow-unsubscribe`
+/// permissions (enabled by default) let a webview read the state and `allow-update` lets it write to it.
+/// Their scope is a [`StringScope`](crate::scope::StringScope) matched against the state name.
+/// Access is denied unless the state name is allowed, use `{ "glob": "*" }` to allow all states.
+///
+/// See [`Manager::manage_synced`](crate::Manager::manage_synced).
+pub struct SyncedState<T> {
+  name: Arc<str>,
+  inner: Arc<Mutex<Inner<T>>>,
+}
+
+impl<T> Clone for SyncedState<T> {
+  fn clone(&self) -> Self {
+    Self {
+      name: self.name.clone(),
+      inner: self.inner.clone(),
+    }
+  }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for SyncedState<T> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let inner = self.inner.lock().unwrap();
+    f.debug_struct("SyncedState")
+      .field("name", &self.name)
+      .field("value", &inner.value)
+      .field("version", &inner.version)
+      .finish()
+  }
+}
+
+impl<T: Serialize + DeserializeOwned + Send + 'static> SyncedState<T> {
+  pub(crate) fn new(name: String, value: T) -> crate::Result<Self> {
+    let snapshot = serde_json::to_value(&value)?;
+    Ok(Self {
+      name: name.into(),
+      inner: Arc::new(Mutex::new(Inner {
+        value,
+        snapshot,
+        version: 0,
+        subscribers: Default::default(),
+      })),
+    })
+  }
+
+  /// The name of this state.
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  /// The current version of this state, incremented on every change.
+  pub fn version(&self) -> u64 {
+    self.inner.lock().unwrap().version
+  }
+
+  /// Runs the given closure with a reference to the current value.
+  pub fn with<O, F: FnOnce(&T) -> O>(&self, f: F) -> O {
+    f(&self.inner.lock().unwrap().value)
+  }
+
+  /// Gets a clone of the current value.
+  pub fn get(&self) -> T
+  where
+    T: Clone,
+  {
+    self.with(Clone::clone)
+  }
+
+  /// Replaces the value and notifies the subscribers.
+  pub fn set(&self, value: T) -> crate::Result<()> {
+    self.update(|current| *current = value)
+  }
+
+  /// Updates the value in place and notifies the subscribers of the changes.
+  ///
+  /// The subscribers are not notified if the serialized value did not change.
+  pub fn update<F: FnOnce(&mut T)>(&self, f: F) -> crate::Result<()> {
+    let pending = {
+      let mut inner = self.inner.lock().unwrap();
+      f(&mut inner.value);
+      let snapshot = serde_json::to_value(&inner.value)?;
+      inner.commit(snapshot)
+    };
+    self.notify(pending);
+    Ok(())
+  }
+
+  /// Sends a committed change to its subscribers, without holding the state lock.
+  ///
+  /// Concurrent changes may be delivered out of order; the frontend resynchronizes when it sees a version gap.
+  fn notify(&self, pending: Option<PendingChange>) {
+    let Some(PendingChange { change, channels }) = pending else {
+      return;
+    };
+
+    let closed = channels
+      .into_iter()
+      .filter(|(_, channel)| channel.send(change.clone()).is_err())
+      .map(|(id, _)| id)
+      .collect::<Vec<_>>();
+
+    // drop the subscribers whose webview is gone
+    if !closed.is_empty() {
+      let mut inner = self.inner.lock().unwrap();
+      for id in closed {
+        inner.subscribers.remove(&id);
+      }
+    }
+  }
+}
+
+/// A committed change and the channels of the subscribers to send it to.
+struct PendingChange {
+  change: SyncedStateChange,
+  channels: Vec<(u32, Channel<SyncedStateChange>)>,
+}
+
+impl<T> Inner<T> {
+  /// Stores the new snapshot, returning its diff and the subscribers to notify.
+  ///
+  /// Returns `None` if the snapshot did not change.
+  fn commit(&mut self, snapshot: JsonValue) -> Option<PendingChange> {
+    let patch = json_patch::diff(&self.snapshot, &snapshot);
+    if patch.0.is_empty() {
+      return None;
+    }
+
+    self.snapshot = snapshot;
+    self.version += 1;
+
+    Some(PendingChange {
+      change: SyncedStateChange {
+        version: self.version,
+        patch,
+      },
+      channels: self
+        .subscribers
+        .iter()
+        .map(|(id, subscriber)| (*id, subscriber.channel.clone()))
+        .collect(),
+    })
+  }
+}
+
+/// Type-erased [`SyncedState`] used by the `core:state` plugin.
+pub(crate) trait ErasedSyncedState: Send + Sync {
+  fn as_any(&self) -> &dyn Any;
+
+  fn snapshot(&self) -> SyncedStateSnapshot;
+
+  fn subscribe(&self, webview: String, channel: Channel<SyncedStateChange>) -> SyncedStateSnapshot;
+
+  /// Removes the subscription if it belongs to the given webview.
+  fn unsubscribe(&self, webview: &str, id: u32);
+
+  fn unsubscribe_webview(&self, webview: &str);
+
+  fn apply(&self, patch: json_patch::Patch, expected_version: Option<u64>) -> crate::Result<u64>;
+}
+
+impl<T: Serialize + DeserializeOwned + Send + 'static> ErasedSyncedState for SyncedState<T> {
+  fn as_any(&self) -> &dyn Any {
+    self
+  }
+
+  fn snapshot(&self) -> SyncedStateSnapshot {
+    let inner = self.inner.lock().unwrap();
+    SyncedStateSnapshot {
+      version: inner.version,
+      value: inner.snapshot.clone(),
+    }
+  }
+
+  fn subscribe(&self, webview: String, channel: Channel<SyncedStateChange>) -> SyncedStateSnapshot {
+    let mut inner = self.inner.lock().unwrap();
+    inner
+      .subscribers
+      .insert(channel.id(), Subscriber { webview, channel });
+    SyncedStateSnapshot {
+      version: inner.version,
+      value: inner.snapshot.clone(),
+    }
+  }
+
+  fn unsubscribe(&self, webview: &str, id: u32) {
+    let mut inner = self.inner.lock().unwrap();
+    if inner
+      .subscribers
+      .get(&id)
+      .is_some_and(|subscriber| subscriber.webview == webview)
+    {
+      inner.subscribers.remove(&id);
+    }
+  }
+
+  fn unsubscribe_webview(&self, webview: &str) {
+    self
+      .inner
+      .lock()
+      .unwrap()
+      .subscribers
+      .retain(|_, subscriber| subscriber.webview != webview);
+  }
+
+  fn apply(&self, patch: json_patch::Patch, expected_version: Option<u64>) -> crate::Result<u64> {
+    let (pending, version) = {
+      let mut inner = self.inner.lock().unwrap();
+
+      if let Some(expected) = expected_version {
+        if expected != inner.version {
+          return Err(crate::Error::SyncedStateVersionMismatch {
+            name: self.name.to_string(),
+            expected,
+            current: inner.version,
+          });
+        }
+      }
+
+      let mut snapshot = inner.snapshot.clone();
+      json_patch::patch(&mut snapshot, &patch)
+        .map_err(|e| crate::Error::InvalidSyncedStatePatch(self.name.to_string(), e.to_string()))?;
+      let value = serde_json::from_value(snapshot)
+        .map_err(|e| crate::Error::InvalidSyncedStatePatch(self.name.to_string(), e.to_string()))?;
+
+      inner.value = value;
+      // use the normalized value so the subscribers see what Rust actually stores
+      let snapshot = serde_json::to_value(&inner.value)?;
+      (inner.commit(snapshot), inner.version)
+    };
+    self.notify(pending);
+    Ok(version)
+  }
+}
+
+/// The registry of [`SyncedState`]s, managed by the application.
+#[derive(Default)]
+pub(crate) struct SyncedStates(Mutex<HashMap<String, Arc<dyn ErasedSyncedState>>>);
+
+impl SyncedStates {
+  pub(crate) fn insert<T: Serialize + DeserializeOwned + Send + 'static>(
+    &self,
+    state: SyncedState<T>,
+  ) -> crate::Result<()> {
+    let mut states = self.0.lock().unwrap();
+    if states.contains_key(state.name()) {
+      return Err(crate::Error::SyncedStateAlreadyManaged(
+        state.name().to_string(),
+      ));
+    }
+    states.insert(state.name().to_string(), Arc::new(state));
+    Ok(())
+  }
+
+  pub(crate) fn get(&self, name: &str) -> Option<Arc<dyn ErasedSyncedState>> {
+    self.0.lock().unwrap().get(name).cloned()
+  }
+
+  pub(crate) fn get_typed<T: Send + 'static>(&self, name: &str) -> Option<SyncedState<T>> {
+    self
+      .get(name)?
+      .as_any()
+      .downcast_ref::<SyncedState<T>>()
+      .cloned()
+  }
+
+  /// Removes the subscriptions of the given webview from all states.
+  pub(crate) fn unsubscribe_webview(&self, webview: &str) {
+    for state in self.0.lock().unwrap().values() {
+      state.unsubscribe_webview(webview);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+  struct Counter {
+    count: u32,
+    label: String,
+  }
+
+  #[test]
+  fn update_and_apply() {
+    let state = SyncedState::new(
+      "counter".into(),
+      Counter {
+        count: 0,
+        label: "clicks".into(),
+      },
+    )
+    .unwrap();
+
+    state.update(|c| c.count += 1).unwrap();
+    assert_eq!(state.version(), 1);
+
+    // no-op updates do not bump the version
+    state.update(|_| {}).unwrap();
+    assert_eq!(state.version(), 1);
+
+    let patch: json_patch::Patch =
+      serde_json::from_str(r#"[{ "op": "replace", "path": "/label", "value": "taps" }]"#).unwrap();
+    assert_eq!(state.apply(patch.clone(), Some(1)).unwrap(), 2);
+    assert_eq!(
+      state.get(),
+      Counter {
+        count: 1,
+        label: "taps".into()
+      }
+    );
+
+    // stale version
+    assert!(matches!(
+      state.apply(patch, Some(1)),
+      Err(crate::Error::SyncedStateVersionMismatch { current: 2, .. })
+    ));
+
+    // patches that produce an invalid value are rejected
+    let patch: json_patch::Patch =
+      serde_json::from_str(r#"[{ "op": "replace", "path": "/count", "value": "one" }]"#).unwrap();
+    assert!(state.apply(patch, None).is_err());
+    assert_eq!(state.snapshot().value["count"], 1);
+  }
+
+  #[test]
+  fn registry() {
+    let states = SyncedStates::default();
+    states
+      .insert(SyncedState::new("a".into(), 1u32).unwrap())
+      .unwrap();
+    assert!(states
+      .insert(SyncedState::new("a".into(), 2u32).unwrap())
+      .is_err());
+    assert_eq!(states.get_typed::<u32>("a").unwrap().get(), 1);
+    assert!(states.get_typed::<String>("a").is_none());
+    assert!(states.get("b").is_none());
+  }
+
+  #[test]
+  fn unsubscribe_checks_owner() {
+    let state = SyncedState::new("a".into(), 1u32).unwrap();
+    let channel = Channel::new(|_| Ok(()));
+    let id = channel.id();
+    state.subscribe("main".into(), channel);
+
+    // another webview cannot remove the subscription
+    state.unsubscribe("other", id);
+    assert_eq!(state.inner.lock().unwrap().subscribers.len(), 1);
+
+    state.unsubscribe("main", id);
+    assert!(state.inner.lock().unwrap().subscribers.is_empty());
+  }
+  #[test]
+  fn subscribers_are_notified_outside_the_lock() {
+    let state = SyncedState::new("a".into(), 1u32).unwrap();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let (state_, received_) = (state.clone(), received.clone());
+    // reads the state from the channel callback, which deadlocks if the lock is held while sending
+    state.subscribe(
+      "main".into(),
+      Channel::new(move |_| {
+        received_.lock().unwrap().push(state_.get());
+        Ok(())
+      }),
+    );
+    // closed webview
+    state.subscribe(
+      "closed".into(),
+      Channel::new(|_| Err(crate::Error::WebviewNotFound)),
+    );
+
+    state.set(2).unwrap();
+    assert_eq!(*received.lock().unwrap(), vec![2]);
+    assert_eq!(state.inner.lock().unwrap().subscribers.len(), 1);
+  }
+}
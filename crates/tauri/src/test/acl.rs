@@ -0,0 +1,265 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{collections::BTreeMap, str::FromStr};
+
+use tauri_utils::{
+  acl::{
+    capability::{Capability, CapabilityFile},
+    manifest::{Manifest, PermissionFile},
+    resolved::Resolved,
+    APP_ACL_KEY,
+  },
+  platform::Target,
+};
+
+use crate::{ipc::RuntimeAuthority, Assets, Context, Runtime};
+
+/// Access control list definitions for tests, replacing the ACL generated by the build script.
+///
+/// Permissions and capabilities use the same format as the files read by `tauri-build`,
+/// so a command can be checked against the real [`RuntimeAuthority`] without a build script.
+///
+/// # Panics
+///
+/// The builder methods panic if a definition cannot be parsed.
+///
+/// # Examples
+///
+/// ```rust
+/// use tauri::test::{mock_builder, mock_context_with_acl, noop_assets, MockAcl};
+///
+/// let acl = MockAcl::new()
+///   .app_permissions(r#"{ "permission": [{ "identifier": "allow-ping", "commands": { "allow": ["ping"] } }] }"#)
+///   .capability(r#"{ "identifier": "main", "windows": ["main"], "permissions": ["allow-ping"] }"#);
+///
+/// let app = mock_builder()
+///   .build(mock_context_with_acl(noop_assets(), acl))
+///   .unwrap();
+/// ```
+#[derive(Debug, Default)]
+pub struct MockAcl {
+  manifests: BTreeMap<String, Manifest>,
+  capabilities: BTreeMap<String, Capability>,
+}
+
+impl MockAcl {
+  /// Creates an empty ACL.
+  ///
+  /// Until [`Self::app_permissions`] is called, app commands are not checked against the ACL.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Defines the permissions of the app commands from a JSON permission file,
+  /// enabling the ACL checks for app commands.
+  pub fn app_permissions(self, permissions: &str) -> Self {
+    self.plugin_permissions(APP_ACL_KEY, permissions)
+  }
+
+  /// Defines the permissions of a plugin from a JSON permission file.
+  ///
+  /// Core plugins use the `core:` prefix, e.g. `core:event`.
+  pub fn plugin_permissions(self, plugin: &str, permissions: &str) -> Self {
+    let file: PermissionFile = serde_json::from_str(permissions)
+      .unwrap_or_else(|e| panic!("invalid permission file for `{plugin}`: {e}"));
+    self.plugin_manifest(plugin, Manifest::new(vec![file], None))
+  }
+
+  /// Uses the given manifest for the plugin permissions.
+  pub fn plugin_manifest(mut self, plugin: impl Into<String>, manifest: Manifest) -> Self {
+    self.manifests.insert(plugin.into(), manifest);
+    self
+  }
+
+  /// Adds a capability, or a list of capabilities, from its JSON or TOML definition.
+  pub fn capability(self, capability: &str) -> Self {
+    let capability =
+      CapabilityFile::from_str(capability).unwrap_or_else(|e| panic!("invalid capability: {e}"));
+    match capability {
+      CapabilityFile::Capability(capability) => self.add_capability(capability),
+      CapabilityFile::List(capabilities) | CapabilityFile::NamedList { capabilities } => {
+        capabilities.into_iter().fold(self, Self::add_capability)
+      }
+    }
+  }
+
+  /// Adds a capability.
+  pub fn add_capability(mut self, capability: Capability) -> Self {
+    self
+      .capabilities
+      .insert(capability.identifier.clone(), capability);
+    self
+  }
+
+  /// Resolves the ACL into a [`RuntimeAuthority`].
+  ///
+  /// # Panics
+  ///
+  /// Panics if a capability references an unknown permission.
+  pub fn build(self) -> RuntimeAuthority {
+    let resolved = Resolved::resolve(&self.manifests, self.capabilities, Target::current())
+      .unwrap_or_else(|e| panic!("failed to resolve the ACL: {e}"));
+    crate::runtime_authority!(self.manifests, resolved)
+  }
+}
+
+/// Creates a new [`crate::Context`] for testing, like [`mock_context`](super::mock_context),
+/// checking the commands against the given ACL.
+pub fn mock_context_with_acl<R: Runtime, A: Assets<R>>(assets: A, acl: MockAcl) -> Context<R> {
+  let mut context = super::mock_context(assets);
+  context.runtime_authority = acl.build();
+  context
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+  use tauri_utils::acl::scope::StringScope;
+
+  use super::{mock_context_with_acl, MockAcl};
+  use crate::{
+    ipc::{CallbackFn, CommandScope, InvokeBody},
+    plugin::{Builder as PluginBuilder, TauriPlugin},
+    test::{get_ipc_response, mock_builder, noop_assets, MockRuntime, INVOKE_KEY},
+    webview::InvokeRequest,
+    App, WebviewWindowBuilder,
+  };
+
+  #[crate::command(root = "crate")]
+  fn ping() -> &'static str {
+    "pong"
+  }
+
+  #[crate::command(root = "crate")]
+  fn read(scope: CommandScope<StringScope>, path: String) -> Result<String, String> {
+    if scope.matches(&path) {
+      Ok(path)
+    } else {
+      Err(format!("{path} is not allowed"))
+    }
+  }
+
+  fn sample_plugin() -> TauriPlugin<MockRuntime> {
+    PluginBuilder::new("sample")
+      .invoke_handler(crate::generate_handler![read])
+      .build()
+  }
+
+  fn app(acl: MockAcl) -> App<MockRuntime> {
+    mock_builder()
+      .plugin(sample_plugin())
+      .invoke_handler(crate::generate_handler![ping])
+      .build(mock_context_with_acl(noop_assets(), acl))
+      .unwrap()
+  }
+
+  fn request(cmd: &str, url: &str, body: serde_json::Value) -> InvokeRequest {
+    InvokeRequest {
+      cmd: cmd.into(),
+      callback: CallbackFn(0),
+      error: CallbackFn(1),
+      url: url.parse().unwrap(),
+      body: InvokeBody::Json(body),
+      headers: Default::default(),
+      invoke_key: INVOKE_KEY.to_string(),
+    }
+  }
+
+  const LOCAL_URL: &str = "http://tauri.localhost";
+
+  #[test]
+  fn app_command_denied_for_webview_and_origin() {
+    let app = app(
+      MockAcl::new()
+        .app_permissions(
+          r#"{ "permission": [{ "identifier": "allow-ping", "commands": { "allow": ["ping"] } }] }"#,
+        )
+        .capability(
+          r#"{ "identifier": "main", "windows": ["main"], "permissions": ["allow-ping"] }"#,
+        ),
+    );
+    let main = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    let other = WebviewWindowBuilder::new(&app, "other", Default::default())
+      .build()
+      .unwrap();
+
+    assert!(get_ipc_response(&main, request("ping", LOCAL_URL, json!({}))).is_ok());
+    assert!(get_ipc_response(&other, request("ping", LOCAL_URL, json!({}))).is_err());
+    assert!(get_ipc_response(&main, request("ping", "https://tauri.app", json!({}))).is_err());
+  }
+
+  #[test]
+  fn remote_capability() {
+    let app = app(
+      MockAcl::new()
+        .app_permissions(
+          r#"{ "permission": [{ "identifier": "allow-ping", "commands": { "allow": ["ping"] } }] }"#,
+        )
+        .capability(
+          r#"{
+            "identifier": "remote",
+            "windows": ["*"],
+            "remote": { "urls": ["https://tauri.app"] },
+            "permissions": ["allow-ping"]
+          }"#,
+        ),
+    );
+    let main = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    assert!(get_ipc_response(&main, request("ping", "https://tauri.app", json!({}))).is_ok());
+    assert!(get_ipc_response(&main, request("ping", "https://evil.com", json!({}))).is_err());
+  }
+
+  #[test]
+  fn plugin_command_scope() {
+    let app = app(
+      MockAcl::new()
+        .plugin_permissions(
+          "sample",
+          r#"{ "permission": [{ "identifier": "allow-read", "commands": { "allow": ["read"] } }] }"#,
+        )
+        .capability(
+          r#"{
+            "identifier": "main",
+            "windows": ["main"],
+            "permissions": [{ "identifier": "sample:allow-read", "allow": [{ "values": ["allowed"] }] }]
+          }"#,
+        ),
+    );
+    let main = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    let response = get_ipc_response(
+      &main,
+      request(
+        "plugin:sample|read",
+        LOCAL_URL,
+        json!({ "path": "allowed" }),
+      ),
+    );
+    assert!(response.is_ok());
+
+    let response = get_ipc_response(
+      &main,
+      request("plugin:sample|read", LOCAL_URL, json!({ "path": "secret" })),
+    );
+    assert_eq!(response.unwrap_err(), json!("secret is not allowed"));
+  }
+
+  #[test]
+  #[should_panic(expected = "failed to resolve the ACL")]
+  fn unknown_permission() {
+    MockAcl::new()
+      .capability(
+        r#"{ "identifier": "main", "windows": ["main"], "permissions": ["missing:default"] }"#,
+      )
+      .build();
+  }
+}
@@ -0,0 +1,390 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::sync::{
+  atomic::{AtomicBool, Ordering},
+  Arc, Mutex,
+};
+
+use serde_json::Value as JsonValue;
+
+use crate::EventTarget;
+
+#[cfg(desktop)]
+use std::{
+  sync::mpsc::{Receiver, RecvTimeoutError},
+  time::{Duration, Instant},
+};
+
+#[cfg(desktop)]
+use super::MockRuntime;
+#[cfg(desktop)]
+use crate::{
+  ipc::{InvokeError, InvokeResponse, InvokeResponseBody},
+  webview::InvokeRequest,
+  App, Manager, Webview,
+};
+
+/// An event emitted to a target, as recorded by the [`EventLoopHarness`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct EmittedEvent {
+  /// The event name.
+  pub event: String,
+  /// The event payload.
+  pub payload: JsonValue,
+  /// The target the event was delivered to.
+  ///
+  /// Events emitted with [`Emitter::emit`](crate::Emitter::emit) are recorded once with [`EventTarget::Any`],
+  /// filtered events are recorded once per existing target they match.
+  pub target: EventTarget,
+}
+
+/// An entry of the timeline recorded by the [`EventLoopHarness`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TimelineEntry {
+  /// An event was emitted.
+  Event(EmittedEvent),
+  /// A script was evaluated in a webview.
+  Eval {
+    /// The webview label.
+    webview: String,
+    /// The evaluated script.
+    script: String,
+  },
+  /// A command awaited with [`EventLoopHarness::wait`] completed.
+  CommandResponse {
+    /// The label of the webview that invoked the command.
+    webview: String,
+    /// The command name.
+    cmd: String,
+    /// The command response.
+    response: Result<JsonValue, JsonValue>,
+  },
+}
+
+/// The timeline shared by the harness, the app manager and the mock runtime.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Timeline {
+  enabled: Arc<AtomicBool>,
+  entries: Arc<Mutex<Vec<TimelineEntry>>>,
+}
+
+impl Timeline {
+  fn push(&self, entry: TimelineEntry) {
+    if self.enabled.load(Ordering::Relaxed) {
+      self.entries.lock().unwrap().push(entry);
+    }
+  }
+
+  pub(crate) fn record_event(&self, event: &str, payload: &str, target: EventTarget) {
+    self.push(TimelineEntry::Event(EmittedEvent {
+      event: event.into(),
+      payload: serde_json::from_str(payload).unwrap_or(JsonValue::Null),
+      target,
+    }));
+  }
+
+  pub(crate) fn record_eval(&self, webview: &str, script: &str) {
+    self.push(TimelineEntry::Eval {
+      webview: webview.into(),
+      script: script.into(),
+    });
+  }
+}
+
+/// A command invoked with [`EventLoopHarness::invoke`] that did not complete yet.
+#[cfg(desktop)]
+#[derive(Debug)]
+pub struct PendingInvoke {
+  webview: String,
+  cmd: String,
+  rx: Receiver<InvokeResponse>,
+}
+
+#[cfg(desktop)]
+impl PendingInvoke {
+  /// The name of the pending command.
+  pub fn cmd(&self) -> &str {
+    &self.cmd
+  }
+}
+
+/// Drives the event loop of an [`App`] built with the [`MockRuntime`] step by step,
+/// recording the emitted events, the evaluated scripts and the command responses on a timeline.
+///
+/// # Examples
+///
+/// ```rust
+/// use tauri::{test::{mock_app, EventLoopHarness}, Emitter, EventTarget};
+///
+/// let mut harness = EventLoopHarness::new(mock_app());
+/// let webview = tauri::WebviewWindowBuilder::new(harness.app(), "main", Default::default()).build().unwrap();
+///
+/// harness.app().emit_to("main", "ready", 1).unwrap();
+/// harness.step();
+///
+/// let events = harness.events_to(&EventTarget::webview_window("main"));
+/// assert_eq!(events.len(), 1);
+/// assert_eq!(events[0].payload, 1);
+/// ```
+#[cfg(desktop)]
+pub struct EventLoopHarness {
+  app: App<MockRuntime>,
+  timeline: Timeline,
+}
+
+#[cfg(desktop)]
+impl EventLoopHarness {
+  /// How long [`Self::wait`] runs the event loop before giving up.
+  pub const WAIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+  /// Starts recording the timeline of the given app.
+  pub fn new(app: App<MockRuntime>) -> Self {
+    let timeline = app.handle().runtime_handle.timeline().clone();
+    timeline.enabled.store(true, Ordering::Relaxed);
+    app.manage(timeline.clone());
+    Self { app, timeline }
+  }
+
+  /// The app driven by this harness.
+  pub fn app(&self) -> &App<MockRuntime> {
+    &self.app
+  }
+
+  /// Runs a single iteration of the event loop,
+  /// handling the queued messages and user events.
+  pub fn step(&mut self) {
+    #[allow(deprecated)]
+    self.app.run_iteration(|_, _| {});
+  }
+
+  /// Runs the event loop until the condition holds, for at most `max_steps` iterations.
+  ///
+  /// Returns whether the condition holds.
+  pub fn step_until<F: FnMut(&Self) -> bool>(
+    &mut self,
+    max_steps: usize,
+    mut condition: F,
+  ) -> bool {
+    for _ in 0..max_steps {
+      if condition(self) {
+        return true;
+      }
+      self.step();
+    }
+    condition(self)
+  }
+
+  /// Invokes a command from the given webview without waiting for it to complete.
+  ///
+  /// Use [`Self::wait`] to run the event loop until the command responds.
+  pub fn invoke<W: AsRef<Webview<MockRuntime>>>(
+    &mut self,
+    webview: &W,
+    request: InvokeRequest,
+  ) -> PendingInvoke {
+    let webview = webview.as_ref();
+    let (tx, rx) = std::sync::mpsc::channel();
+    let pending = PendingInvoke {
+      webview: webview.label().into(),
+      cmd: request.cmd.clone(),
+      rx,
+    };
+    webview.clone().on_message(
+      request,
+      Box::new(move |_webview, _cmd, response, _callback, _error| {
+        let _ = tx.send(response);
+      }),
+    );
+    pending
+  }
+
+  /// Runs the event loop until the command completes and returns its response.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the command does not complete in [`Self::WAIT_TIMEOUT`].
+  pub fn wait(&mut self, pending: PendingInvoke) -> Result<InvokeResponseBody, JsonValue> {
+    let deadline = Instant::now() + Self::WAIT_TIMEOUT;
+    let response = loop {
+      self.step();
+      match pending.rx.recv_timeout(Duration::from_millis(10)) {
+        Ok(response) => break response,
+        Err(RecvTimeoutError::Timeout) if Instant::now() < deadline => continue,
+        Err(_) => panic!(
+          "command `{}` did not complete in {:?}",
+          pending.cmd,
+          Self::WAIT_TIMEOUT
+        ),
+      }
+    };
+
+    let response = match response {
+      InvokeResponse::Ok(body) => Ok(body),
+      InvokeResponse::Err(InvokeError(value)) => Err(value),
+    };
+    self.timeline.push(TimelineEntry::CommandResponse {
+      webview: pending.webview,
+      cmd: pending.cmd,
+      response: match &response {
+        Ok(InvokeResponseBody::Json(json)) => Ok(serde_json::from_str(json).unwrap_or_default()),
+        Ok(InvokeResponseBody::Raw(bytes)) => Ok(bytes.clone().into()),
+        Err(value) => Err(value.clone()),
+      },
+    });
+    response
+  }
+
+  /// Invokes a command and runs the event loop until it completes.
+  pub fn invoke_and_wait<W: AsRef<Webview<MockRuntime>>>(
+    &mut self,
+    webview: &W,
+    request: InvokeRequest,
+  ) -> Result<InvokeResponseBody, JsonValue> {
+    let pending = self.invoke(webview, request);
+    self.wait(pending)
+  }
+
+  /// The recorded timeline, in order.
+  pub fn timeline(&self) -> Vec<TimelineEntry> {
+    self.timeline.entries.lock().unwrap().clone()
+  }
+
+  /// Takes the recorded timeline, starting a new one.
+  pub fn take_timeline(&self) -> Vec<TimelineEntry> {
+    std::mem::take(&mut *self.timeline.entries.lock().unwrap())
+  }
+
+  /// The recorded events emitted to the given target,
+  /// including the events emitted to [`EventTarget::Any`].
+  pub fn events_to(&self, target: &EventTarget) -> Vec<EmittedEvent> {
+    self
+      .timeline()
+      .into_iter()
+      .filter_map(|entry| match entry {
+        TimelineEntry::Event(event)
+          if event.target == *target || event.target == EventTarget::Any =>
+        {
+          Some(event)
+        }
+        _ => None,
+      })
+      .collect()
+  }
+
+  /// The recorded scripts evaluated in the webview with the given label.
+  pub fn scripts(&self, webview: &str) -> Vec<String> {
+    self
+      .timeline()
+      .into_iter()
+      .filter_map(|entry| match entry {
+        TimelineEntry::Eval {
+          webview: label,
+          script,
+        } if label == webview => Some(script),
+        _ => None,
+      })
+      .collect()
+  }
+}
+
+#[cfg(all(test, desktop))]
+mod tests {
+  use serde_json::json;
+
+  use super::{EventLoopHarness, TimelineEntry};
+  use crate::{
+    ipc::{CallbackFn, InvokeBody},
+    test::{mock_builder, mock_context, noop_assets, INVOKE_KEY},
+    webview::InvokeRequest,
+    Emitter, EventTarget,
+  };
+
+  #[crate::command(root = "crate")]
+  async fn greet(name: String) -> String {
+    format!("Hello, {name}!")
+  }
+
+  fn request(cmd: &str, body: serde_json::Value) -> InvokeRequest {
+    InvokeRequest {
+      cmd: cmd.into(),
+      callback: CallbackFn(0),
+      error: CallbackFn(1),
+      url: "http://tauri.localhost".parse().unwrap(),
+      body: InvokeBody::Json(body),
+      headers: Default::default(),
+      invoke_key: INVOKE_KEY.to_string(),
+    }
+  }
+
+  #[test]
+  fn records_events_per_target() {
+    let mut harness = EventLoopHarness::new(crate::test::mock_app());
+    let main = crate::WebviewWindowBuilder::new(harness.app(), "main", Default::default())
+      .build()
+      .unwrap();
+    crate::WebviewWindowBuilder::new(harness.app(), "other", Default::default())
+      .build()
+      .unwrap();
+    harness.app().emit("ping", "all").unwrap();
+    harness.app().emit_to("main", "ping", "main").unwrap();
+    harness.step();
+
+    let main_events = harness.events_to(&EventTarget::webview_window("main"));
+    assert_eq!(
+      main_events
+        .iter()
+        .map(|e| e.payload.clone())
+        .collect::<Vec<_>>(),
+      vec![json!("all"), json!("main")]
+    );
+    let other_events = harness.events_to(&EventTarget::webview_window("other"));
+    assert_eq!(other_events.len(), 1);
+    assert_eq!(other_events[0].target, EventTarget::Any);
+
+    main.eval("console.log('main')").unwrap();
+    assert_eq!(harness.scripts("main"), vec!["console.log('main')"]);
+    assert!(harness.scripts("other").is_empty());
+
+    assert!(!harness.take_timeline().is_empty());
+    assert!(harness.timeline().is_empty());
+  }
+
+  #[test]
+  fn waits_for_async_commands() {
+    let app = mock_builder()
+      .invoke_handler(crate::generate_handler![greet])
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let mut harness = EventLoopHarness::new(app);
+    let webview = crate::WebviewWindowBuilder::new(harness.app(), "main", Default::default())
+      .build()
+      .unwrap();
+
+    let pending = harness.invoke(&webview, request("greet", json!({ "name": "Tauri" })));
+    assert_eq!(pending.cmd(), "greet");
+    let response = harness
+      .wait(pending)
+      .map(|body| body.deserialize::<String>().unwrap());
+    assert_eq!(response, Ok("Hello, Tauri!".into()));
+
+    let error = harness.invoke_and_wait(&webview, request("greet", json!({})));
+    assert!(error.is_err());
+
+    let responses = harness
+      .timeline()
+      .into_iter()
+      .filter_map(|entry| match entry {
+        TimelineEntry::CommandResponse { cmd, response, .. } => Some((cmd, response.is_ok())),
+        _ => None,
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(
+      responses,
+      vec![("greet".to_string(), true), ("greet".to_string(), false)]
+    );
+  }
+}
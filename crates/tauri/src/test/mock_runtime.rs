@@ -6,16 +6,16 @@
 #![allow(missing_docs)]
 
 use tauri_runtime::{
-  dpi::{PhysicalPosition, PhysicalSize, Position, Size},
+  dpi::{PhysicalPosition, PhysicalRect, PhysicalSize, Position, Rect, Size},
   monitor::Monitor,
   webview::{DetachedWebview, PendingWebview},
   window::{
-    CursorIcon, DetachedWindow, DetachedWindowWebview, PendingWindow, RawWindow, WindowBuilder,
-    WindowBuilderBase, WindowEvent, WindowId,
+    CursorIcon, DetachedWindow, DetachedWindowWebview, PendingWindow, RawWindow, WebviewEvent,
+    WindowBuilder, WindowBuilderBase, WindowEvent, WindowId,
   },
   DeviceEventFilter, Error, EventLoopProxy, ExitRequestedEventAction, Icon, ProgressBarState,
   Result, RunEvent, Runtime, RuntimeHandle, RuntimeInitArgs, UserAttentionType, UserEvent,
-  WebviewDispatch, WindowDispatch, WindowEventId,
+  WebviewDispatch, WebviewEventId, WindowDispatch, WindowEventId,
 };
 
 #[cfg(target_os = "macos")]
@@ -23,18 +23,21 @@ use tauri_utils::TitleBarStyle;
 use tauri_utils::{config::WindowConfig, Theme};
 use url::Url;
 
+use super::Timeline;
+
 #[cfg(windows)]
 use windows::Win32::Foundation::HWND;
 
 use std::{
-  cell::RefCell,
+  any::Any,
   collections::HashMap,
   fmt,
   sync::{
     atomic::{AtomicBool, AtomicU32, Ordering},
-    mpsc::{channel, sync_channel, Receiver, SyncSender},
+    mpsc::{channel, Receiver, Sender},
     Arc, Mutex,
   },
+  time::Duration,
 };
 
 type ShortcutMap = HashMap<String, Box<dyn Fn() + Send + 'static>>;
@@ -43,50 +46,451 @@ enum Message {
   Task(Box<dyn FnOnce() + Send>),
   CloseWindow(WindowId),
   DestroyWindow(WindowId),
+  WindowEvent(WindowId, WindowEvent),
+  WebviewEvent(String, EventListeners<WebviewEvent>, WebviewEvent),
+  UserEvent(Box<dyn Any + Send>),
+  RequestExit(i32),
+}
+
+type EventListener<E> = Arc<Mutex<Box<dyn Fn(&E) + Send>>>;
+
+/// Event listeners registered with `on_window_event` and `on_webview_event`.
+struct EventListeners<E>(Arc<Mutex<HashMap<u32, EventListener<E>>>>);
+
+impl<E> Default for EventListeners<E> {
+  fn default() -> Self {
+    Self(Default::default())
+  }
 }
 
-struct Webview;
+impl<E> Clone for EventListeners<E> {
+  fn clone(&self) -> Self {
+    Self(self.0.clone())
+  }
+}
+
+impl<E> fmt::Debug for EventListeners<E> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("EventListeners").finish_non_exhaustive()
+  }
+}
+
+impl<E> EventListeners<E> {
+  fn add<F: Fn(&E) + Send + 'static>(&self, id: u32, f: F) {
+    self
+      .0
+      .lock()
+      .unwrap()
+      .insert(id, Arc::new(Mutex::new(Box::new(f))));
+  }
+
+  fn notify(&self, event: &E) {
+    // the listeners are called outside the map lock so they can register listeners
+    // or trigger events delivered to the other listeners
+    let listeners = self.0.lock().unwrap().values().cloned().collect::<Vec<_>>();
+    for listener in listeners {
+      (listener.lock().unwrap())(event);
+    }
+  }
+}
+
+/// The simulated state of a window created by the [`MockRuntime`].
+///
+/// The mock runtime uses a scale factor of `1.0` by default, so logical and physical units match.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct MockWindowState {
+  pub title: String,
+  pub position: PhysicalPosition<i32>,
+  pub size: PhysicalSize<u32>,
+  pub min_size: Option<PhysicalSize<u32>>,
+  pub max_size: Option<PhysicalSize<u32>>,
+  pub scale_factor: f64,
+  pub visible: bool,
+  pub focused: bool,
+  pub focusable: bool,
+  pub minimized: bool,
+  pub maximized: bool,
+  pub fullscreen: bool,
+  pub resizable: bool,
+  pub maximizable: bool,
+  pub minimizable: bool,
+  pub closable: bool,
+  pub decorated: bool,
+  pub always_on_top: bool,
+  pub enabled: bool,
+  /// The window theme override. `None` follows the application theme.
+  pub theme: Option<Theme>,
+}
+
+impl Default for MockWindowState {
+  fn default() -> Self {
+    Self {
+      title: String::new(),
+      position: PhysicalPosition::new(0, 0),
+      size: PhysicalSize::new(800, 600),
+      min_size: None,
+      max_size: None,
+      scale_factor: 1.0,
+      visible: true,
+      focused: true,
+      focusable: true,
+      minimized: false,
+      maximized: false,
+      fullscreen: false,
+      resizable: true,
+      maximizable: true,
+      minimizable: true,
+      closable: true,
+      decorated: true,
+      always_on_top: false,
+      enabled: true,
+      theme: None,
+    }
+  }
+}
+
+impl MockWindowState {
+  fn clamp_size(&self, size: PhysicalSize<u32>) -> PhysicalSize<u32> {
+    let (mut width, mut height) = (size.width, size.height);
+    if let Some(min) = self.min_size {
+      width = width.max(min.width);
+      height = height.max(min.height);
+    }
+    if let Some(max) = self.max_size {
+      width = width.min(max.width);
+      height = height.min(max.height);
+    }
+    PhysicalSize::new(width, height)
+  }
+
+  /// Applies an event injected with [`MockWindowDispatcher::emit_event`].
+  fn apply(&mut self, event: &WindowEvent) {
+    match event {
+      WindowEvent::Resized(size) => self.size = *size,
+      WindowEvent::Moved(position) => self.position = *position,
+      WindowEvent::Focused(focused) => self.focused = *focused,
+      WindowEvent::ScaleFactorChanged {
+        scale_factor,
+        new_inner_size,
+      } => {
+        self.scale_factor = *scale_factor;
+        self.size = *new_inner_size;
+      }
+      _ => {}
+    }
+  }
+}
+
+/// The simulated state of a webview created by the [`MockRuntime`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct MockWebviewState {
+  pub url: String,
+  pub position: PhysicalPosition<i32>,
+  pub size: PhysicalSize<u32>,
+  pub visible: bool,
+  pub focused: bool,
+  pub zoom: f64,
+  pub auto_resize: bool,
+  pub devtools_open: bool,
+}
+
+/// The single monitor simulated by the [`MockRuntime`].
+fn mock_monitor() -> Monitor {
+  let size = PhysicalSize::new(1920, 1080);
+  Monitor {
+    name: Some("Mock Monitor".into()),
+    size,
+    position: PhysicalPosition::new(0, 0),
+    work_area: PhysicalRect {
+      position: PhysicalPosition::new(0, 0),
+      size,
+    },
+    scale_factor: 1.0,
+  }
+}
+
+fn mock_monitor_from_point(x: f64, y: f64) -> Option<Monitor> {
+  let monitor = mock_monitor();
+  let (left, top) = (monitor.position.x as f64, monitor.position.y as f64);
+  let contains = x >= left
+    && y >= top
+    && x < left + monitor.size.width as f64
+    && y < top + monitor.size.height as f64;
+  contains.then_some(monitor)
+}
 
 struct Window {
   label: String,
-  webviews: Vec<Webview>,
+  state: Arc<Mutex<MockWindowState>>,
+  listeners: EventListeners<WindowEvent>,
+  webviews: Vec<u32>,
 }
 
 #[derive(Clone)]
 pub struct RuntimeContext {
   is_running: Arc<AtomicBool>,
-  windows: Arc<RefCell<HashMap<WindowId, Window>>>,
+  windows: Arc<Mutex<HashMap<WindowId, Window>>>,
+  theme: Arc<Mutex<Option<Theme>>>,
   shortcuts: Arc<Mutex<ShortcutMap>>,
-  run_tx: SyncSender<Message>,
+  run_tx: Sender<Message>,
+  timeline: Timeline,
   next_window_id: Arc<AtomicU32>,
   next_webview_id: Arc<AtomicU32>,
   next_window_event_id: Arc<AtomicU32>,
   next_webview_event_id: Arc<AtomicU32>,
 }
 
-// SAFETY: we ensure this type is only used on the main thread.
-#[allow(clippy::non_send_fields_in_send_ty)]
-unsafe impl Send for RuntimeContext {}
-
-// SAFETY: we ensure this type is only used on the main thread.
-#[allow(clippy::non_send_fields_in_send_ty)]
-unsafe impl Sync for RuntimeContext {}
-
 impl RuntimeContext {
   fn send_message(&self, message: Message) -> Result<()> {
-    if self.is_running.load(Ordering::Relaxed) {
+    // user events are only delivered by the event loop, so they are queued until it runs
+    if self.is_running.load(Ordering::Relaxed) || matches!(message, Message::UserEvent(_)) {
       self
         .run_tx
         .send(message)
         .map_err(|_| Error::FailedToSendMessage)
     } else {
-      match message {
-        Message::Task(task) => task(),
-        Message::CloseWindow(id) | Message::DestroyWindow(id) => {
-          self.windows.borrow_mut().remove(&id);
+      // there's no run event callback before the event loop runs
+      self.handle_message::<()>(message, &mut |_| {});
+      Ok(())
+    }
+  }
+
+  /// Handles a message, returning `true` if the event loop must exit.
+  fn handle_message<T: UserEvent>(
+    &self,
+    message: Message,
+    callback: &mut dyn FnMut(RunEvent<T>),
+  ) -> bool {
+    match message {
+      Message::Task(task) => task(),
+      Message::CloseWindow(id) => {
+        let Some((label, listeners)) = self.window_listeners(id) else {
+          return false;
+        };
+        let (tx, rx) = channel();
+        listeners.notify(&WindowEvent::CloseRequested {
+          signal_tx: tx.clone(),
+        });
+        callback(RunEvent::WindowEvent {
+          label,
+          event: WindowEvent::CloseRequested { signal_tx: tx },
+        });
+
+        let should_prevent = matches!(rx.try_recv(), Ok(true));
+        if !should_prevent {
+          return self.destroy_window(id, callback);
         }
       }
-      Ok(())
+      Message::DestroyWindow(id) => return self.destroy_window(id, callback),
+      Message::WindowEvent(id, event) => {
+        if let Some((label, listeners)) = self.window_listeners(id) {
+          listeners.notify(&event);
+          callback(RunEvent::WindowEvent { label, event });
+        }
+      }
+      Message::WebviewEvent(label, listeners, event) => {
+        listeners.notify(&event);
+        callback(RunEvent::WebviewEvent { label, event });
+      }
+      Message::UserEvent(event) => {
+        if let Ok(event) = event.downcast::<T>() {
+          callback(RunEvent::UserEvent(*event));
+        }
+      }
+      Message::RequestExit(code) => return self.request_exit(Some(code), callback),
+    }
+    false
+  }
+
+  fn destroy_window<T: UserEvent>(
+    &self,
+    id: WindowId,
+    callback: &mut dyn FnMut(RunEvent<T>),
+  ) -> bool {
+    let window = self.windows.lock().unwrap().remove(&id);
+    let Some(window) = window else {
+      return false;
+    };
+
+    window.listeners.notify(&WindowEvent::Destroyed);
+    callback(RunEvent::WindowEvent {
+      label: window.label,
+      event: WindowEvent::Destroyed,
+    });
+
+    let is_empty = self.windows.lock().unwrap().is_empty();
+    is_empty && self.request_exit(None, callback)
+  }
+
+  fn request_exit<T: UserEvent>(
+    &self,
+    code: Option<i32>,
+    callback: &mut dyn FnMut(RunEvent<T>),
+  ) -> bool {
+    let (tx, rx) = channel();
+    callback(RunEvent::ExitRequested { code, tx });
+    !matches!(rx.try_recv(), Ok(ExitRequestedEventAction::Prevent))
+  }
+
+  fn window_listeners(&self, id: WindowId) -> Option<(String, EventListeners<WindowEvent>)> {
+    self
+      .windows
+      .lock()
+      .unwrap()
+      .get(&id)
+      .map(|w| (w.label.clone(), w.listeners.clone()))
+  }
+
+  fn emit_window_event(&self, id: WindowId, event: WindowEvent) -> Result<()> {
+    self.send_message(Message::WindowEvent(id, event))
+  }
+
+  /// Focuses the given window, blurring the previously focused one.
+  fn focus_window(&self, id: WindowId) -> Result<()> {
+    let mut events = Vec::new();
+    for (window_id, window) in self.windows.lock().unwrap().iter() {
+      let mut state = window.state.lock().unwrap();
+      let focused = *window_id == id;
+      if state.focused != focused {
+        state.focused = focused;
+        events.push((*window_id, focused));
+      }
+    }
+    // blur events come first
+    events.sort_by_key(|(_, focused)| *focused);
+    for (id, focused) in events {
+      self.emit_window_event(id, WindowEvent::Focused(focused))?;
+    }
+    Ok(())
+  }
+
+  fn theme(&self) -> Theme {
+    self.theme.lock().unwrap().unwrap_or(Theme::Light)
+  }
+
+  fn set_theme(&self, theme: Option<Theme>) {
+    let previous = std::mem::replace(&mut *self.theme.lock().unwrap(), theme);
+    let current = theme.unwrap_or(Theme::Light);
+    if previous.unwrap_or(Theme::Light) == current {
+      return;
+    }
+
+    let following_app_theme = self
+      .windows
+      .lock()
+      .unwrap()
+      .iter()
+      .filter(|(_, w)| w.state.lock().unwrap().theme.is_none())
+      .map(|(id, _)| *id)
+      .collect::<Vec<_>>();
+    for id in following_app_theme {
+      let _ = self.emit_window_event(id, WindowEvent::ThemeChanged(current));
+    }
+  }
+
+  fn create_window<T: UserEvent>(
+    &self,
+    pending: PendingWindow<T, MockRuntime>,
+  ) -> Result<DetachedWindow<T, MockRuntime>> {
+    let id = self.next_window_id();
+
+    let MockWindowBuilder { mut state, center } = pending.window_builder;
+    if center {
+      let monitor = mock_monitor();
+      state.position = PhysicalPosition::new(
+        (monitor.size.width as i32 - state.size.width as i32) / 2,
+        (monitor.size.height as i32 - state.size.height as i32) / 2,
+      );
+    }
+    state.size = state.clamp_size(state.size);
+    let focus = state.focused && state.focusable && state.visible;
+    state.focused = false;
+
+    let state = Arc::new(Mutex::new(state));
+    let listeners = EventListeners::default();
+    self.windows.lock().unwrap().insert(
+      id,
+      Window {
+        label: pending.label.clone(),
+        state: state.clone(),
+        listeners: listeners.clone(),
+        webviews: Vec::new(),
+      },
+    );
+
+    if focus {
+      self.focus_window(id)?;
+    }
+
+    let webview = pending.webview.map(|pending| {
+      let use_https_scheme = pending.webview_attributes.use_https_scheme;
+      DetachedWindowWebview {
+        webview: self.create_webview(id, pending),
+        use_https_scheme,
+      }
+    });
+
+    Ok(DetachedWindow {
+      id,
+      label: pending.label,
+      dispatcher: MockWindowDispatcher {
+        id,
+        context: self.clone(),
+        state,
+        listeners,
+      },
+      webview,
+    })
+  }
+
+  fn create_webview<T: UserEvent>(
+    &self,
+    window_id: WindowId,
+    pending: PendingWebview<T, MockRuntime>,
+  ) -> DetachedWebview<T, MockRuntime> {
+    let id = self.next_webview_id();
+
+    let mut windows = self.windows.lock().unwrap();
+    let window = windows.get_mut(&window_id);
+    let (position, size) = match (pending.webview_attributes.bounds, &window) {
+      (Some(bounds), _) => (
+        bounds.position.to_physical::<i32>(1.0),
+        bounds.size.to_physical::<u32>(1.0),
+      ),
+      (None, Some(window)) => (
+        PhysicalPosition::new(0, 0),
+        window.state.lock().unwrap().size,
+      ),
+      (None, None) => (PhysicalPosition::new(0, 0), PhysicalSize::new(0, 0)),
+    };
+    if let Some(window) = window {
+      window.webviews.push(id);
+    }
+    drop(windows);
+
+    let state = MockWebviewState {
+      url: pending.url,
+      position,
+      size,
+      visible: true,
+      focused: pending.webview_attributes.focus,
+      zoom: 1.0,
+      auto_resize: pending.webview_attributes.auto_resize,
+      devtools_open: false,
+    };
+
+    DetachedWebview {
+      label: pending.label.clone(),
+      dispatcher: MockWebviewDispatcher {
+        id,
+        label: pending.label,
+        context: self.clone(),
+        state: Arc::new(Mutex::new(state)),
+        listeners: Default::default(),
+        evaluated_scripts: Default::default(),
+      },
     }
   }
 
@@ -102,7 +506,7 @@ impl RuntimeContext {
     self.next_window_event_id.fetch_add(1, Ordering::Relaxed)
   }
 
-  fn next_webview_event_id(&self) -> WindowEventId {
+  fn next_webview_event_id(&self) -> WebviewEventId {
     self.next_webview_event_id.fetch_add(1, Ordering::Relaxed)
   }
 }
@@ -118,11 +522,19 @@ pub struct MockRuntimeHandle {
   context: RuntimeContext,
 }
 
+impl MockRuntimeHandle {
+  pub(crate) fn timeline(&self) -> &Timeline {
+    &self.context.timeline
+  }
+}
+
 impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
   type Runtime = MockRuntime;
 
   fn create_proxy(&self) -> EventProxy {
-    EventProxy {}
+    EventProxy {
+      context: self.context.clone(),
+    }
   }
 
   #[cfg(target_os = "macos")]
@@ -141,7 +553,7 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
   }
 
   fn request_exit(&self, code: i32) -> Result<()> {
-    unimplemented!()
+    self.context.send_message(Message::RequestExit(code))
   }
 
   /// Create a new webview window.
@@ -150,44 +562,7 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
     pending: PendingWindow<T, Self::Runtime>,
     _after_window_creation: Option<F>,
   ) -> Result<DetachedWindow<T, Self::Runtime>> {
-    let id = self.context.next_window_id();
-
-    let (webview_id, webviews) = if let Some(w) = &pending.webview {
-      (Some(self.context.next_webview_id()), vec![Webview])
-    } else {
-      (None, Vec::new())
-    };
-
-    self.context.windows.borrow_mut().insert(
-      id,
-      Window {
-        label: pending.label.clone(),
-        webviews,
-      },
-    );
-
-    let webview = webview_id.map(|id| DetachedWindowWebview {
-      webview: DetachedWebview {
-        label: pending.label.clone(),
-        dispatcher: MockWebviewDispatcher {
-          id,
-          context: self.context.clone(),
-          url: Arc::new(Mutex::new(pending.webview.unwrap().url)),
-          last_evaluated_script: Default::default(),
-        },
-      },
-      use_https_scheme: false,
-    });
-
-    Ok(DetachedWindow {
-      id,
-      label: pending.label,
-      dispatcher: MockWindowDispatcher {
-        id,
-        context: self.context.clone(),
-      },
-      webview,
-    })
+    self.context.create_window(pending)
   }
 
   fn create_webview(
@@ -195,21 +570,7 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
     window_id: WindowId,
     pending: PendingWebview<T, Self::Runtime>,
   ) -> Result<DetachedWebview<T, Self::Runtime>> {
-    let id = self.context.next_webview_id();
-    let webview = Webview;
-    if let Some(w) = self.context.windows.borrow_mut().get_mut(&window_id) {
-      w.webviews.push(webview);
-    }
-
-    Ok(DetachedWebview {
-      label: pending.label,
-      dispatcher: MockWebviewDispatcher {
-        id,
-        context: self.context.clone(),
-        last_evaluated_script: Default::default(),
-        url: Arc::new(Mutex::new(pending.url)),
-      },
-    })
+    Ok(self.context.create_webview(window_id, pending))
   }
 
   /// Run a task on the main thread.
@@ -243,19 +604,19 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
   }
 
   fn primary_monitor(&self) -> Option<Monitor> {
-    unimplemented!()
+    Some(mock_monitor())
   }
 
   fn monitor_from_point(&self, x: f64, y: f64) -> Option<Monitor> {
-    unimplemented!()
+    mock_monitor_from_point(x, y)
   }
 
   fn available_monitors(&self) -> Vec<Monitor> {
-    unimplemented!()
+    vec![mock_monitor()]
   }
 
   fn set_theme(&self, theme: Option<Theme>) {
-    unimplemented!()
+    self.context.set_theme(theme);
   }
 
   /// Shows the application, but does not automatically focus it.
@@ -277,11 +638,13 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
   #[cfg(target_os = "android")]
   fn find_class<'a>(
     &self,
-    env: &mut jni::JNIEnv<'a>,
-    activity: &jni::objects::JObject<'_>,
-    name: impl Into<String>,
+    _env: &mut jni::JNIEnv<'a>,
+    _activity: &jni::objects::JObject<'_>,
+    _name: impl Into<String>,
   ) -> std::result::Result<jni::objects::JClass<'a>, jni::errors::Error> {
-    todo!()
+    Err(jni::errors::Error::NullPtr(
+      "the MockRuntime does not have an Android context",
+    ))
   }
 
   #[cfg(target_os = "android")]
@@ -289,7 +652,8 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
   where
     F: FnOnce(&mut jni::JNIEnv, &jni::objects::JObject, &jni::objects::JObject) + Send + 'static,
   {
-    todo!()
+    // the MockRuntime does not have an Android context to run `f` on
+    drop(f);
   }
 
   #[cfg(any(target_os = "macos", target_os = "ios"))]
@@ -297,7 +661,8 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
     &self,
     cb: F,
   ) -> Result<()> {
-    todo!()
+    cb(Vec::new());
+    Ok(())
   }
 
   #[cfg(any(target_os = "macos", target_os = "ios"))]
@@ -306,7 +671,8 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
     uuid: [u8; 16],
     cb: F,
   ) -> Result<()> {
-    todo!()
+    cb(Ok(()));
+    Ok(())
   }
 
   fn cursor_position(&self) -> Result<PhysicalPosition<f64>> {
@@ -317,14 +683,46 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
 #[derive(Debug, Clone)]
 pub struct MockWebviewDispatcher {
   id: u32,
+  label: String,
   context: RuntimeContext,
-  url: Arc<Mutex<String>>,
-  last_evaluated_script: Arc<Mutex<Option<String>>>,
+  state: Arc<Mutex<MockWebviewState>>,
+  listeners: EventListeners<WebviewEvent>,
+  evaluated_scripts: Arc<Mutex<Vec<String>>>,
 }
 
 impl MockWebviewDispatcher {
+  /// The current simulated state of the webview.
+  pub fn state(&self) -> MockWebviewState {
+    self.state.lock().unwrap().clone()
+  }
+
+  /// The scripts evaluated on this webview, in order.
+  pub fn evaluated_scripts(&self) -> Vec<String> {
+    self.evaluated_scripts.lock().unwrap().clone()
+  }
+
   pub fn last_evaluated_script(&self) -> Option<String> {
-    self.last_evaluated_script.lock().unwrap().clone()
+    self.evaluated_scripts.lock().unwrap().last().cloned()
+  }
+
+  /// Clears the recorded evaluated scripts.
+  pub fn clear_evaluated_scripts(&self) {
+    self.evaluated_scripts.lock().unwrap().clear();
+  }
+
+  /// Sends a synthetic event to the webview event listeners and the run event callback.
+  pub fn emit_event(&self, event: WebviewEvent) -> Result<()> {
+    self.context.send_message(Message::WebviewEvent(
+      self.label.clone(),
+      self.listeners.clone(),
+      event,
+    ))
+  }
+
+  fn remove_from_windows(&self) {
+    for window in self.context.windows.lock().unwrap().values_mut() {
+      window.webviews.retain(|id| *id != self.id);
+    }
   }
 }
 
@@ -332,39 +730,124 @@ impl MockWebviewDispatcher {
 pub struct MockWindowDispatcher {
   id: WindowId,
   context: RuntimeContext,
+  state: Arc<Mutex<MockWindowState>>,
+  listeners: EventListeners<WindowEvent>,
+}
+
+impl MockWindowDispatcher {
+  /// The current simulated state of the window.
+  pub fn state(&self) -> MockWindowState {
+    self.state.lock().unwrap().clone()
+  }
+
+  /// Sends a synthetic event to the window event listeners and the run event callback.
+  ///
+  /// Resize, move, focus and scale factor events also update the simulated window state.
+  pub fn emit_event(&self, event: WindowEvent) -> Result<()> {
+    self.state.lock().unwrap().apply(&event);
+    self.context.emit_window_event(self.id, event)
+  }
+
+  /// Updates the window state and emits the returned event, if any.
+  fn update<F: FnOnce(&mut MockWindowState) -> Option<WindowEvent>>(&self, f: F) -> Result<()> {
+    let event = f(&mut self.state.lock().unwrap());
+    match event {
+      Some(event) => self.context.emit_window_event(self.id, event),
+      None => Ok(()),
+    }
+  }
+
+  fn resize(&self, size: PhysicalSize<u32>) -> Result<()> {
+    self.update(|state| {
+      let size = state.clamp_size(size);
+      (state.size != size).then(|| {
+        state.size = size;
+        WindowEvent::Resized(size)
+      })
+    })
+  }
+
+  fn move_to(&self, position: PhysicalPosition<i32>) -> Result<()> {
+    self.update(|state| {
+      (state.position != position).then(|| {
+        state.position = position;
+        WindowEvent::Moved(position)
+      })
+    })
+  }
 }
 
 #[derive(Debug, Clone)]
-pub struct MockWindowBuilder {}
+pub struct MockWindowBuilder {
+  state: MockWindowState,
+  center: bool,
+}
 
 impl WindowBuilderBase for MockWindowBuilder {}
 
 impl WindowBuilder for MockWindowBuilder {
   fn new() -> Self {
-    Self {}
+    Self {
+      state: MockWindowState::default(),
+      center: false,
+    }
   }
 
   fn with_config(config: &WindowConfig) -> Self {
-    Self {}
+    let mut builder = Self::new()
+      .title(config.title.clone())
+      .inner_size(config.width, config.height)
+      .resizable(config.resizable)
+      .maximizable(config.maximizable)
+      .minimizable(config.minimizable)
+      .closable(config.closable)
+      .fullscreen(config.fullscreen)
+      .focused(config.focus)
+      .focusable(config.focusable)
+      .maximized(config.maximized)
+      .visible(config.visible)
+      .decorations(config.decorations)
+      .always_on_top(config.always_on_top)
+      .theme(config.theme);
+
+    if let (Some(x), Some(y)) = (config.x, config.y) {
+      builder = builder.position(x, y);
+    } else if config.center {
+      builder = builder.center();
+    }
+    if let (Some(min_width), Some(min_height)) = (config.min_width, config.min_height) {
+      builder = builder.min_inner_size(min_width, min_height);
+    }
+    if let (Some(max_width), Some(max_height)) = (config.max_width, config.max_height) {
+      builder = builder.max_inner_size(max_width, max_height);
+    }
+
+    builder
   }
 
-  fn center(self) -> Self {
+  fn center(mut self) -> Self {
+    self.center = true;
     self
   }
 
-  fn position(self, x: f64, y: f64) -> Self {
+  fn position(mut self, x: f64, y: f64) -> Self {
+    self.state.position = PhysicalPosition::new(x as i32, y as i32);
+    self.center = false;
     self
   }
 
-  fn inner_size(self, min_width: f64, min_height: f64) -> Self {
+  fn inner_size(mut self, width: f64, height: f64) -> Self {
+    self.state.size = PhysicalSize::new(width as u32, height as u32);
     self
   }
 
-  fn min_inner_size(self, min_width: f64, min_height: f64) -> Self {
+  fn min_inner_size(mut self, min_width: f64, min_height: f64) -> Self {
+    self.state.min_size = Some(PhysicalSize::new(min_width as u32, min_height as u32));
     self
   }
 
-  fn max_inner_size(self, max_width: f64, max_height: f64) -> Self {
+  fn max_inner_size(mut self, max_width: f64, max_height: f64) -> Self {
+    self.state.max_size = Some(PhysicalSize::new(max_width as u32, max_height as u32));
     self
   }
 
@@ -383,43 +866,53 @@ impl WindowBuilder for MockWindowBuilder {
     self
   }
 
-  fn resizable(self, resizable: bool) -> Self {
+  fn resizable(mut self, resizable: bool) -> Self {
+    self.state.resizable = resizable;
     self
   }
 
-  fn maximizable(self, resizable: bool) -> Self {
+  fn maximizable(mut self, maximizable: bool) -> Self {
+    self.state.maximizable = maximizable;
     self
   }
 
-  fn minimizable(self, resizable: bool) -> Self {
+  fn minimizable(mut self, minimizable: bool) -> Self {
+    self.state.minimizable = minimizable;
     self
   }
 
-  fn closable(self, resizable: bool) -> Self {
+  fn closable(mut self, closable: bool) -> Self {
+    self.state.closable = closable;
     self
   }
 
-  fn title<S: Into<String>>(self, title: S) -> Self {
+  fn title<S: Into<String>>(mut self, title: S) -> Self {
+    self.state.title = title.into();
     self
   }
 
-  fn fullscreen(self, fullscreen: bool) -> Self {
+  fn fullscreen(mut self, fullscreen: bool) -> Self {
+    self.state.fullscreen = fullscreen;
     self
   }
 
-  fn focused(self, focused: bool) -> Self {
+  fn focused(mut self, focused: bool) -> Self {
+    self.state.focused = focused;
     self
   }
 
-  fn focusable(self, focusable: bool) -> Self {
+  fn focusable(mut self, focusable: bool) -> Self {
+    self.state.focusable = focusable;
     self
   }
 
-  fn maximized(self, maximized: bool) -> Self {
+  fn maximized(mut self, maximized: bool) -> Self {
+    self.state.maximized = maximized;
     self
   }
 
-  fn visible(self, visible: bool) -> Self {
+  fn visible(mut self, visible: bool) -> Self {
+    self.state.visible = visible;
     self
   }
 
@@ -432,7 +925,8 @@ impl WindowBuilder for MockWindowBuilder {
     self
   }
 
-  fn decorations(self, decorations: bool) -> Self {
+  fn decorations(mut self, decorations: bool) -> Self {
+    self.state.decorated = decorations;
     self
   }
 
@@ -440,7 +934,8 @@ impl WindowBuilder for MockWindowBuilder {
     self
   }
 
-  fn always_on_top(self, always_on_top: bool) -> Self {
+  fn always_on_top(mut self, always_on_top: bool) -> Self {
+    self.state.always_on_top = always_on_top;
     self
   }
 
@@ -519,7 +1014,8 @@ impl WindowBuilder for MockWindowBuilder {
     self
   }
 
-  fn theme(self, theme: Option<Theme>) -> Self {
+  fn theme(mut self, theme: Option<Theme>) -> Self {
+    self.state.theme = theme;
     self
   }
 
@@ -528,7 +1024,7 @@ impl WindowBuilder for MockWindowBuilder {
   }
 
   fn get_theme(&self) -> Option<Theme> {
-    None
+    self.state.theme
   }
 
   fn background_color(self, _color: tauri_utils::config::Color) -> Self {
@@ -543,11 +1039,10 @@ impl<T: UserEvent> WebviewDispatch<T> for MockWebviewDispatcher {
     self.context.send_message(Message::Task(Box::new(f)))
   }
 
-  fn on_webview_event<F: Fn(&tauri_runtime::window::WebviewEvent) + Send + 'static>(
-    &self,
-    f: F,
-  ) -> tauri_runtime::WebviewEventId {
-    self.context.next_window_event_id()
+  fn on_webview_event<F: Fn(&WebviewEvent) + Send + 'static>(&self, f: F) -> WebviewEventId {
+    let id = self.context.next_webview_event_id();
+    self.listeners.add(id, f);
+    id
   }
 
   fn with_webview<F: FnOnce(Box<dyn std::any::Any>) + Send + 'static>(&self, f: F) -> Result<()> {
@@ -555,50 +1050,54 @@ impl<T: UserEvent> WebviewDispatch<T> for MockWebviewDispatcher {
   }
 
   #[cfg(any(debug_assertions, feature = "devtools"))]
-  fn open_devtools(&self) {}
+  fn open_devtools(&self) {
+    self.state.lock().unwrap().devtools_open = true;
+  }
 
   #[cfg(any(debug_assertions, feature = "devtools"))]
-  fn close_devtools(&self) {}
+  fn close_devtools(&self) {
+    self.state.lock().unwrap().devtools_open = false;
+  }
 
   #[cfg(any(debug_assertions, feature = "devtools"))]
   fn is_devtools_open(&self) -> Result<bool> {
-    Ok(false)
+    Ok(self.state.lock().unwrap().devtools_open)
   }
 
   fn set_zoom(&self, scale_factor: f64) -> Result<()> {
+    self.state.lock().unwrap().zoom = scale_factor;
     Ok(())
   }
 
   fn eval_script<S: Into<String>>(&self, script: S) -> Result<()> {
-    self
-      .last_evaluated_script
-      .lock()
-      .unwrap()
-      .replace(script.into());
+    let script = script.into();
+    self.context.timeline.record_eval(&self.label, &script);
+    self.evaluated_scripts.lock().unwrap().push(script);
     Ok(())
   }
 
   fn url(&self) -> Result<String> {
-    Ok(self.url.lock().unwrap().clone())
+    Ok(self.state.lock().unwrap().url.clone())
   }
 
-  fn bounds(&self) -> Result<tauri_runtime::dpi::Rect> {
-    Ok(tauri_runtime::dpi::Rect::default())
+  fn bounds(&self) -> Result<Rect> {
+    let state = self.state.lock().unwrap();
+    Ok(Rect {
+      position: state.position.into(),
+      size: state.size.into(),
+    })
   }
 
   fn position(&self) -> Result<PhysicalPosition<i32>> {
-    Ok(PhysicalPosition { x: 0, y: 0 })
+    Ok(self.state.lock().unwrap().position)
   }
 
   fn size(&self) -> Result<PhysicalSize<u32>> {
-    Ok(PhysicalSize {
-      width: 0,
-      height: 0,
-    })
+    Ok(self.state.lock().unwrap().size)
   }
 
   fn navigate(&self, url: Url) -> Result<()> {
-    *self.url.lock().unwrap() = url.to_string();
+    self.state.lock().unwrap().url = url.to_string();
     Ok(())
   }
 
@@ -611,26 +1110,37 @@ impl<T: UserEvent> WebviewDispatch<T> for MockWebviewDispatcher {
   }
 
   fn close(&self) -> Result<()> {
+    self.remove_from_windows();
     Ok(())
   }
 
-  fn set_bounds(&self, bounds: tauri_runtime::dpi::Rect) -> Result<()> {
+  fn set_bounds(&self, bounds: Rect) -> Result<()> {
+    let mut state = self.state.lock().unwrap();
+    state.position = bounds.position.to_physical(1.0);
+    state.size = bounds.size.to_physical(1.0);
     Ok(())
   }
 
-  fn set_size(&self, _size: Size) -> Result<()> {
+  fn set_size(&self, size: Size) -> Result<()> {
+    self.state.lock().unwrap().size = size.to_physical(1.0);
     Ok(())
   }
 
-  fn set_position(&self, _position: Position) -> Result<()> {
+  fn set_position(&self, position: Position) -> Result<()> {
+    self.state.lock().unwrap().position = position.to_physical(1.0);
     Ok(())
   }
 
   fn set_focus(&self) -> Result<()> {
+    self.state.lock().unwrap().focused = true;
     Ok(())
   }
 
   fn reparent(&self, window_id: WindowId) -> Result<()> {
+    self.remove_from_windows();
+    let mut windows = self.context.windows.lock().unwrap();
+    let window = windows.get_mut(&window_id).ok_or(Error::WindowNotFound)?;
+    window.webviews.push(self.id);
     Ok(())
   }
 
@@ -651,6 +1161,7 @@ impl<T: UserEvent> WebviewDispatch<T> for MockWebviewDispatcher {
   }
 
   fn set_auto_resize(&self, auto_resize: bool) -> Result<()> {
+    self.state.lock().unwrap().auto_resize = auto_resize;
     Ok(())
   }
 
@@ -659,10 +1170,12 @@ impl<T: UserEvent> WebviewDispatch<T> for MockWebviewDispatcher {
   }
 
   fn hide(&self) -> Result<()> {
+    self.state.lock().unwrap().visible = false;
     Ok(())
   }
 
   fn show(&self) -> Result<()> {
+    self.state.lock().unwrap().visible = true;
     Ok(())
   }
 
@@ -681,97 +1194,98 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
   }
 
   fn on_window_event<F: Fn(&WindowEvent) + Send + 'static>(&self, f: F) -> WindowEventId {
-    self.context.next_window_event_id()
+    let id = self.context.next_window_event_id();
+    self.listeners.add(id, f);
+    id
   }
 
   fn scale_factor(&self) -> Result<f64> {
-    Ok(1.0)
+    Ok(self.state.lock().unwrap().scale_factor)
   }
 
   fn inner_position(&self) -> Result<PhysicalPosition<i32>> {
-    Ok(PhysicalPosition { x: 0, y: 0 })
+    Ok(self.state.lock().unwrap().position)
   }
 
   fn outer_position(&self) -> Result<PhysicalPosition<i32>> {
-    Ok(PhysicalPosition { x: 0, y: 0 })
+    Ok(self.state.lock().unwrap().position)
   }
 
   fn inner_size(&self) -> Result<PhysicalSize<u32>> {
-    Ok(PhysicalSize {
-      width: 0,
-      height: 0,
-    })
+    Ok(self.state.lock().unwrap().size)
   }
 
   fn outer_size(&self) -> Result<PhysicalSize<u32>> {
-    Ok(PhysicalSize {
-      width: 0,
-      height: 0,
-    })
+    Ok(self.state.lock().unwrap().size)
   }
 
   fn is_fullscreen(&self) -> Result<bool> {
-    Ok(false)
+    Ok(self.state.lock().unwrap().fullscreen)
   }
 
   fn is_minimized(&self) -> Result<bool> {
-    Ok(false)
+    Ok(self.state.lock().unwrap().minimized)
   }
 
   fn is_maximized(&self) -> Result<bool> {
-    Ok(false)
+    Ok(self.state.lock().unwrap().maximized)
   }
 
   fn is_focused(&self) -> Result<bool> {
-    Ok(false)
+    Ok(self.state.lock().unwrap().focused)
   }
 
   fn is_decorated(&self) -> Result<bool> {
-    Ok(false)
+    Ok(self.state.lock().unwrap().decorated)
   }
 
   fn is_resizable(&self) -> Result<bool> {
-    Ok(false)
+    Ok(self.state.lock().unwrap().resizable)
   }
 
   fn is_maximizable(&self) -> Result<bool> {
-    Ok(true)
+    Ok(self.state.lock().unwrap().maximizable)
   }
 
   fn is_minimizable(&self) -> Result<bool> {
-    Ok(true)
+    Ok(self.state.lock().unwrap().minimizable)
   }
 
   fn is_closable(&self) -> Result<bool> {
-    Ok(true)
+    Ok(self.state.lock().unwrap().closable)
   }
 
   fn is_visible(&self) -> Result<bool> {
-    Ok(true)
+    Ok(self.state.lock().unwrap().visible)
   }
 
   fn title(&self) -> Result<String> {
-    Ok(String::new())
+    Ok(self.state.lock().unwrap().title.clone())
   }
 
   fn current_monitor(&self) -> Result<Option<Monitor>> {
-    Ok(None)
+    let position = self.state.lock().unwrap().position;
+    Ok(mock_monitor_from_point(
+      position.x as f64,
+      position.y as f64,
+    ))
   }
 
   fn primary_monitor(&self) -> Result<Option<Monitor>> {
-    Ok(None)
+    Ok(Some(mock_monitor()))
   }
 
   fn monitor_from_point(&self, x: f64, y: f64) -> Result<Option<Monitor>> {
-    Ok(None)
+    Ok(mock_monitor_from_point(x, y))
   }
 
   fn available_monitors(&self) -> Result<Vec<Monitor>> {
-    Ok(Vec::new())
+    Ok(vec![mock_monitor()])
   }
 
   fn theme(&self) -> Result<Theme> {
-    Ok(Theme::Light)
+    let theme = self.state.lock().unwrap().theme;
+    Ok(theme.unwrap_or_else(|| self.context.theme()))
   }
 
   #[cfg(any(
@@ -782,7 +1296,8 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
     target_os = "openbsd"
   ))]
   fn gtk_window(&self) -> Result<gtk::ApplicationWindow> {
-    unimplemented!()
+    // the MockRuntime does not create GTK windows
+    Err(Error::WindowNotFound)
   }
 
   #[cfg(any(
@@ -793,7 +1308,8 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
     target_os = "openbsd"
   ))]
   fn default_vbox(&self) -> Result<gtk::Box> {
-    unimplemented!()
+    // the MockRuntime does not create GTK windows
+    Err(Error::WindowNotFound)
   }
 
   fn window_handle(
@@ -826,7 +1342,12 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
   }
 
   fn center(&self) -> Result<()> {
-    Ok(())
+    let monitor = mock_monitor();
+    let size = self.state.lock().unwrap().size;
+    self.move_to(PhysicalPosition::new(
+      (monitor.size.width as i32 - size.width as i32) / 2,
+      (monitor.size.height as i32 - size.height as i32) / 2,
+    ))
   }
 
   fn request_user_attention(&self, request_type: Option<UserAttentionType>) -> Result<()> {
@@ -838,109 +1359,72 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
     pending: PendingWindow<T, Self::Runtime>,
     _after_window_creation: Option<F>,
   ) -> Result<DetachedWindow<T, Self::Runtime>> {
-    let id = self.context.next_window_id();
-
-    let (webview_id, webviews) = if let Some(w) = &pending.webview {
-      (Some(self.context.next_webview_id()), vec![Webview])
-    } else {
-      (None, Vec::new())
-    };
-
-    self.context.windows.borrow_mut().insert(
-      id,
-      Window {
-        label: pending.label.clone(),
-        webviews,
-      },
-    );
-
-    let webview = webview_id.map(|id| DetachedWindowWebview {
-      webview: DetachedWebview {
-        label: pending.label.clone(),
-        dispatcher: MockWebviewDispatcher {
-          id,
-          context: self.context.clone(),
-          url: Arc::new(Mutex::new(pending.webview.unwrap().url)),
-          last_evaluated_script: Default::default(),
-        },
-      },
-      use_https_scheme: false,
-    });
-
-    Ok(DetachedWindow {
-      id,
-      label: pending.label,
-      dispatcher: MockWindowDispatcher {
-        id,
-        context: self.context.clone(),
-      },
-      webview,
-    })
+    self.context.create_window(pending)
   }
 
   fn create_webview(
     &mut self,
     pending: PendingWebview<T, Self::Runtime>,
   ) -> Result<DetachedWebview<T, Self::Runtime>> {
-    let id = self.context.next_webview_id();
-    let webview = Webview;
-    if let Some(w) = self.context.windows.borrow_mut().get_mut(&self.id) {
-      w.webviews.push(webview);
-    }
-
-    Ok(DetachedWebview {
-      label: pending.label,
-      dispatcher: MockWebviewDispatcher {
-        id,
-        context: self.context.clone(),
-        last_evaluated_script: Default::default(),
-        url: Arc::new(Mutex::new(pending.url)),
-      },
-    })
+    Ok(self.context.create_webview(self.id, pending))
   }
 
   fn set_resizable(&self, resizable: bool) -> Result<()> {
+    self.state.lock().unwrap().resizable = resizable;
     Ok(())
   }
 
   fn set_maximizable(&self, maximizable: bool) -> Result<()> {
+    self.state.lock().unwrap().maximizable = maximizable;
     Ok(())
   }
 
   fn set_minimizable(&self, minimizable: bool) -> Result<()> {
+    self.state.lock().unwrap().minimizable = minimizable;
     Ok(())
   }
 
   fn set_closable(&self, closable: bool) -> Result<()> {
+    self.state.lock().unwrap().closable = closable;
     Ok(())
   }
 
   fn set_title<S: Into<String>>(&self, title: S) -> Result<()> {
+    self.state.lock().unwrap().title = title.into();
     Ok(())
   }
 
   fn maximize(&self) -> Result<()> {
+    self.state.lock().unwrap().maximized = true;
     Ok(())
   }
 
   fn unmaximize(&self) -> Result<()> {
+    self.state.lock().unwrap().maximized = false;
     Ok(())
   }
 
   fn minimize(&self) -> Result<()> {
+    self.state.lock().unwrap().minimized = true;
     Ok(())
   }
 
   fn unminimize(&self) -> Result<()> {
+    self.state.lock().unwrap().minimized = false;
     Ok(())
   }
 
   fn show(&self) -> Result<()> {
+    self.state.lock().unwrap().visible = true;
     Ok(())
   }
 
   fn hide(&self) -> Result<()> {
-    Ok(())
+    self.update(|state| {
+      state.visible = false;
+      // hidden windows lose focus
+      std::mem::take(&mut state.focused).then_some(WindowEvent::Focused(false))
+    })
   }
 
   fn close(&self) -> Result<()> {
@@ -954,6 +1438,7 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
   }
 
   fn set_decorations(&self, decorations: bool) -> Result<()> {
+    self.state.lock().unwrap().decorated = decorations;
     Ok(())
   }
 
@@ -966,6 +1451,7 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
   }
 
   fn set_always_on_top(&self, always_on_top: bool) -> Result<()> {
+    self.state.lock().unwrap().always_on_top = always_on_top;
     Ok(())
   }
 
@@ -978,35 +1464,56 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
   }
 
   fn set_size(&self, size: Size) -> Result<()> {
-    Ok(())
+    let scale_factor = self.state.lock().unwrap().scale_factor;
+    self.resize(size.to_physical(scale_factor))
   }
 
   fn set_min_size(&self, size: Option<Size>) -> Result<()> {
-    Ok(())
+    let current = {
+      let mut state = self.state.lock().unwrap();
+      let scale_factor = state.scale_factor;
+      state.min_size = size.map(|s| s.to_physical(scale_factor));
+      state.size
+    };
+    self.resize(current)
   }
 
   fn set_max_size(&self, size: Option<Size>) -> Result<()> {
-    Ok(())
+    let current = {
+      let mut state = self.state.lock().unwrap();
+      let scale_factor = state.scale_factor;
+      state.max_size = size.map(|s| s.to_physical(scale_factor));
+      state.size
+    };
+    self.resize(current)
   }
 
   fn set_position(&self, position: Position) -> Result<()> {
-    Ok(())
+    let scale_factor = self.state.lock().unwrap().scale_factor;
+    self.move_to(position.to_physical(scale_factor))
   }
 
   fn set_fullscreen(&self, fullscreen: bool) -> Result<()> {
+    self.state.lock().unwrap().fullscreen = fullscreen;
     Ok(())
   }
 
   #[cfg(target_os = "macos")]
   fn set_simple_fullscreen(&self, enable: bool) -> Result<()> {
+    self.state.lock().unwrap().fullscreen = enable;
     Ok(())
   }
 
   fn set_focus(&self) -> Result<()> {
+    let focusable = self.state.lock().unwrap().focusable;
+    if focusable {
+      self.context.focus_window(self.id)?;
+    }
     Ok(())
   }
 
   fn set_focusable(&self, focusable: bool) -> Result<()> {
+    self.state.lock().unwrap().focusable = focusable;
     Ok(())
   }
 
@@ -1078,19 +1585,26 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
   }
 
   fn set_theme(&self, theme: Option<Theme>) -> Result<()> {
-    Ok(())
+    let app_theme = self.context.theme();
+    self.update(|state| {
+      let previous = state.theme.unwrap_or(app_theme);
+      state.theme = theme;
+      let current = theme.unwrap_or(app_theme);
+      (previous != current).then_some(WindowEvent::ThemeChanged(current))
+    })
   }
 
   fn set_enabled(&self, enabled: bool) -> Result<()> {
+    self.state.lock().unwrap().enabled = enabled;
     Ok(())
   }
 
   fn is_enabled(&self) -> Result<bool> {
-    Ok(true)
+    Ok(self.state.lock().unwrap().enabled)
   }
 
   fn is_always_on_top(&self) -> Result<bool> {
-    Ok(false)
+    Ok(self.state.lock().unwrap().always_on_top)
   }
 
   fn set_background_color(&self, color: Option<tauri_utils::config::Color>) -> Result<()> {
@@ -1099,11 +1613,15 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
 }
 
 #[derive(Debug, Clone)]
-pub struct EventProxy {}
+pub struct EventProxy {
+  context: RuntimeContext,
+}
 
 impl<T: UserEvent> EventLoopProxy<T> for EventProxy {
   fn send_event(&self, event: T) -> Result<()> {
-    Ok(())
+    self
+      .context
+      .send_message(Message::UserEvent(Box::new(event)))
   }
 }
 
@@ -1117,12 +1635,14 @@ pub struct MockRuntime {
 impl MockRuntime {
   fn init() -> Self {
     let is_running = Arc::new(AtomicBool::new(false));
-    let (tx, rx) = sync_channel(256);
+    let (tx, rx) = channel();
     let context = RuntimeContext {
       is_running: is_running.clone(),
       windows: Default::default(),
+      theme: Default::default(),
       shortcuts: Default::default(),
       run_tx: tx,
+      timeline: Default::default(),
       next_window_id: Default::default(),
       next_webview_id: Default::default(),
       next_window_event_id: Default::default(),
@@ -1159,7 +1679,9 @@ impl<T: UserEvent> Runtime<T> for MockRuntime {
   }
 
   fn create_proxy(&self) -> EventProxy {
-    EventProxy {}
+    EventProxy {
+      context: self.context.clone(),
+    }
   }
 
   fn handle(&self) -> Self::Handle {
@@ -1173,44 +1695,7 @@ impl<T: UserEvent> Runtime<T> for MockRuntime {
     pending: PendingWindow<T, Self>,
     _after_window_creation: Option<F>,
   ) -> Result<DetachedWindow<T, Self>> {
-    let id = self.context.next_window_id();
-
-    let (webview_id, webviews) = if let Some(w) = &pending.webview {
-      (Some(self.context.next_webview_id()), vec![Webview])
-    } else {
-      (None, Vec::new())
-    };
-
-    self.context.windows.borrow_mut().insert(
-      id,
-      Window {
-        label: pending.label.clone(),
-        webviews,
-      },
-    );
-
-    let webview = webview_id.map(|id| DetachedWindowWebview {
-      webview: DetachedWebview {
-        label: pending.label.clone(),
-        dispatcher: MockWebviewDispatcher {
-          id,
-          context: self.context.clone(),
-          url: Arc::new(Mutex::new(pending.webview.unwrap().url)),
-          last_evaluated_script: Default::default(),
-        },
-      },
-      use_https_scheme: false,
-    });
-
-    Ok(DetachedWindow {
-      id,
-      label: pending.label,
-      dispatcher: MockWindowDispatcher {
-        id,
-        context: self.context.clone(),
-      },
-      webview,
-    })
+    self.context.create_window(pending)
   }
 
   fn create_webview(
@@ -1218,37 +1703,23 @@ impl<T: UserEvent> Runtime<T> for MockRuntime {
     window_id: WindowId,
     pending: PendingWebview<T, Self>,
   ) -> Result<DetachedWebview<T, Self>> {
-    let id = self.context.next_webview_id();
-    let webview = Webview;
-    if let Some(w) = self.context.windows.borrow_mut().get_mut(&window_id) {
-      w.webviews.push(webview);
-    }
-
-    Ok(DetachedWebview {
-      label: pending.label,
-      dispatcher: MockWebviewDispatcher {
-        id,
-        context: self.context.clone(),
-        last_evaluated_script: Default::default(),
-        url: Arc::new(Mutex::new(pending.url)),
-      },
-    })
+    Ok(self.context.create_webview(window_id, pending))
   }
 
   fn primary_monitor(&self) -> Option<Monitor> {
-    unimplemented!()
+    Some(mock_monitor())
   }
 
   fn monitor_from_point(&self, x: f64, y: f64) -> Option<Monitor> {
-    unimplemented!()
+    mock_monitor_from_point(x, y)
   }
 
   fn available_monitors(&self) -> Vec<Monitor> {
-    unimplemented!()
+    vec![mock_monitor()]
   }
 
   fn set_theme(&self, theme: Option<Theme>) {
-    unimplemented!()
+    self.context.set_theme(theme);
   }
 
   #[cfg(target_os = "macos")]
@@ -1278,7 +1749,20 @@ impl<T: UserEvent> Runtime<T> for MockRuntime {
     target_os = "netbsd",
     target_os = "openbsd"
   ))]
-  fn run_iteration<F: FnMut(RunEvent<T>)>(&mut self, callback: F) {}
+  fn run_iteration<F: FnMut(RunEvent<T>)>(&mut self, mut callback: F) {
+    if !self.is_running.swap(true, Ordering::Relaxed) {
+      callback(RunEvent::Ready);
+    }
+
+    while let Ok(message) = self.run_rx.try_recv() {
+      if self.context.handle_message(message, &mut callback) {
+        callback(RunEvent::Exit);
+        return;
+      }
+    }
+
+    callback(RunEvent::MainEventsCleared);
+  }
 
   fn run_return<F: FnMut(RunEvent<T>) + 'static>(self, callback: F) -> i32 {
     self.run(callback);
@@ -1290,66 +1774,16 @@ impl<T: UserEvent> Runtime<T> for MockRuntime {
     self.is_running.store(true, Ordering::Relaxed);
     callback(RunEvent::Ready);
 
-    loop {
-      if let Ok(m) = self.run_rx.try_recv() {
-        match m {
-          Message::Task(p) => p(),
-          Message::CloseWindow(id) => {
-            let label = self
-              .context
-              .windows
-              .borrow()
-              .get(&id)
-              .map(|w| w.label.clone());
-            if let Some(label) = label {
-              let (tx, rx) = channel();
-              callback(RunEvent::WindowEvent {
-                label,
-                event: WindowEvent::CloseRequested { signal_tx: tx },
-              });
-
-              let should_prevent = matches!(rx.try_recv(), Ok(true));
-              if !should_prevent {
-                self.context.windows.borrow_mut().remove(&id);
-
-                let is_empty = self.context.windows.borrow().is_empty();
-                if is_empty {
-                  let (tx, rx) = channel();
-                  callback(RunEvent::ExitRequested { code: None, tx });
-
-                  let recv = rx.try_recv();
-                  let should_prevent = matches!(recv, Ok(ExitRequestedEventAction::Prevent));
-
-                  if !should_prevent {
-                    break;
-                  }
-                }
-              }
-            }
-          }
-          Message::DestroyWindow(id) => {
-            let removed = self.context.windows.borrow_mut().remove(&id).is_some();
-            if removed {
-              let is_empty = self.context.windows.borrow().is_empty();
-              if is_empty {
-                let (tx, rx) = channel();
-                callback(RunEvent::ExitRequested { code: None, tx });
-
-                let recv = rx.try_recv();
-                let should_prevent = matches!(recv, Ok(ExitRequestedEventAction::Prevent));
-
-                if !should_prevent {
-                  break;
-                }
-              }
-            }
-          }
+    'event_loop: loop {
+      let mut next = self.run_rx.recv_timeout(Duration::from_millis(100)).ok();
+      while let Some(message) = next {
+        if self.context.handle_message(message, &mut callback) {
+          break 'event_loop;
         }
+        next = self.run_rx.try_recv().ok();
       }
 
       callback(RunEvent::MainEventsCleared);
-
-      std::thread::sleep(std::time::Duration::from_secs(1));
     }
 
     callback(RunEvent::Exit);
@@ -51,7 +51,12 @@
 
 #![allow(unused_variables)]
 
+mod acl;
+mod harness;
 mod mock_runtime;
+pub mod webdriver;
+pub use acl::*;
+pub use harness::*;
 pub use mock_runtime::*;
 use serde::Serialize;
 use serialize_to_javascript::DefaultTemplate;
@@ -61,7 +66,7 @@ use std::{borrow::Cow, collections::HashMap, fmt::Debug};
 use crate::{
   ipc::{InvokeError, InvokeResponse, InvokeResponseBody},
   webview::InvokeRequest,
-  App, Assets, Builder, Context, Pattern, Runtime, Webview,
+  App, Assets, Builder, Context, Pattern, Runtime, Webview, Window,
 };
 use tauri_utils::{
   acl::resolved::Resolved,
@@ -304,11 +309,100 @@ pub fn get_ipc_response<W: AsRef<Webview<MockRuntime>>>(
   }
 }
 
+/// Gets the simulated window backing a [`Window`] created with the [`MockRuntime`].
+///
+/// Use it to assert on the window state or to inject synthetic [`WindowEvent`](tauri_runtime::window::WindowEvent)s.
+///
+/// # Examples
+///
+/// ```rust
+/// use tauri::{test::{mock_app, mock_window}, LogicalSize};
+///
+/// let app = mock_app();
+/// let webview = tauri::WebviewWindowBuilder::new(&app, "main", Default::default()).build().unwrap();
+/// webview.set_size(LogicalSize::new(1024, 768)).unwrap();
+///
+/// let state = mock_window(&webview.as_ref().window()).state();
+/// assert_eq!(state.size.width, 1024);
+/// ```
+pub fn mock_window(window: &Window<MockRuntime>) -> MockWindowDispatcher {
+  window.window.dispatcher.clone()
+}
+
+/// Gets the simulated webview backing a [`Webview`] created with the [`MockRuntime`].
+///
+/// Use it to assert on the webview state, the scripts it evaluated
+/// or to inject synthetic [`WebviewEvent`](tauri_runtime::window::WebviewEvent)s.
+///
+/// # Examples
+///
+/// ```rust
+/// use tauri::test::{mock_app, mock_webview};
+///
+/// let app = mock_app();
+/// let webview = tauri::WebviewWindowBuilder::new(&app, "main", Default::default()).build().unwrap();
+/// webview.eval("document.title = 'Tauri'").unwrap();
+///
+/// assert_eq!(
+///   mock_webview(&webview).last_evaluated_script().as_deref(),
+///   Some("document.title = 'Tauri'")
+/// );
+/// ```
+pub fn mock_webview<W: AsRef<Webview<MockRuntime>>>(webview: &W) -> MockWebviewDispatcher {
+  webview.as_ref().webview.dispatcher.clone()
+}
+
+/// Simulates a click on the menu item with the given id.
+///
+/// The event is delivered to the menu event listeners by the event loop,
+/// so it is only handled when the app runs.
+#[cfg(desktop)]
+pub fn emit_menu_event<M: crate::Manager<MockRuntime>>(
+  manager: &M,
+  id: impl Into<crate::menu::MenuId>,
+) -> crate::Result<()> {
+  use tauri_runtime::{EventLoopProxy, RuntimeHandle};
+
+  manager
+    .app_handle()
+    .runtime_handle
+    .create_proxy()
+    .send_event(crate::EventLoopMessage::MenuEvent(crate::menu::MenuEvent {
+      id: id.into(),
+    }))
+    .map_err(Into::into)
+}
+
+/// Simulates a tray icon event.
+///
+/// The event is delivered to the tray icon event listeners by the event loop,
+/// so it is only handled when the app runs.
+#[cfg(all(desktop, feature = "tray-icon"))]
+pub fn emit_tray_icon_event<M: crate::Manager<MockRuntime>>(
+  manager: &M,
+  event: crate::tray::TrayIconEvent,
+) -> crate::Result<()> {
+  use tauri_runtime::{EventLoopProxy, RuntimeHandle};
+
+  manager
+    .app_handle()
+    .runtime_handle
+    .create_proxy()
+    .send_event(crate::EventLoopMessage::TrayIconEvent(event))
+    .map_err(Into::into)
+}
+
 #[cfg(test)]
 mod tests {
-  use std::time::Duration;
+  use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+  };
+
+  use tauri_runtime::window::WindowEvent;
 
-  use super::mock_app;
+  use super::{mock_app, mock_webview, mock_window};
+  use crate::{LogicalPosition, LogicalSize, Manager};
 
   #[test]
   fn run_app() {
@@ -327,4 +421,106 @@ mod tests {
       println!("{event:?}");
     });
   }
+
+  #[test]
+  fn window_state() {
+    let app = mock_app();
+    let main = crate::WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    let other = crate::WebviewWindowBuilder::new(&app, "other", Default::default())
+      .build()
+      .unwrap();
+    let main_window = mock_window(&main.as_ref().window());
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let events_ = events.clone();
+    main.on_window_event(move |event| events_.lock().unwrap().push(format!("{event:?}")));
+
+    // the last created window is focused
+    assert!(!main.is_focused().unwrap());
+    assert!(other.is_focused().unwrap());
+
+    main.set_focus().unwrap();
+    assert!(main.is_focused().unwrap());
+    assert!(!other.is_focused().unwrap());
+
+    main.set_min_size(Some(LogicalSize::new(400, 300))).unwrap();
+    main.set_size(LogicalSize::new(200, 500)).unwrap();
+    main.set_position(LogicalPosition::new(10, 20)).unwrap();
+    assert_eq!(
+      main.inner_size().unwrap(),
+      crate::PhysicalSize::new(400, 500)
+    );
+    assert_eq!(
+      main_window.state().position,
+      crate::PhysicalPosition::new(10, 20)
+    );
+
+    main.hide().unwrap();
+    assert!(!main.is_visible().unwrap());
+    assert!(!main.is_focused().unwrap());
+
+    main_window
+      .emit_event(WindowEvent::Resized(crate::PhysicalSize::new(640, 480)))
+      .unwrap();
+    assert_eq!(main.inner_size().unwrap().width, 640);
+
+    assert_eq!(
+      *events.lock().unwrap(),
+      vec![
+        "Focused(true)",
+        "Resized(PhysicalSize { width: 400, height: 500 })",
+        "Moved(PhysicalPosition { x: 10, y: 20 })",
+        "Focused(false)",
+        "Resized(PhysicalSize { width: 640, height: 480 })",
+      ]
+    );
+
+    main.destroy().unwrap();
+    assert!(app.get_webview_window("other").is_some());
+  }
+
+  #[test]
+  fn evaluated_scripts() {
+    let app = mock_app();
+    let webview = crate::WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    let mock = mock_webview(&webview);
+    mock.clear_evaluated_scripts();
+
+    webview.eval("console.log(1)").unwrap();
+    webview.eval("console.log(2)").unwrap();
+    assert_eq!(
+      mock.evaluated_scripts(),
+      vec!["console.log(1)", "console.log(2)"]
+    );
+
+    webview.set_zoom(2.0).unwrap();
+    assert_eq!(mock.state().zoom, 2.0);
+  }
+
+  #[cfg(desktop)]
+  #[test]
+  fn menu_event() {
+    let app = mock_app();
+    let w = crate::WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    app.on_menu_event(move |_app, event| tx.send(event.id().0.clone()).unwrap());
+
+    super::emit_menu_event(&app, "quit").unwrap();
+    // the menu event is delivered by the event loop, close the window once it is received to exit
+    let clicked = std::thread::spawn(move || {
+      let clicked = rx.recv_timeout(Duration::from_secs(10));
+      w.close().unwrap();
+      clicked
+    });
+
+    app.run(|_app, _event| {});
+    assert_eq!(clicked.join().unwrap().unwrap(), "quit");
+  }
 }
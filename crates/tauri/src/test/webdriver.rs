@@ -0,0 +1,292 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! A test-only plugin servicing the Tauri extension commands of `tauri-driver`.
+//!
+//! `tauri-driver` launches the application with the [`CHANNEL_ENV`] and [`TOKEN_ENV`] environment variables set.
+//! The plugin connects to that address, authenticates with the token and then answers the requests of the driver,
+//! one JSON object per line: it invokes commands as if a webview had sent them, so the capabilities of that webview apply,
+//! emits events and records the payloads of the events the tests listen to.
+//!
+//! The plugin does nothing when the application is not launched by `tauri-driver`,
+//! but it should only be registered in the builds used for end-to-end tests.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! let mut builder = tauri::Builder::default();
+//! #[cfg(feature = "e2e")]
+//! {
+//!   builder = builder.plugin(tauri::test::webdriver::init());
+//! }
+//! ```
+
+use std::{
+  collections::HashMap,
+  io::{BufRead, BufReader, Write},
+  net::TcpStream,
+  sync::{Arc, Mutex},
+};
+
+use serde::Deserialize;
+use serde_json::{json, Value as JsonValue};
+
+use crate::{
+  event::EventName,
+  ipc::{CallbackFn, InvokeBody, InvokeError, InvokeResponse, InvokeResponseBody},
+  plugin::{Builder, TauriPlugin},
+  webview::InvokeRequest,
+  AppHandle, Emitter, Listener, Manager, Runtime,
+};
+
+/// The environment variable holding the address of the `tauri-driver` channel.
+pub const CHANNEL_ENV: &str = "TAURI_WEBDRIVER_CHANNEL";
+
+/// The environment variable holding the token the plugin authenticates with.
+pub const TOKEN_ENV: &str = "TAURI_WEBDRIVER_TOKEN";
+
+/// A request of `tauri-driver`.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum Request {
+  /// Invokes a command from the given webview.
+  Invoke {
+    webview: String,
+    cmd: String,
+    #[serde(default)]
+    args: JsonValue,
+  },
+  /// Emits an event, to the given label if `target` is set.
+  Emit {
+    event: String,
+    #[serde(default)]
+    payload: JsonValue,
+    target: Option<String>,
+  },
+  /// Starts recording the payloads of an event.
+  Listen { event: String },
+  /// Returns and clears the payloads recorded for an event.
+  Events { event: String },
+}
+
+/// The payloads recorded for each event listened to.
+type Events = Arc<Mutex<HashMap<String, Vec<JsonValue>>>>;
+
+/// Initializes the plugin.
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+  Builder::new("webdriver")
+    .setup(|app, _api| {
+      let (Ok(address), Ok(token)) = (std::env::var(CHANNEL_ENV), std::env::var(TOKEN_ENV)) else {
+        return Ok(());
+      };
+      let app = app.clone();
+      std::thread::spawn(move || {
+        if let Err(e) = serve(&app, &address, &token) {
+          log::error!("tauri-driver channel closed: {e}");
+        }
+      });
+      Ok(())
+    })
+    .build()
+}
+
+/// Connects to `tauri-driver` and answers its requests until the connection is closed.
+fn serve<R: Runtime>(app: &AppHandle<R>, address: &str, token: &str) -> std::io::Result<()> {
+  let stream = TcpStream::connect(address)?;
+  let mut writer = stream.try_clone()?;
+  writeln!(writer, "{token}")?;
+
+  let events = Events::default();
+  for line in BufReader::new(stream).lines() {
+    let response = match serde_json::from_str(&line?) {
+      Ok(request) => handle(app, &events, request),
+      Err(e) => Err(JsonValue::String(format!("invalid request: {e}"))),
+    };
+    let response = match response {
+      Ok(value) => json!({ "value": value }),
+      Err(error) => json!({ "error": error }),
+    };
+    writeln!(writer, "{response}")?;
+  }
+
+  Ok(())
+}
+
+fn handle<R: Runtime>(
+  app: &AppHandle<R>,
+  events: &Events,
+  request: Request,
+) -> Result<JsonValue, JsonValue> {
+  let error = |e: crate::Error| JsonValue::String(e.to_string());
+
+  match request {
+    Request::Invoke { webview, cmd, args } => invoke(app, &webview, cmd, args),
+    Request::Emit {
+      event,
+      payload,
+      target,
+    } => match target {
+      Some(target) => app.emit_to(target.as_str(), &event, payload),
+      None => app.emit(&event, payload),
+    }
+    .map(|()| JsonValue::Null)
+    .map_err(error),
+    Request::Listen { event } => {
+      EventName::new(event.as_str()).map_err(error)?;
+      let mut recorded = events.lock().unwrap();
+      if !recorded.contains_key(&event) {
+        recorded.insert(event.clone(), Vec::new());
+        let events = events.clone();
+        let name = event.clone();
+        app.listen_any(event, move |event| {
+          let payload = serde_json::from_str(event.payload())
+            .unwrap_or_else(|_| JsonValue::String(event.payload().into()));
+          if let Some(payloads) = events.lock().unwrap().get_mut(&name) {
+            payloads.push(payload);
+          }
+        });
+      }
+      Ok(JsonValue::Null)
+    }
+    Request::Events { event } => events
+      .lock()
+      .unwrap()
+      .get_mut(&event)
+      .map(|payloads| JsonValue::Array(std::mem::take(payloads)))
+      .ok_or_else(|| JsonValue::String(format!("not listening to event {event}"))),
+  }
+}
+
+/// Invokes a command as if the webview had sent it, waiting for its response.
+fn invoke<R: Runtime>(
+  app: &AppHandle<R>,
+  label: &str,
+  cmd: String,
+  args: JsonValue,
+) -> Result<JsonValue, JsonValue> {
+  let error = |e: crate::Error| JsonValue::String(e.to_string());
+
+  let webview = app
+    .get_webview(label)
+    .ok_or_else(|| JsonValue::String(format!("webview `{label}` not found")))?;
+  let request = InvokeRequest {
+    cmd,
+    callback: CallbackFn(0),
+    error: CallbackFn(1),
+    url: webview.url().map_err(error)?,
+    body: InvokeBody::Json(if args.is_null() { json!({}) } else { args }),
+    headers: Default::default(),
+    invoke_key: app.invoke_key().into(),
+  };
+
+  let (tx, rx) = std::sync::mpsc::sync_channel(1);
+  // the IPC handler runs on the main thread, like the messages sent by the webview
+  app
+    .run_on_main_thread(move || {
+      webview.on_message(
+        request,
+        Box::new(move |_webview, _cmd, response, _callback, _error| {
+          let _ = tx.send(response);
+        }),
+      );
+    })
+    .map_err(error)?;
+
+  match rx.recv() {
+    Ok(InvokeResponse::Ok(InvokeResponseBody::Json(json))) => {
+      Ok(serde_json::from_str(&json).unwrap_or(JsonValue::Null))
+    }
+    Ok(InvokeResponse::Ok(InvokeResponseBody::Raw(bytes))) => Ok(bytes.into()),
+    Ok(InvokeResponse::Err(InvokeError(value))) => Err(value),
+    Err(_) => Err(JsonValue::String("the command did not respond".into())),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    time::{Duration, Instant},
+  };
+
+  use serde_json::{json, Value as JsonValue};
+
+  use crate::test::{mock_builder, mock_context, noop_assets, EventLoopHarness};
+
+  #[crate::command(root = "crate")]
+  fn greet(name: String) -> String {
+    format!("Hello, {name}!")
+  }
+
+  #[test]
+  fn serves_driver_requests() {
+    let app = mock_builder()
+      .invoke_handler(crate::generate_handler![greet])
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    crate::WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    let mut harness = EventLoopHarness::new(app);
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let handle = harness.app().handle().clone();
+    std::thread::spawn(move || super::serve(&handle, &address, "secret"));
+
+    let (stream, _) = listener.accept().unwrap();
+    stream
+      .set_read_timeout(Some(Duration::from_secs(5)))
+      .unwrap();
+    let mut writer = stream.try_clone().unwrap();
+    let mut reader = BufReader::new(stream);
+    let mut token = String::new();
+    reader.read_line(&mut token).unwrap();
+    assert_eq!(token, "secret\n");
+
+    // the driver side runs on another thread so the event loop keeps running
+    let driver = std::thread::spawn(move || {
+      let mut request = |request: JsonValue| {
+        writeln!(writer, "{request}").unwrap();
+        let mut response = String::new();
+        reader.read_line(&mut response).unwrap();
+        serde_json::from_str::<JsonValue>(&response).unwrap()
+      };
+
+      vec![
+        request(
+          json!({ "type": "invoke", "webview": "main", "cmd": "greet", "args": { "name": "tauri" } }),
+        ),
+        request(json!({ "type": "invoke", "webview": "other", "cmd": "greet" })),
+        request(json!({ "type": "listen", "event": "ready" })),
+        request(json!({ "type": "emit", "event": "ready", "payload": { "count": 1 } })),
+        request(json!({ "type": "emit", "event": "ready", "target": "main", "payload": 2 })),
+        request(json!({ "type": "events", "event": "ready" })),
+        request(json!({ "type": "events", "event": "ready" })),
+        request(json!({ "type": "events", "event": "other" })),
+        request(json!({ "type": "listen", "event": "invalid name" })),
+      ]
+    });
+
+    let deadline = Instant::now() + EventLoopHarness::WAIT_TIMEOUT;
+    while !driver.is_finished() {
+      assert!(Instant::now() < deadline, "the driver requests timed out");
+      harness.step();
+      std::thread::sleep(Duration::from_millis(1));
+    }
+    let responses = driver.join().unwrap();
+    assert_eq!(responses[0], json!({ "value": "Hello, tauri!" }));
+    assert_eq!(
+      responses[1],
+      json!({ "error": "webview `other` not found" })
+    );
+    assert_eq!(responses[2], json!({ "value": null }));
+    assert_eq!(responses[5], json!({ "value": [{ "count": 1 }, 2] }));
+    // the recorded payloads are drained
+    assert_eq!(responses[6], json!({ "value": [] }));
+    assert!(responses[7]["error"].is_string());
+    assert!(responses[8]["error"].is_string());
+  }
+}
@@ -0,0 +1,232 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Content Security Policy violation reports.
+
+use serde::{Deserialize, Serialize};
+
+/// Path of the CSP report endpoint on the `ipc` custom protocol.
+pub(crate) const CSP_REPORT_PATH: &str = "__tauri_csp_report__";
+
+/// The `report-uri` directive value pointing to the CSP report endpoint.
+pub(crate) fn report_uri(https: bool) -> String {
+  if cfg!(windows) || cfg!(target_os = "android") {
+    let scheme = if https { "https" } else { "http" };
+    format!("{scheme}://ipc.localhost/{CSP_REPORT_PATH}")
+  } else {
+    format!("ipc://localhost/{CSP_REPORT_PATH}")
+  }
+}
+
+/// A Content Security Policy violation reported by the webview.
+///
+/// See <https://developer.mozilla.org/en-US/docs/Web/API/CSPViolationReportBody>.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct CspViolationReport {
+  /// The URL of the document in which the violation occurred.
+  pub document_url: Option<String>,
+  /// The referrer of the document in which the violation occurred.
+  pub referrer: Option<String>,
+  /// The URL of the resource that was blocked, or `inline`, `eval`, etc.
+  pub blocked_url: Option<String>,
+  /// The directive whose enforcement caused the violation, e.g. `script-src-elem`.
+  pub effective_directive: Option<String>,
+  /// The policy whose enforcement caused the violation.
+  pub original_policy: Option<String>,
+  /// Either `enforce` or `report`.
+  pub disposition: Option<String>,
+  /// The URL of the source file where the violation occurred.
+  pub source_file: Option<String>,
+  /// The line number in the source file where the violation occurred.
+  pub line_number: Option<u64>,
+  /// The column number in the source file where the violation occurred.
+  pub column_number: Option<u64>,
+  /// A sample of the blocked resource, usually the first 40 characters of an inline script.
+  pub sample: Option<String>,
+  /// The HTTP status code of the document in which the violation occurred.
+  pub status_code: Option<u16>,
+}
+
+/// The `application/csp-report` body sent to `report-uri` endpoints.
+#[derive(Deserialize)]
+struct LegacyReport {
+  #[serde(rename = "csp-report")]
+  csp_report: LegacyReportBody,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct LegacyReportBody {
+  document_uri: Option<String>,
+  referrer: Option<String>,
+  blocked_uri: Option<String>,
+  effective_directive: Option<String>,
+  violated_directive: Option<String>,
+  original_policy: Option<String>,
+  disposition: Option<String>,
+  source_file: Option<String>,
+  line_number: Option<u64>,
+  column_number: Option<u64>,
+  script_sample: Option<String>,
+  status_code: Option<u16>,
+}
+
+impl From<LegacyReportBody> for CspViolationReport {
+  fn from(body: LegacyReportBody) -> Self {
+    Self {
+      document_url: body.document_uri,
+      referrer: body.referrer,
+      blocked_url: body.blocked_uri,
+      effective_directive: body.effective_directive.or(body.violated_directive),
+      original_policy: body.original_policy,
+      disposition: body.disposition,
+      source_file: body.source_file,
+      line_number: body.line_number,
+      column_number: body.column_number,
+      sample: body.script_sample,
+      status_code: body.status_code,
+    }
+  }
+}
+
+/// An entry of the `application/reports+json` body sent to `report-to` endpoints.
+#[derive(Deserialize)]
+struct Report {
+  #[serde(rename = "type")]
+  kind: String,
+  body: ReportBody,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportBody {
+  #[serde(rename = "documentURL")]
+  document_url: Option<String>,
+  referrer: Option<String>,
+  #[serde(rename = "blockedURL")]
+  blocked_url: Option<String>,
+  effective_directive: Option<String>,
+  original_policy: Option<String>,
+  disposition: Option<String>,
+  source_file: Option<String>,
+  line_number: Option<u64>,
+  column_number: Option<u64>,
+  sample: Option<String>,
+  status_code: Option<u16>,
+}
+
+impl From<ReportBody> for CspViolationReport {
+  fn from(body: ReportBody) -> Self {
+    Self {
+      document_url: body.document_url,
+      referrer: body.referrer,
+      blocked_url: body.blocked_url,
+      effective_directive: body.effective_directive,
+      original_policy: body.original_policy,
+      disposition: body.disposition,
+      source_file: body.source_file,
+      line_number: body.line_number,
+      column_number: body.column_number,
+      sample: body.sample,
+      status_code: body.status_code,
+    }
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ReportPayload {
+  Legacy(LegacyReport),
+  Reports(Vec<Report>),
+}
+
+impl CspViolationReport {
+  /// Parses the body of a request made to the CSP report endpoint.
+  ///
+  /// Supports both the `application/csp-report` and `application/reports+json` formats.
+  pub(crate) fn parse(body: &[u8]) -> serde_json::Result<Vec<Self>> {
+    Ok(match serde_json::from_slice(body)? {
+      ReportPayload::Legacy(report) => vec![report.csp_report.into()],
+      ReportPayload::Reports(reports) => reports
+        .into_iter()
+        .filter(|r| r.kind == "csp-violation")
+        .map(|r| r.body.into())
+        .collect(),
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::CspViolationReport;
+
+  #[test]
+  fn parse_legacy_report() {
+    let reports = CspViolationReport::parse(
+      br#"{
+        "csp-report": {
+          "document-uri": "tauri://localhost/",
+          "referrer": "",
+          "violated-directive": "script-src-elem",
+          "effective-directive": "script-src-elem",
+          "original-policy": "default-src 'self'",
+          "disposition": "enforce",
+          "blocked-uri": "https://evil.com/script.js",
+          "status-code": 200
+        }
+      }"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+      reports,
+      vec![CspViolationReport {
+        document_url: Some("tauri://localhost/".into()),
+        referrer: Some("".into()),
+        blocked_url: Some("https://evil.com/script.js".into()),
+        effective_directive: Some("script-src-elem".into()),
+        original_policy: Some("default-src 'self'".into()),
+        disposition: Some("enforce".into()),
+        status_code: Some(200),
+        ..Default::default()
+      }]
+    );
+  }
+
+  #[test]
+  fn parse_reporting_api_report() {
+    let reports = CspViolationReport::parse(
+      br#"[
+        {
+          "type": "csp-violation",
+          "age": 10,
+          "url": "http://tauri.localhost/",
+          "body": {
+            "documentURL": "http://tauri.localhost/",
+            "blockedURL": "inline",
+            "effectiveDirective": "style-src-attr",
+            "originalPolicy": "default-src 'self'",
+            "sample": "color: red",
+            "disposition": "enforce",
+            "lineNumber": 12,
+            "columnNumber": 4
+          }
+        },
+        {
+          "type": "deprecation",
+          "url": "http://tauri.localhost/",
+          "body": {}
+        }
+      ]"#,
+    )
+    .unwrap();
+
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].blocked_url.as_deref(), Some("inline"));
+    assert_eq!(reports[0].line_number, Some(12));
+    assert_eq!(reports[0].sample.as_deref(), Some("color: red"));
+  }
+}
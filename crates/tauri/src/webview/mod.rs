@@ -4,9 +4,11 @@
 
 //! The Tauri webview types and functions.
 
+pub(crate) mod csp;
 pub(crate) mod plugin;
 mod webview_window;
 
+pub use csp::CspViolationReport;
 pub use webview_window::{WebviewWindow, WebviewWindowBuilder};
 
 /// Cookie crate used for [`Webview::set_cookie`] and [`Webview::delete_cookie`].
@@ -44,8 +46,9 @@ use crate::{
   manager::AppManager,
   path::SafePathBuf,
   sealed::{ManagerBase, RuntimeOrDispatch},
+  state::ScopedStateManager,
   AppHandle, Emitter, Event, EventId, EventLoopMessage, EventName, Listener, Manager,
-  ResourceTable, Runtime, Window,
+  ResourceTable, Runtime, ScopedState, Window,
 };
 
 use std::{
@@ -1285,6 +1288,7 @@ pub struct Webview<R: Runtime> {
   pub(crate) manager: Arc<AppManager<R>>,
   pub(crate) app_handle: AppHandle<R>,
   pub(crate) resources_table: Arc<Mutex<ResourceTable>>,
+  pub(crate) scoped_state: Arc<ScopedStateManager>,
   use_https_scheme: bool,
 }
 
@@ -1306,6 +1310,7 @@ impl<R: Runtime> Clone for Webview<R> {
       manager: self.manager.clone(),
       app_handle: self.app_handle.clone(),
       resources_table: self.resources_table.clone(),
+      scoped_state: self.scoped_state.clone(),
       use_https_scheme: self.use_https_scheme,
     }
   }
@@ -1340,6 +1345,7 @@ impl<R: Runtime> Webview<R> {
       window: Arc::new(Mutex::new(window)),
       webview,
       resources_table: Default::default(),
+      scoped_state: Arc::new(ScopedStateManager::new()),
       use_https_scheme,
     }
   }
@@ -1367,6 +1373,58 @@ impl<R: Runtime> Webview<R> {
     &self.webview.label
   }
 
+  /// Adds `state` to the state scoped to this webview.
+  ///
+  /// Unlike [`Manager::manage`], which stores state for the whole application, this state is
+  /// scoped to this webview and is dropped when it is destroyed, or once the last [`ScopedState`] guard is dropped if any is still held.
+  ///
+  /// Returns `true` if the state was added and `false` if a state of type `T` was already managed
+  /// or the webview was already destroyed.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use std::sync::Mutex;
+  /// use tauri::{Manager, WebviewState};
+  ///
+  /// #[derive(Default)]
+  /// struct UndoStack(Mutex<Vec<String>>);
+  ///
+  /// #[tauri::command]
+  /// fn push(action: String, stack: WebviewState<UndoStack>) {
+  ///   stack.0.lock().unwrap().push(action);
+  /// }
+  ///
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     for webview_window in app.webview_windows().values() {
+  ///       webview_window.as_ref().manage_scoped(UndoStack::default());
+  ///     }
+  ///     Ok(())
+  ///   })
+  ///   .invoke_handler(tauri::generate_handler![push]);
+  /// ```
+  pub fn manage_scoped<T: Send + Sync + 'static>(&self, state: T) -> bool {
+    self.scoped_state.set(state)
+  }
+
+  /// Retrieves the state of type `T` scoped to this webview.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the state for the type `T` has not been previously [managed](Self::manage_scoped).
+  /// Use [try_scoped_state](Self::try_scoped_state) for a non-panicking version.
+  pub fn scoped_state<T: Send + Sync + 'static>(&self) -> ScopedState<T> {
+    self.scoped_state.get()
+  }
+
+  /// Attempts to retrieve the state of type `T` scoped to this webview.
+  ///
+  /// Returns `Some` if the state has previously been [managed](Self::manage_scoped). Otherwise returns `None`.
+  pub fn try_scoped_state<T: Send + Sync + 'static>(&self) -> Option<ScopedState<T>> {
+    self.scoped_state.try_get()
+  }
+
   /// Whether the webview was configured to use the HTTPS scheme or not.
   pub(crate) fn use_https_scheme(&self) -> bool {
     self.use_https_scheme
@@ -2313,6 +2371,120 @@ mod tests {
     crate::test_utils::assert_sync::<super::Webview>();
   }
 
+  #[test]
+  fn scoped_state() {
+    use crate::test::{mock_builder, mock_context, noop_assets};
+
+    let app = mock_builder().build(mock_context(noop_assets())).unwrap();
+    let first = crate::WebviewWindowBuilder::new(&app, "first", Default::default())
+      .build()
+      .unwrap();
+    let second = crate::WebviewWindowBuilder::new(&app, "second", Default::default())
+      .build()
+      .unwrap();
+
+    assert!(first.as_ref().manage_scoped(1u32));
+    assert!(!first.as_ref().manage_scoped(2u32));
+    assert_eq!(*first.as_ref().scoped_state::<u32>(), 1);
+    assert!(second.as_ref().try_scoped_state::<u32>().is_none());
+
+    // state is shared between handles of the same webview
+    let webview = first.as_ref().clone();
+    assert_eq!(*webview.scoped_state::<u32>(), 1);
+
+    // window and webview state are independent
+    assert!(first.as_ref().window().try_scoped_state::<u32>().is_none());
+  }
+
+  #[test]
+  fn scoped_state_dropped_on_destroy() {
+    use crate::test::{mock_builder, mock_context, noop_assets};
+    use std::sync::{
+      atomic::{AtomicBool, Ordering},
+      Arc,
+    };
+
+    struct Flag(Arc<AtomicBool>);
+
+    impl Drop for Flag {
+      fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+      }
+    }
+
+    let mut app = mock_builder().build(mock_context(noop_assets())).unwrap();
+    // the app exits once its last window is destroyed
+    let _main = crate::WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    let other = crate::WebviewWindowBuilder::new(&app, "other", Default::default())
+      .build()
+      .unwrap();
+
+    let webview_dropped = Arc::new(AtomicBool::new(false));
+    let window_dropped = Arc::new(AtomicBool::new(false));
+    assert!(other.as_ref().manage_scoped(Flag(webview_dropped.clone())));
+    assert!(other
+      .as_ref()
+      .window()
+      .manage_scoped(Flag(window_dropped.clone())));
+
+    app.run_iteration(|_, _| {});
+    other.destroy().unwrap();
+    app.run_iteration(|_, _| {});
+
+    // the handle is still alive but its state was dropped
+    assert!(webview_dropped.load(Ordering::SeqCst));
+    assert!(window_dropped.load(Ordering::SeqCst));
+    assert!(other.as_ref().try_scoped_state::<Flag>().is_none());
+    assert!(!other.as_ref().manage_scoped(Flag(Default::default())));
+  }
+
+  #[test]
+  fn resources_closed_on_destroy() {
+    use crate::{
+      test::{mock_builder, mock_context, noop_assets},
+      Manager, Resource,
+    };
+    use std::sync::{
+      atomic::{AtomicUsize, Ordering},
+      Arc,
+    };
+
+    struct Handle(Arc<AtomicUsize>);
+
+    impl Resource for Handle {
+      fn close(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+      }
+    }
+
+    let mut app = mock_builder().build(mock_context(noop_assets())).unwrap();
+    // the app exits once its last window is destroyed
+    let _main = crate::WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    let other = crate::WebviewWindowBuilder::new(&app, "other", Default::default())
+      .build()
+      .unwrap();
+
+    let closed = Arc::new(AtomicUsize::new(0));
+    other.as_ref().resources_table().add(Handle(closed.clone()));
+    other
+      .as_ref()
+      .window()
+      .resources_table()
+      .add(Handle(closed.clone()));
+
+    app.run_iteration(|_, _| {});
+    other.destroy().unwrap();
+    app.run_iteration(|_, _| {});
+
+    assert_eq!(closed.load(Ordering::SeqCst), 2);
+    let stats = other.as_ref().resources_table().stats();
+    assert_eq!((stats.live, stats.released), (0, 1));
+  }
+
   #[cfg(target_os = "macos")]
   #[test]
   fn test_webview_window_has_set_simple_fullscreen_method() {
@@ -26,10 +26,11 @@ use crate::{
     RuntimeHandle, WindowDispatch,
   },
   sealed::{ManagerBase, RuntimeOrDispatch},
+  state::ScopedStateManager,
   utils::config::{WindowConfig, WindowEffectsConfig},
   webview::WebviewBuilder,
-  Emitter, EventLoopMessage, EventName, Listener, Manager, ResourceTable, Runtime, Theme, Webview,
-  WindowEvent,
+  Emitter, EventLoopMessage, EventName, Listener, Manager, ResourceTable, Runtime, ScopedState,
+  Theme, Webview, WindowEvent,
 };
 #[cfg(desktop)]
 use crate::{
@@ -928,6 +929,7 @@ pub struct Window<R: Runtime> {
   #[cfg(desktop)]
   pub(crate) menu: Arc<Mutex<Option<WindowMenu<R>>>>,
   pub(crate) resources_table: Arc<Mutex<ResourceTable>>,
+  pub(crate) scoped_state: Arc<ScopedStateManager>,
 }
 
 impl<R: Runtime> std::fmt::Debug for Window<R> {
@@ -965,6 +967,7 @@ impl<R: Runtime> Clone for Window<R> {
       #[cfg(desktop)]
       menu: self.menu.clone(),
       resources_table: self.resources_table.clone(),
+      scoped_state: self.scoped_state.clone(),
     }
   }
 }
@@ -1034,6 +1037,7 @@ impl<R: Runtime> Window<R> {
       #[cfg(desktop)]
       menu: Arc::new(std::sync::Mutex::new(menu)),
       resources_table: Default::default(),
+      scoped_state: Arc::new(ScopedStateManager::new()),
     }
   }
 
@@ -1098,6 +1102,58 @@ impl<R: Runtime> Window<R> {
     &self.window.label
   }
 
+  /// Adds `state` to the state scoped to this window.
+  ///
+  /// Unlike [`Manager::manage`], which stores state for the whole application, this state is
+  /// scoped to this window and is dropped when it is destroyed, or once the last [`ScopedState`] guard is dropped if any is still held.
+  ///
+  /// Returns `true` if the state was added and `false` if a state of type `T` was already managed
+  /// or the window was already destroyed.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use std::sync::Mutex;
+  /// use tauri::{Manager, Window};
+  ///
+  /// #[derive(Default)]
+  /// struct Document(Mutex<String>);
+  ///
+  /// #[tauri::command]
+  /// fn edit(contents: String, window: Window) {
+  ///   *window.scoped_state::<Document>().0.lock().unwrap() = contents;
+  /// }
+  ///
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     for webview_window in app.webview_windows().values() {
+  ///       webview_window.as_ref().window().manage_scoped(Document::default());
+  ///     }
+  ///     Ok(())
+  ///   })
+  ///   .invoke_handler(tauri::generate_handler![edit]);
+  /// ```
+  pub fn manage_scoped<T: Send + Sync + 'static>(&self, state: T) -> bool {
+    self.scoped_state.set(state)
+  }
+
+  /// Retrieves the state of type `T` scoped to this window.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the state for the type `T` has not been previously [managed](Self::manage_scoped).
+  /// Use [try_scoped_state](Self::try_scoped_state) for a non-panicking version.
+  pub fn scoped_state<T: Send + Sync + 'static>(&self) -> ScopedState<T> {
+    self.scoped_state.get()
+  }
+
+  /// Attempts to retrieve the state of type `T` scoped to this window.
+  ///
+  /// Returns `Some` if the state has previously been [managed](Self::manage_scoped). Otherwise returns `None`.
+  pub fn try_scoped_state<T: Send + Sync + 'static>(&self) -> Option<ScopedState<T>> {
+    self.scoped_state.try_get()
+  }
+
   /// Registers a window event listener.
   pub fn on_window_event<F: Fn(&WindowEvent) + Send + 'static>(&self, f: F) {
     self
@@ -31,6 +31,7 @@ import * as image from './image'
 import * as menu from './menu'
 import * as mocks from './mocks'
 import * as path from './path'
+import * as state from './state'
 import * as tray from './tray'
 import * as webview from './webview'
 import * as webviewWindow from './webviewWindow'
@@ -45,6 +46,7 @@ export {
   menu,
   mocks,
   path,
+  state,
   tray,
   webview,
   webviewWindow,
@@ -0,0 +1,269 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+/**
+ * Read and subscribe to state managed on the Rust side with `Manager::manage_synced`.
+ *
+ * Access is controlled by the `core:state` permissions: reading is enabled by default,
+ * writing requires the `core:state:allow-update` permission.
+ * The permissions must be scoped to the state names, e.g. `{ "identifier": "core:state:default", "allow": [{ "values": ["counter"] }] }`,
+ * a state that is not allowed by the scope cannot be accessed.
+ *
+ * This package is also accessible with `window.__TAURI__.state` when [`app.withGlobalTauri`](https://v2.tauri.app/reference/config/#withglobaltauri) in `tauri.conf.json` is set to `true`.
+ * @module
+ */
+
+import { Channel, invoke } from './core'
+
+/**
+ * A [JSON Patch](https://datatracker.ietf.org/doc/html/rfc6902) operation.
+ *
+ * @since 2.11.0
+ */
+type JsonPatchOperation =
+  | { op: 'add' | 'replace' | 'test'; path: string; value: unknown }
+  | { op: 'remove'; path: string }
+  | { op: 'move' | 'copy'; from: string; path: string }
+
+interface Snapshot<T> {
+  version: number
+  value: T
+}
+
+interface Change {
+  version: number
+  patch: JsonPatchOperation[]
+}
+
+function parsePointer(pointer: string): string[] {
+  if (pointer === '') {
+    return []
+  }
+  return pointer
+    .slice(1)
+    .split('/')
+    .map((token) => token.replace(/~1/g, '/').replace(/~0/g, '~'))
+}
+
+function getAt(doc: unknown, tokens: string[]): unknown {
+  return tokens.reduce<unknown>(
+    (node, token) => (node as Record<string, unknown>)[token],
+    doc
+  )
+}
+
+function setAt(
+  doc: unknown,
+  tokens: string[],
+  value: unknown,
+  insert: boolean
+): unknown {
+  if (tokens.length === 0) {
+    return value
+  }
+  const parent = getAt(doc, tokens.slice(0, -1))
+  const key = tokens[tokens.length - 1]
+  if (Array.isArray(parent)) {
+    const index = key === '-' ? parent.length : Number(key)
+    parent.splice(index, insert ? 0 : 1, value)
+  } else {
+    ;(parent as Record<string, unknown>)[key] = value
+  }
+  return doc
+}
+
+function removeAt(doc: unknown, tokens: string[]): unknown {
+  const parent = getAt(doc, tokens.slice(0, -1))
+  const key = tokens[tokens.length - 1]
+  const value = (parent as Record<string, unknown>)[key]
+  if (Array.isArray(parent)) {
+    parent.splice(Number(key), 1)
+  } else {
+    delete (parent as Record<string, unknown>)[key]
+  }
+  return value
+}
+
+/**
+ * Applies a JSON Patch to a deep copy of the given document.
+ *
+ * @ignore
+ */
+function applyPatch<T>(doc: T, patch: JsonPatchOperation[]): T {
+  let result: unknown = structuredClone(doc)
+  for (const operation of patch) {
+    const tokens = parsePointer(operation.path)
+    switch (operation.op) {
+      case 'add':
+        result = setAt(result, tokens, operation.value, true)
+        break
+      case 'replace':
+        result = setAt(result, tokens, operation.value, false)
+        break
+      case 'remove':
+        removeAt(result, tokens)
+        break
+      case 'move': {
+        const value = removeAt(result, parsePointer(operation.from))
+        result = setAt(result, tokens, value, true)
+        break
+      }
+      case 'copy': {
+        const value = structuredClone(
+          getAt(result, parsePointer(operation.from))
+        )
+        result = setAt(result, tokens, value, true)
+        break
+      }
+      case 'test':
+        if (
+          JSON.stringify(getAt(result, tokens))
+          !== JSON.stringify(operation.value)
+        ) {
+          throw new Error(`JSON Patch test failed at ${operation.path}`)
+        }
+        break
+    }
+  }
+  return result as T
+}
+
+/**
+ * A state managed on the Rust side with `Manager::manage_synced`, kept in sync with its Rust value.
+ *
+ * @example
+ * ```typescript
+ * import { SyncedState } from '@tauri-apps/api/state'
+ * const settings = await SyncedState.subscribe<{ theme: string }>('settings', (value) => {
+ *   document.body.dataset.theme = value.theme
+ * })
+ * // requires the `core:state:allow-update` permission
+ * await settings.update([{ op: 'replace', path: '/theme', value: 'light' }])
+ * ```
+ *
+ * @since 2.11.0
+ */
+class SyncedState<T = unknown> {
+  /** The state name. */
+  readonly name: string
+  #value: T
+  #version: number
+  #channel: Channel<Change>
+  #onChange?: (value: T) => void
+
+  private constructor(
+    name: string,
+    snapshot: Snapshot<T>,
+    channel: Channel<Change>,
+    onChange?: (value: T) => void
+  ) {
+    this.name = name
+    this.#value = snapshot.value
+    this.#version = snapshot.version
+    this.#channel = channel
+    this.#onChange = onChange
+  }
+
+  /**
+   * Reads a snapshot of the state without subscribing to it.
+   */
+  static async get<T>(name: string): Promise<T> {
+    const snapshot = await invoke<Snapshot<T>>('plugin:state|get', { name })
+    return snapshot.value
+  }
+
+  /**
+   * Subscribes to the state, calling `onChange` with the new value whenever it changes.
+   */
+  static async subscribe<T>(
+    name: string,
+    onChange?: (value: T) => void
+  ): Promise<SyncedState<T>> {
+    // changes may arrive before the snapshot, so they are queued until it does
+    let state: SyncedState<T> | undefined
+    const pending: Change[] = []
+    const channel = new Channel<Change>((change) => {
+      if (state) {
+        void state.#apply(change)
+      } else {
+        pending.push(change)
+      }
+    })
+
+    const snapshot = await invoke<Snapshot<T>>('plugin:state|subscribe', {
+      name,
+      onChange: channel
+    })
+    state = new SyncedState(name, snapshot, channel, onChange)
+    for (const change of pending) {
+      await state.#apply(change)
+    }
+    return state
+  }
+
+  /** The current value. */
+  get value(): T {
+    return this.#value
+  }
+
+  /** The current version, incremented on every change. */
+  get version(): number {
+    return this.#version
+  }
+
+  /**
+   * Applies a JSON Patch to the state.
+   *
+   * The update is rejected if the state changed since the current {@linkcode SyncedState.version}.
+   *
+   * Requires the `core:state:allow-update` permission.
+   */
+  async update(patch: JsonPatchOperation[]): Promise<void> {
+    await invoke<number>('plugin:state|update', {
+      name: this.name,
+      patch,
+      version: this.#version
+    })
+  }
+
+  /**
+   * Replaces the whole value of the state.
+   *
+   * Requires the `core:state:allow-update` permission.
+   */
+  async set(value: T): Promise<void> {
+    await this.update([{ op: 'replace', path: '', value }])
+  }
+
+  /** Stops receiving changes. */
+  async unsubscribe(): Promise<void> {
+    await invoke('plugin:state|unsubscribe', {
+      name: this.name,
+      id: this.#channel.id
+    })
+  }
+
+  async #apply(change: Change): Promise<void> {
+    if (change.version <= this.#version) {
+      return
+    }
+
+    if (change.version === this.#version + 1) {
+      this.#value = applyPatch(this.#value, change.patch)
+      this.#version = change.version
+    } else {
+      // a change was missed, resynchronize
+      const snapshot = await invoke<Snapshot<T>>('plugin:state|get', {
+        name: this.name
+      })
+      this.#value = snapshot.value
+      this.#version = snapshot.version
+    }
+
+    this.#onChange?.(this.#value)
+  }
+}
+
+export { SyncedState }
+export type { JsonPatchOperation }